@@ -14,6 +14,7 @@ import {
   Taxiway,
   Terminal,
   Vec2,
+  Wind,
   World,
 } from './lib/types';
 import {
@@ -34,6 +35,7 @@ import {
   shortLandingState,
   shortTaxiingState,
   toRadians,
+  trueToMagnetic,
 } from './lib/lib';
 import colors from './lib/colors';
 import { useAircraft, useWorld } from './lib/api';
@@ -363,6 +365,49 @@ export default function Canvas() {
     ctx.stroke();
   }
 
+  function drawWindsock(ctx: Ctx, wind: Wind) {
+    resetTransform(ctx);
+    let radius = 25;
+    let origin: Vec2 = [
+      canvas.width * 0.5 - radius - 40,
+      -canvas.height * 0.5 + radius + 40,
+    ];
+
+    ctx.strokeStyle = '#888';
+    ctx.lineWidth = 1;
+    ctx.beginPath();
+    ctx.arc(origin[0], origin[1], radius, 0, Math.PI * 2);
+    ctx.stroke();
+
+    // The sock streams downwind, away from where the wind blows from.
+    if (wind.speed > 0) {
+      let angle = toRadians(wind.heading + 90);
+      let dx = Math.cos(angle) * radius;
+      let dy = Math.sin(angle) * radius;
+
+      ctx.strokeStyle = colors.text_yellow;
+      ctx.lineWidth = 3;
+      ctx.beginPath();
+      ctx.moveTo(origin[0] - dx, origin[1] - dy);
+      ctx.lineTo(origin[0] + dx, origin[1] + dy);
+      ctx.stroke();
+    }
+
+    let heading = Math.round(
+      trueToMagnetic(wind.heading, world.data?.magnetic_variation ?? 0)
+    )
+      .toString()
+      .padStart(3, '0')
+      .replace('000', '360');
+    let text =
+      wind.speed > 0 ? `${heading}/${Math.round(wind.speed)}KT` : 'CALM';
+    ctx.fillStyle = '#888';
+    ctx.font = `${16}px monospace`;
+    ctx.textAlign = 'center';
+    ctx.textBaseline = 'top';
+    ctx.fillText(text, origin[0], origin[1] + radius + 8);
+  }
+
   function drawRunway(ctx: Ctx, runway: Runway) {
     resetTransform(ctx);
     let info = runwayInfo(runway);
@@ -637,7 +682,12 @@ export default function Canvas() {
     let headingInfo =
       aircraft.state.type === 'landing'
         ? shortLandingState(aircraft.state.value.state)
-        : Math.round(aircraft.heading)
+        : Math.round(
+            trueToMagnetic(
+              aircraft.heading,
+              world.data?.magnetic_variation ?? 0
+            )
+          )
             .toString()
             .padStart(3, '0')
             .replace('360', '000');
@@ -990,6 +1040,8 @@ export default function Canvas() {
     for (let aircraft of aircrafts.filter((a) => a.id == selectedAircraft())) {
       drawFlightPlanWaypoints(ctx, aircraft);
     }
+
+    drawWindsock(ctx, airspace.wind);
   }
 
   function drawGround(ctx: Ctx, world: World, aircrafts: Array<Aircraft>) {
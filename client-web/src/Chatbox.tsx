@@ -69,7 +69,7 @@ export default function Chatbox({
 
   createEffect(() => {
     for (const message of messages.data) {
-      if (message.created.secs > lastRead() && message.id !== 'ATC') {
+      if (message.created.secs > lastRead() && message.origin !== 'atc') {
         if (message.reply !== '') {
           speak(message);
         } else {
@@ -162,13 +162,15 @@ export default function Chatbox({
               classList={{
                 message: true,
                 selected: m.id === selectedAircraft(),
+                caution: m.severity === 'caution',
+                warning: m.severity === 'warning',
               }}
             >
               {showAll() ? <span class="frequency">{m.frequency}</span> : null}
               <span
                 classList={{
                   callsign: true,
-                  atc: m.id === 'ATC',
+                  atc: m.origin === 'atc',
                 }}
               >
                 {m.id}
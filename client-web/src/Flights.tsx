@@ -7,7 +7,7 @@ import {
   useFlights,
 } from './lib/api';
 import { formatTime } from './lib/lib';
-import { Flight } from './lib/types';
+import { Duration, Flight, FlightList } from './lib/types';
 import './Flights.scss';
 import { useQueryClient } from '@tanstack/solid-query';
 import { createMemo, createSignal, Show } from 'solid-js';
@@ -25,7 +25,13 @@ function formatKind(kind: string) {
   }
 }
 
-export function FlightItem({ flight }: { flight: Flight }) {
+export function FlightItem({
+  flight,
+  now,
+}: {
+  flight: Flight;
+  now: Duration;
+}) {
   const client = useQueryClient();
 
   async function handleDelete() {
@@ -35,9 +41,12 @@ export function FlightItem({ flight }: { flight: Flight }) {
 
     if (res.ok) {
       await client.cancelQueries({ queryKey: [getFlights] });
-      await client.setQueryData([getFlights], (old: Flight[]) => {
+      await client.setQueryData([getFlights], (old: FlightList) => {
         console.log({ old });
-        return old.filter((f) => f.id !== flight.id);
+        return {
+          ...old,
+          flights: old.flights.filter((f) => f.id !== flight.id),
+        };
       });
     }
   }
@@ -53,9 +62,11 @@ export function FlightItem({ flight }: { flight: Flight }) {
 
   const isSelected = createMemo(() => selected() === callsign);
 
-  let time = formatTime(flight.spawn_at.secs * 1000 - new Date().getTime());
+  // Flights are scheduled in game time, which runs with the time scale and
+  // stops while paused, so count down from the server's game time.
+  let time = formatTime((flight.spawn_at.secs - now.secs) * 1000);
   if (flight.status.type === 'ongoing') {
-    time = formatTime(new Date().getTime() - flight.spawn_at.secs * 1000);
+    time = formatTime((now.secs - flight.spawn_at.secs) * 1000);
   } else if (flight.status.type === 'completed') {
     time = formatTime(
       (flight.status.value[1].secs - flight.spawn_at.secs) * 1000
@@ -174,28 +185,28 @@ export default function Flights() {
           <hr />
           <h2>Scheduled</h2>
           <div class="list">
-            {query.data
+            {query.data.flights
               .filter((f) => f.status.type === 'scheduled')
               .map((f) => (
-                <FlightItem flight={f} />
+                <FlightItem flight={f} now={query.data.now} />
               ))}
           </div>
           <hr />
           <h2>Ongoing</h2>
           <div class="list">
-            {query.data
+            {query.data.flights
               .filter((f) => f.status.type === 'ongoing')
               .map((f) => (
-                <FlightItem flight={f} />
+                <FlightItem flight={f} now={query.data.now} />
               ))}
           </div>
           <hr />
           <h2>Completed</h2>
           <div class="list">
-            {query.data
+            {query.data.flights
               .filter((f) => f.status.type === 'completed')
               .map((f) => (
-                <FlightItem flight={f} />
+                <FlightItem flight={f} now={query.data.now} />
               ))}
           </div>
         </Show>
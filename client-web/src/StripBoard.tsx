@@ -9,6 +9,7 @@ import {
   formatTime,
   nauticalMilesToFeet,
   runwayInfo,
+  trueToMagnetic,
 } from './lib/lib';
 import { createQuery } from '@tanstack/solid-query';
 import { getAircraft, useWorld } from './lib/api';
@@ -236,6 +237,8 @@ function Strip({ strip }: StripProps) {
       topStatus = 'TXWY';
     } else if (current.kind === 'apron') {
       topStatus = 'APRN';
+    } else if (current.kind === 'helipad') {
+      topStatus = 'HELI';
     }
 
     bottomStatus = current.name;
@@ -279,7 +282,13 @@ function Strip({ strip }: StripProps) {
         }
       }
 
-      distanceText = `FOR ${heading.toString().slice(0, 2)}`;
+      // Runways are named for their magnetic heading.
+      const magnetic = Math.round(
+        trueToMagnetic(heading, query.data.magnetic_variation)
+      )
+        .toString()
+        .padStart(3, '0');
+      distanceText = `FOR ${magnetic.slice(0, 2)}`;
     }
   } else if (strip.state.type === 'parked' && !strip.state.value.active) {
     dimmer = true;
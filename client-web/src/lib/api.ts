@@ -1,6 +1,14 @@
 import { createQuery } from '@tanstack/solid-query';
 import { Accessor } from 'solid-js';
-import { Aircraft, Flight, Points, RadioMessage, World } from './types';
+import {
+  Aircraft,
+  Flight,
+  FlightList,
+  Points,
+  RadioMessage,
+  World,
+  newDuration,
+} from './types';
 import fastDeepEqual from 'fast-deep-equal';
 
 const defaultURL = `${window.location.protocol}//${window.location.hostname}:9001`;
@@ -51,14 +59,14 @@ export function useAircraft(renderRate: Accessor<number>) {
 // Flights
 export const getFlights = '/api/game/flights';
 export function useFlights() {
-  return createQuery<Array<Flight>>(() => ({
+  return createQuery<FlightList>(() => ({
     queryKey: [getFlights],
     queryFn: async () => {
       const result = await fetch(`${baseAPIPath}${getFlights}`);
-      if (!result.ok) return [];
+      if (!result.ok) return { flights: [], now: newDuration(0, 0) };
       return result.json();
     },
-    initialData: [],
+    initialData: { flights: [], now: newDuration(0, 0) },
     staleTime: 2000,
     refetchInterval: 2000,
     refetchOnMount: 'always',
@@ -73,7 +81,7 @@ export function useFlight(id: number) {
     queryFn: async () => {
       const flights = useFlights();
       if (!flights.data) return null;
-      return flights.data.find((f) => f.id === id) ?? null;
+      return flights.data.flights.find((f) => f.id === id) ?? null;
     },
     staleTime: 2000,
     refetchInterval: 2000,
@@ -90,7 +98,7 @@ export function useFlightByAircraft(id: string) {
       const flights = useFlights();
       if (!flights.data) return null;
       return (
-        flights.data.find(
+        flights.data.flights.find(
           (f) => f.status.type !== 'scheduled' && f.status.value === id
         ) ?? null
       );
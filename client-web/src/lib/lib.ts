@@ -56,6 +56,10 @@ export function inverseDegrees(degrees: number): number {
   return (degrees + 180) % 360;
 }
 
+export function trueToMagnetic(heading: number, variation: number): number {
+  return (heading - variation + 360) % 360;
+}
+
 export function runwayInfo(
   runway: Runway
   // scale: number
@@ -149,6 +153,12 @@ export function shortLandingState(state: LandingState): string {
       return 'LOC';
     case 'glideslope':
       return 'GLS';
+    case 'circling':
+      return 'CIR';
+    case 'touchdown':
+      return 'TDN';
+    case 'go-around':
+      return 'GA';
 
     default:
       return 'UKN';
@@ -4,7 +4,7 @@ export type TaxiWaypointBehavior = {
 
 export type NodeVec2 = {
   name: string;
-  kind: 'taxiway' | 'gate' | 'apron' | 'runway';
+  kind: 'taxiway' | 'gate' | 'apron' | 'runway' | 'helipad';
   behavior: 'goto' | 'holdshort' | 'park';
   value: Vec2;
 };
@@ -27,10 +27,22 @@ export type LandingState =
   | 'correcting'
   | 'localizer'
   | 'glideslope'
+  | 'circling'
   | 'touchdown'
   | 'go-around';
 
-export type TaxiingState = 'armed' | 'stopped' | 'override' | 'holding';
+export type TaxiingState =
+  | 'armed'
+  | 'stopped'
+  | 'override'
+  | 'holding'
+  | 'queued';
+
+export type TaxiStopReason =
+  | 'collision-ahead'
+  | 'hold-short'
+  | 'runway-occupied'
+  | 'player-hold';
 
 export type AircraftStateLanding = {
   type: 'landing';
@@ -63,7 +75,7 @@ export type AircraftState =
   | AircraftStateTaxiing
   | AircraftStateParked;
 
-type Duration = {
+export type Duration = {
   secs: number;
   nanos: number;
 };
@@ -72,6 +84,15 @@ export function newDuration(secs: number, nanos: number): Duration {
   return { secs, nanos };
 }
 
+export type AircraftKind =
+  | 'a21n'
+  | 'a333'
+  | 'b737'
+  | 'b747'
+  | 'b77l'
+  | 'crj7'
+  | 'e170';
+
 export type Aircraft = {
   id: string;
   is_colliding: boolean;
@@ -92,6 +113,8 @@ export type Aircraft = {
     heading: number;
     /** In Feet */
     altitude: number;
+    /** A Mach number flown instead of `speed` */
+    mach: number | null;
   };
   flight_plan: {
     departing: string;
@@ -99,9 +122,66 @@ export type Aircraft = {
 
     speed: number;
     altitude: number;
+
+    route: string;
+    distances: number[];
   };
 
   frequency: number;
+  queue_position: number | null;
+  stop_reason: TaxiStopReason | null;
+  expected_runway: string | null;
+  circle_to_land: string | null;
+  frozen: boolean;
+  ifr_cleared: boolean;
+  vectored_from: Array<NodeVOR>;
+  lined_up_until: Duration | null;
+  kind: AircraftKind;
+  fuel: number;
+  approach_mode: 'pilot-nav' | 'vectors';
+  nav_mode: 'heading' | 'lnav';
+  hold: Hold | null;
+  squawk: number | null;
+};
+
+export type Hold = {
+  fix: string;
+  pos: Vec2;
+  /** In Degrees */
+  inbound_course: number;
+  direction: 'right' | 'left';
+  /** In Seconds */
+  leg_seconds: number;
+  leg: 'entry' | 'outbound' | 'inbound';
+  elapsed: number;
+};
+
+export type FlightSegment =
+  | 'parked'
+  | 'taxi'
+  | 'takeoff'
+  | 'departure'
+  | 'enroute'
+  | 'approach'
+  | 'landing';
+
+export type FlightStrip = {
+  callsign: string;
+  kind: AircraftKind;
+  departing: string;
+  arriving: string;
+
+  /** In Feet */
+  assigned_altitude: number;
+  /** In Feet */
+  cruise_altitude: number;
+  squawk: number | null;
+  route: string;
+
+  segment: FlightSegment;
+  frequency: number;
+  /** From 0 to 1 */
+  progress: number;
 };
 
 export function isAircraftFlying(
@@ -149,6 +229,12 @@ export type Gate = {
   id: string;
   pos: Vec2;
   heading: number;
+  terminal: string | null;
+};
+
+export type Helipad = {
+  id: string;
+  pos: Vec2;
 };
 
 export type Terminal = {
@@ -161,6 +247,7 @@ export type Terminal = {
   apron: [Vec2, Vec2];
 
   gates: Array<Gate>;
+  helipads: Array<Helipad>;
 };
 
 export type Airport = {
@@ -169,6 +256,14 @@ export type Airport = {
   runways: Array<Runway>;
   taxiways: Array<Taxiway>;
   terminals: Array<Terminal>;
+  /** In seconds */
+  min_departure_interval: number;
+  procedures: Array<Procedure>;
+};
+
+export type Procedure = {
+  id: string;
+  fixes: Array<string>;
 };
 
 export type Frequencies = {
@@ -185,6 +280,14 @@ export type Airspace = {
   radius: number;
   airports: Array<Airport>;
   frequencies: Frequencies;
+  wind: Wind;
+};
+
+export type Wind = {
+  /** The direction the wind is blowing from, in Degrees */
+  heading: number;
+  /** In Knots */
+  speed: number;
 };
 
 export function DefaultAirspace(): Airspace {
@@ -200,6 +303,7 @@ export function DefaultAirspace(): Airspace {
       ground: 118.5,
       center: 118.5,
     },
+    wind: { heading: 0, speed: 0 },
   };
 }
 
@@ -213,12 +317,14 @@ export type Connection = {
 export type World = {
   airspace: Airspace;
   connections: Array<Connection>;
+  magnetic_variation: number;
 };
 
 export function DefaultWorld(): World {
   return {
     airspace: DefaultAirspace(),
     connections: [],
+    magnetic_variation: 0,
   };
 }
 
@@ -227,6 +333,8 @@ export type RadioMessage = {
   frequency: number;
   reply: string;
   created: Duration;
+  origin: 'atc' | 'pilot' | 'system';
+  severity: 'info' | 'caution' | 'warning';
 };
 
 export type Game = {
@@ -244,6 +352,15 @@ export type Points = {
     rate: Duration;
     marks: Duration[];
   };
+  score: Score | null;
+};
+
+export type Score = {
+  total: number;
+  completed_flights: number;
+  separation_losses: number;
+  go_arounds: number;
+  ground_delay: number;
 };
 
 export type Flight = {
@@ -255,3 +372,8 @@ export type Flight = {
     | { type: 'ongoing'; value: string }
     | { type: 'completed'; value: [string, Duration] };
 };
+
+export type FlightList = {
+  flights: Array<Flight>;
+  now: Duration;
+};
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use turborand::{rng::Rng, TurboRand};
+
+use crate::entities::aircraft::AircraftKind;
+
+/// An airline that aircraft fly for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Airline {
+  /// The three letter ICAO code that starts its callsigns.
+  pub icao: String,
+  /// How its callsigns are spoken, such as "Speedbird" for BAW.
+  pub telephony: String,
+  /// The types of aircraft it flies.
+  #[serde(default)]
+  pub fleet: Vec<AircraftKind>,
+}
+
+impl Airline {
+  pub fn new(icao: &str, telephony: &str) -> Self {
+    Self {
+      icao: icao.to_string(),
+      telephony: telephony.to_string(),
+      fleet: Vec::new(),
+    }
+  }
+}
+
+/// The airlines that callsigns are generated for and decoded with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Airlines(pub Vec<Airline>);
+
+impl Default for Airlines {
+  fn default() -> Self {
+    Self(vec![
+      Airline::new("AAL", "American Airlines"),
+      Airline::new("SKW", "Skywest"),
+      Airline::new("JBU", "JetBlue"),
+    ])
+  }
+}
+
+impl Airlines {
+  pub fn find(&self, icao: &str) -> Option<&Airline> {
+    self.0.iter().find(|a| a.icao == icao)
+  }
+
+  /// A callsign of a random airline and a four digit flight number.
+  pub fn random_callsign(&self, rng: &mut Rng) -> String {
+    let mut string = String::new();
+    if let Some(airline) = rng.sample(&self.0) {
+      string.push_str(&airline.icao);
+    }
+
+    for _ in 0..4 {
+      string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
+    }
+
+    string
+  }
+
+  /// A kind of aircraft for `callsign` to fly, out of the fleet of its
+  /// airline, or any kind if its airline has no fleet.
+  pub fn random_kind(&self, callsign: &str, rng: &mut Rng) -> AircraftKind {
+    let icao = callsign.chars().take(3).collect::<String>();
+    self
+      .find(&icao)
+      .and_then(|a| rng.sample(&a.fleet))
+      .cloned()
+      .unwrap_or_else(|| AircraftKind::random(rng))
+  }
+
+  /// How `callsign` is spoken, such as "American Airlines 1234" for AAL1234.
+  pub fn telephony(&self, callsign: &str) -> String {
+    let icao = callsign.chars().take(3).collect::<String>();
+    let number = callsign.chars().skip(3).collect::<String>();
+
+    let telephony = self
+      .find(&icao)
+      .map(|a| a.telephony.as_str())
+      .unwrap_or("Unknown");
+
+    format!("{telephony} {number}")
+  }
+}
@@ -0,0 +1,51 @@
+use core::fmt;
+use std::{
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use crate::duration_now;
+
+/// A source of wall-clock time, measured since the UNIX epoch.
+pub trait Clock: fmt::Debug + Send + Sync {
+  fn now(&self) -> Duration;
+}
+
+/// A [`Clock`] backed by the system time.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> Duration {
+    duration_now()
+  }
+}
+
+/// A [`Clock`] that only moves when told to. Clones share the same time, so
+/// a test can keep a handle and advance the clock used by the runner.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+  now: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+  pub fn new(now: Duration) -> Self {
+    Self {
+      now: Arc::new(Mutex::new(now)),
+    }
+  }
+
+  pub fn set(&self, now: Duration) {
+    *self.now.lock().unwrap() = now;
+  }
+
+  pub fn advance(&self, by: Duration) {
+    *self.now.lock().unwrap() += by;
+  }
+}
+
+impl Clock for MockClock {
+  fn now(&self) -> Duration {
+    *self.now.lock().unwrap()
+  }
+}
@@ -4,7 +4,7 @@ use std::time::Duration;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
 
-use crate::{abbreviate_altitude, duration_now, pathfinder::Node};
+use crate::{abbreviate_altitude, airline::Airlines, pathfinder::Node};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -16,20 +16,95 @@ pub enum TaskWaypoint {
   Destination,
 }
 
+/// Which way we turn at the fix to fly a holding pattern.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldDirection {
+  #[default]
+  Right,
+  Left,
+}
+
+/// How long the outbound legs of a hold are, in seconds, unless we are told.
+pub const DEFAULT_HOLD_LEG_SECONDS: f32 = 60.0;
+
+fn default_hold_leg_seconds() -> f32 {
+  DEFAULT_HOLD_LEG_SECONDS
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", content = "value")]
 pub enum Task {
   Altitude(f32),
+  AltitudeAtDiscretion(f32),
+  AutoTune,
+  /// Climbs to an altitude, which is refused if it is below us.
+  Climb(f32),
+  CircleToLand {
+    approach_runway: Intern<String>,
+    land_runway: Intern<String>,
+  },
+  /// Descends to an altitude, which is refused if it is above us.
+  Descend(f32),
+  /// Flies direct to a fix, or to a point `distance` nautical miles out on
+  /// its (magnetic) `radial`, such as "KLAX/270/15".
+  Direct {
+    fix: Intern<String>,
+    #[serde(default)]
+    radial: Option<f32>,
+    #[serde(default)]
+    distance: Option<f32>,
+  },
+  /// Flies direct to a fix, and starts the approach to a runway from it.
+  DirectApproach {
+    fix: Intern<String>,
+    runway: Intern<String>,
+  },
+  DirectNearest,
+  ExpectRunway(Intern<String>),
+  /// Holds our heading inside the airspace instead of flying our route,
+  /// until we are vectored or told to resume it.
+  ExpectVectors,
   Frequency(f32),
   GoAround,
   Heading(f32),
+  /// Holds over a fix, turning `direction` at it, with outbound legs of
+  /// `leg_seconds`. We leave the hold when told to resume our route or
+  /// given a heading.
+  #[serde(alias = "hp")]
+  Hold {
+    fix: Intern<String>,
+    #[serde(default)]
+    direction: HoldDirection,
+    #[serde(default = "default_hold_leg_seconds")]
+    leg_seconds: f32,
+  },
   Ident,
+  /// Gives a parked aircraft its IFR clearance, with its transponder code.
+  IssueClearance(u16),
   Land(Intern<String>),
+  /// A speed as a Mach number, such as 0.78, which is flown at altitude.
+  Mach(f32),
+  MaintainPresentHeading,
+  Monitor(String),
   NamedFrequency(String),
+  /// Flies the named SID or STAR of one of our airports.
+  Procedure(Intern<String>),
   #[serde(rename = "resume")]
   ResumeOwnNavigation,
+  /// Files a route, such as "KSFO SID KSJC J80 KLAX", and flies the fixes in
+  /// it that we know of.
+  SetRoute(String),
+  /// Rejoins the route we were vectored off of.
+  ResumeArrival,
+  SayAgain,
   Speed(f32),
+  SpeedAtDiscretion(f32),
+  Squawk(u16),
+  SquawkIdent(u16),
 
   Taxi(Vec<Node<()>>),
   TaxiContinue,
@@ -38,6 +113,49 @@ pub enum Task {
   LineUp(Intern<String>),
 
   Delete,
+  /// Moves an aircraft to a new position and state of flight. Only available
+  /// in debug builds, for setting up scenarios.
+  #[cfg(debug_assertions)]
+  Reposition {
+    pos: glam::Vec2,
+    altitude: f32,
+    heading: f32,
+    speed: f32,
+  },
+}
+
+impl Task {
+  /// Checks that the arguments of a task are in range. Headings are given
+  /// between 0 and 360, so anything else was likely misheard.
+  pub fn validate(&self) -> Result<(), String> {
+    match self {
+      Task::Heading(heading) if !(0.0..=360.0).contains(heading) => {
+        Err(format!("heading {heading} is not between 0 and 360"))
+      }
+      Task::Hold { leg_seconds, .. } if *leg_seconds <= 0.0 => Err(format!(
+        "hold legs of {leg_seconds} seconds are not positive"
+      )),
+      Task::Mach(mach) if !(*mach > 0.0 && *mach < 1.0) => {
+        Err(format!("mach {mach} is not between 0 and 1"))
+      }
+      Task::Direct {
+        radial, distance, ..
+      } => match (radial, distance) {
+        (None, None) => Ok(()),
+        (Some(radial), Some(distance)) => {
+          if !(0.0..=360.0).contains(radial) {
+            Err(format!("radial {radial} is not between 0 and 360"))
+          } else if *distance <= 0.0 {
+            Err(format!("distance {distance} is not positive"))
+          } else {
+            Ok(())
+          }
+        }
+        _ => Err("a radial needs a distance, and a distance a radial".into()),
+      },
+      _ => Ok(()),
+    }
+  }
 }
 
 pub type Tasks = Vec<Task>;
@@ -49,13 +167,48 @@ pub struct Command {
   pub tasks: Tasks,
 }
 
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+/// Who a message on the frequency came from.
+pub enum MessageOrigin {
+  /// The controller (the player).
+  Atc,
+  /// An aircraft, such as a readback or a callout.
+  #[default]
+  Pilot,
+  /// The simulation itself, such as warnings and notices.
+  System,
+}
+
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+/// How urgently a message needs the controller's attention, so the client
+/// can color and prioritize it.
+pub enum Severity {
+  /// Routine calls, such as check-ins and readiness.
+  #[default]
+  Info,
+  /// Requests that couldn't be followed.
+  Caution,
+  /// Safety-critical calls, such as go-arounds and altitude alerts.
+  Warning,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommandWithFreq {
   pub id: String,
   pub frequency: f32,
   pub reply: CommandReply,
   pub tasks: Tasks,
+  /// When the message was sent, stamped from the runner's clock once it is
+  /// received.
   pub created: Duration,
+  #[serde(default)]
+  pub origin: MessageOrigin,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -64,15 +217,22 @@ pub struct OutgoingCommandReply {
   pub frequency: f32,
   pub reply: String,
   pub created: Duration,
+  pub origin: MessageOrigin,
+  #[serde(default)]
+  pub severity: Severity,
 }
 
-impl From<CommandWithFreq> for OutgoingCommandReply {
-  fn from(value: CommandWithFreq) -> Self {
+impl OutgoingCommandReply {
+  /// The reply of `value` as it is spoken, with its callsign spoken as one
+  /// of `airlines`.
+  pub fn new(value: CommandWithFreq, airlines: &Airlines) -> Self {
     Self {
       id: value.id.clone(),
       frequency: value.frequency,
-      reply: value.to_string(),
+      reply: value.to_text(airlines),
       created: value.created,
+      origin: value.origin,
+      severity: value.reply.severity(),
     }
   }
 }
@@ -89,23 +249,28 @@ impl CommandWithFreq {
       frequency,
       reply,
       tasks,
-      created: duration_now(),
+      created: Duration::ZERO,
+      origin: MessageOrigin::default(),
     }
   }
-}
 
-pub fn decode_callsign(callsign: &str) -> String {
-  let airline = callsign.chars().take(3).collect::<String>();
-  let fnumber = callsign.chars().skip(3).collect::<String>();
+  pub fn with_origin(mut self, origin: MessageOrigin) -> Self {
+    self.origin = origin;
+    self
+  }
 
-  let airline_str = match airline.as_str() {
-    "AAL" => "American Airlines",
-    "SKW" => "Skywest",
-    "JBU" => "JetBlue",
-    _ => "Unknown",
-  };
+  pub fn with_created(mut self, created: Duration) -> Self {
+    self.created = created;
+    self
+  }
+}
 
-  format!("{airline_str} {fnumber}")
+/// An IFR clearance, as read back by the aircraft that was given it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Clearance {
+  pub airport: String,
+  pub altitude: f32,
+  pub squawk: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -115,16 +280,93 @@ pub enum CommandReply {
   WithoutCallsign { text: String },
   WithCallsign { text: String },
 
-  GoAround { runway: String },
+  GoAround { runway: String, heading: f32 },
+  GoAroundTraffic { runway: String },
+  GoAroundCrosswind { runway: String },
+  RunwayInSight { runway: String },
+  Minimums { runway: String },
   HoldShortRunway { runway: String },
   ReadyForDeparture { airport: String },
+  RequestClearance { airport: String },
+  ClearanceReadback { clearance: Clearance },
   TaxiToGates { runway: String },
+  UnableTaxi { reason: String },
+  LineUpTimeout { runway: String, vacating: bool },
   ArriveInAirspace { direction: String, altitude: f32 },
+  LevelOff { altitude: f32 },
+  LowAltitude { altitude: f32 },
+  UnableAltitude { altitude: f32 },
+  UnableClimb { altitude: f32 },
+  UnableDescend { altitude: f32 },
+  UnableSpeed { speed: f32, minimum: f32 },
+  ExpectRunway { runway: String },
+  UnableTailwind { runway: String, or: Option<String> },
+  UnknownFix { fix: String },
+  UnknownRunway { runway: String },
+  UnknownProcedure { procedure: String },
+  CheckIn { controller: String },
+  MinimumFuel { minutes: f32 },
+  FuelExhausted,
 }
 
-impl fmt::Display for CommandWithFreq {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let decoded_callsign = decode_callsign(&self.id);
+impl CommandReply {
+  /// Whether this is a routine callout that can be left out of the message
+  /// log, unlike safety-critical ones such as go-arounds.
+  pub fn is_routine(&self) -> bool {
+    matches!(
+      self,
+      CommandReply::HoldShortRunway { .. }
+        | CommandReply::RunwayInSight { .. }
+        | CommandReply::Minimums { .. }
+        | CommandReply::ReadyForDeparture { .. }
+        | CommandReply::RequestClearance { .. }
+        | CommandReply::TaxiToGates { .. }
+        | CommandReply::ArriveInAirspace { .. }
+        | CommandReply::LevelOff { .. }
+        | CommandReply::ExpectRunway { .. }
+        | CommandReply::CheckIn { .. }
+    )
+  }
+
+  pub fn severity(&self) -> Severity {
+    match self {
+      CommandReply::GoAround { .. }
+      | CommandReply::GoAroundTraffic { .. }
+      | CommandReply::GoAroundCrosswind { .. }
+      | CommandReply::LowAltitude { .. }
+      | CommandReply::MinimumFuel { .. }
+      | CommandReply::FuelExhausted => Severity::Warning,
+      CommandReply::UnableTaxi { .. }
+      | CommandReply::LineUpTimeout { .. }
+      | CommandReply::UnableAltitude { .. }
+      | CommandReply::UnableClimb { .. }
+      | CommandReply::UnableDescend { .. }
+      | CommandReply::UnableSpeed { .. }
+      | CommandReply::UnableTailwind { .. }
+      | CommandReply::UnknownFix { .. }
+      | CommandReply::UnknownRunway { .. }
+      | CommandReply::UnknownProcedure { .. } => Severity::Caution,
+      _ => Severity::Info,
+    }
+  }
+}
+
+impl CommandWithFreq {
+  /// Our reply as it is spoken, with our callsign spoken as one of
+  /// `airlines`.
+  pub fn to_text(&self, airlines: &Airlines) -> String {
+    let mut text = String::new();
+    // Writing to a string can't fail.
+    let _ = self.write_text(&mut text, airlines);
+    text
+  }
+
+  fn write_text(
+    &self,
+    f: &mut impl fmt::Write,
+    airlines: &Airlines,
+  ) -> fmt::Result {
+    let decoded_callsign = airlines.telephony(&self.id);
 
     match &self.reply {
       CommandReply::Empty => {
@@ -140,8 +382,24 @@ impl fmt::Display for CommandWithFreq {
         write!(f, "{text}, {}.", decoded_callsign)
       }
 
-      CommandReply::GoAround { runway } => {
-        write!(f, "{decoded_callsign}, going around, missed approach for runway {runway}.")
+      CommandReply::GoAround { runway, heading } => {
+        write!(f, "{decoded_callsign}, going around, runway heading {heading:03.0}, missed approach for runway {runway}.")
+      }
+      CommandReply::GoAroundCrosswind { runway } => {
+        write!(f, "{decoded_callsign}, going around, crosswind too strong for runway {runway}.")
+      }
+      // We caught up with the traffic ahead on final.
+      CommandReply::GoAroundTraffic { runway } => {
+        write!(f, "{decoded_callsign}, going around, traffic too close ahead on final for runway {runway}.")
+      }
+      CommandReply::RunwayInSight { runway } => {
+        write!(f, "{decoded_callsign}, runway {runway} in sight.")
+      }
+      CommandReply::Minimums { runway } => {
+        write!(
+          f,
+          "{decoded_callsign}, minimums, continuing to runway {runway}."
+        )
       }
       CommandReply::ArriveInAirspace {
         direction,
@@ -164,10 +422,139 @@ impl fmt::Display for CommandWithFreq {
       CommandReply::ReadyForDeparture { airport } => {
         write!(
           f,
-          "Ground, {} ready for departure to {}.",
+          "Ground, {} boarding complete, ready for departure to {}.",
           decoded_callsign, airport
         )
       }
+      CommandReply::RequestClearance { airport } => {
+        write!(
+          f,
+          "Clearance, {} is ready to copy IFR to {}.",
+          decoded_callsign, airport
+        )
+      }
+      CommandReply::ClearanceReadback { clearance } => {
+        write!(
+          f,
+          "Cleared to {}, climb and maintain {}, squawk {:04}, {decoded_callsign}.",
+          clearance.airport,
+          abbreviate_altitude(clearance.altitude),
+          clearance.squawk
+        )
+      }
+      CommandReply::LevelOff { altitude } => {
+        write!(
+          f,
+          "{}, level at {}.",
+          decoded_callsign,
+          abbreviate_altitude(*altitude)
+        )
+      }
+      CommandReply::LowAltitude { altitude } => {
+        write!(
+          f,
+          "{}, low altitude alert, check your altitude immediately. The minimum safe altitude is {}.",
+          decoded_callsign,
+          abbreviate_altitude(*altitude)
+        )
+      }
+      CommandReply::UnableAltitude { altitude } => {
+        write!(
+          f,
+          "Unable, maintaining {} to remain in the airspace, {decoded_callsign}.",
+          abbreviate_altitude(*altitude)
+        )
+      }
+      CommandReply::UnableClimb { altitude } => {
+        write!(
+          f,
+          "Unable to climb, {} is below us, {decoded_callsign}.",
+          abbreviate_altitude(*altitude)
+        )
+      }
+      CommandReply::UnableDescend { altitude } => {
+        write!(
+          f,
+          "Unable to descend, {} is above us, {decoded_callsign}.",
+          abbreviate_altitude(*altitude)
+        )
+      }
+      CommandReply::UnableSpeed { speed, minimum } => {
+        write!(
+          f,
+          "Unable {} knots, our minimum speed is {} knots, {decoded_callsign}.",
+          speed.round(),
+          minimum.round()
+        )
+      }
+      CommandReply::ExpectRunway { runway } => {
+        write!(f, "Expect runway {runway}, {decoded_callsign}.")
+      }
+      CommandReply::UnableTailwind { runway, or } => {
+        if let Some(or) = or {
+          write!(
+            f,
+            "Unable runway {runway} due to tailwind, request runway {or}, {decoded_callsign}."
+          )
+        } else {
+          write!(
+            f,
+            "Unable runway {runway} due to tailwind, {decoded_callsign}."
+          )
+        }
+      }
+      CommandReply::UnknownFix { fix } => {
+        write!(f, "Unable, we can't find {fix}, {decoded_callsign}.")
+      }
+      CommandReply::UnknownRunway { runway } => {
+        write!(
+          f,
+          "Unable, there is no runway {runway}, {decoded_callsign}."
+        )
+      }
+      CommandReply::UnknownProcedure { procedure } => {
+        write!(
+          f,
+          "Unable, we don't have the {procedure} procedure, {decoded_callsign}."
+        )
+      }
+      CommandReply::CheckIn { controller } => {
+        let mut chars = controller.chars();
+        let controller = chars
+          .next()
+          .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+          .unwrap_or_default();
+        write!(f, "{controller}, {decoded_callsign}, with you.")
+      }
+      CommandReply::MinimumFuel { minutes } => {
+        write!(
+          f,
+          "{decoded_callsign}, declaring minimum fuel, {} minutes remaining.",
+          minutes.round()
+        )
+      }
+      CommandReply::FuelExhausted => {
+        write!(
+          f,
+          "Mayday, mayday, mayday, {decoded_callsign}, fuel exhausted, diverting to the nearest airport."
+        )
+      }
+      CommandReply::UnableTaxi { reason } => {
+        write!(f, "Unable to taxi, {reason}, {decoded_callsign}.")
+      }
+      CommandReply::LineUpTimeout { runway, vacating } => {
+        if *vacating {
+          write!(
+            f,
+            "Tower, {decoded_callsign} has been waiting on runway {runway}, vacating the runway."
+          )
+        } else {
+          write!(
+            f,
+            "Tower, {decoded_callsign} is still lined up and waiting on runway {runway}."
+          )
+        }
+      }
       CommandReply::TaxiToGates { runway } => {
         write!(
           f,
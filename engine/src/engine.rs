@@ -1,29 +1,44 @@
-use std::collections::HashSet;
+use std::{
+  collections::{HashMap, HashSet},
+  time::Duration,
+};
 
+use glam::Vec2;
 use internment::Intern;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use turborand::rng::Rng;
 
 use crate::{
-  angle_between_points, delta_angle,
+  command::{CommandReply, CommandWithFreq},
   entities::{
     aircraft::{
       effects::{
-        AircraftEffect, AircraftUpdateFlyingEffect,
-        AircraftUpdateFromTargetsEffect, AircraftUpdateLandingEffect,
-        AircraftUpdatePositionEffect, AircraftUpdateTaxiingEffect,
+        AircraftEffect, AircraftUpdateApproachCalloutsEffect,
+        AircraftUpdateBoardingEffect, AircraftUpdateDeferredTargetsEffect,
+        AircraftUpdateFlyingEffect, AircraftUpdateFromTargetsEffect,
+        AircraftUpdateFuelEffect, AircraftUpdateHoldingEffect,
+        AircraftUpdateLandingEffect, AircraftUpdateLevelOffEffect,
+        AircraftUpdateLineUpTimeoutEffect, AircraftUpdateMinSafeAltitudeEffect,
+        AircraftUpdatePositionEffect, AircraftUpdateRolloutEffect,
+        AircraftUpdateTaxiingEffect, AircraftUpdateTransponderEffect,
       },
       events::{
         AircraftEvent, AircraftEventHandler, EventKind, HandleAircraftEvent,
       },
-      Aircraft, AircraftState, TaxiingState,
+      wake_separation, Aircraft, AircraftState, LandingState, TaxiStopReason,
+      TaxiingState, WakeCategory, APPROACH_SEPARATION,
     },
-    world::{Game, World},
+    airport::Runway,
+    world::{closest_airport, Game, World, MAX_TIME_SCALE, MIN_TIME_SCALE},
   },
-  ENROUTE_TIME_MULTIPLIER, NAUTICALMILES_TO_FEET,
+  move_point, ENROUTE_TIME_MULTIPLIER, NAUTICALMILES_TO_FEET,
 };
 
+/// How close an arrival can be to the threshold before departures on the
+/// same runway have to wait for it to land, in feet.
+pub const SHORT_FINAL: f32 = NAUTICALMILES_TO_FEET * 3.0;
+
 #[derive(Debug)]
 pub struct Bundle<'a> {
   pub prev: Aircraft,
@@ -33,10 +48,17 @@ pub struct Bundle<'a> {
 
   pub rng: &'a mut Rng,
   pub dt: f32,
+  /// The time of the current tick, as given by the runner's clock.
+  pub now: Duration,
 }
 
 impl<'a> Bundle<'a> {
-  pub fn from_world(world: &'a World, rng: &'a mut Rng, dt: f32) -> Self {
+  pub fn from_world(
+    world: &'a World,
+    rng: &'a mut Rng,
+    dt: f32,
+    now: Duration,
+  ) -> Self {
     let prev = Aircraft::default();
     Self {
       prev,
@@ -44,6 +66,7 @@ impl<'a> Bundle<'a> {
       world,
       rng,
       dt,
+      now,
     }
   }
 }
@@ -56,9 +79,10 @@ pub enum UICommand {
   Purchase(usize),
 
   Pause,
+  SetTimeScale(f32),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 /// UI Events are sent from the engine to the frontend.
 pub enum UIEvent {
   // Inbound
@@ -68,6 +92,7 @@ pub enum UIEvent {
   Funds(usize),
 
   Pause,
+  TimeScale(f32),
 }
 
 impl From<UICommand> for UIEvent {
@@ -75,6 +100,7 @@ impl From<UICommand> for UIEvent {
     match value {
       UICommand::Purchase(aircraft_id) => Self::Purchase(aircraft_id),
       UICommand::Pause => Self::Pause,
+      UICommand::SetTimeScale(scale) => Self::TimeScale(scale),
     }
   }
 }
@@ -91,6 +117,37 @@ impl From<AircraftEvent> for Event {
   }
 }
 
+/// The events of a tick, sorted into what their subscribers act on so that
+/// each doesn't have to scan all of them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EventIndex {
+  pub callouts: Vec<CommandWithFreq>,
+  /// Aircraft to be removed from the game.
+  pub deletes: Vec<Intern<String>>,
+  /// Aircraft that have completed their flight.
+  pub completed_flights: Vec<Intern<String>>,
+}
+
+impl EventIndex {
+  pub fn new(events: &[Event]) -> Self {
+    let mut index = Self::default();
+    for event in events {
+      let Event::Aircraft(AircraftEvent { id, kind }) = event else {
+        continue;
+      };
+
+      match kind {
+        EventKind::Callout(command) => index.callouts.push(command.clone()),
+        EventKind::Delete => index.deletes.push(*id),
+        EventKind::CompleteFlight => index.completed_flights.push(*id),
+        _ => {}
+      }
+    }
+
+    index
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Engine {
   pub events: Vec<Event>,
@@ -103,14 +160,40 @@ impl Engine {
     game: &mut Game,
     rng: &mut Rng,
     dt: f32,
+    now: Duration,
   ) -> Vec<Event> {
-    let mut bundle = Bundle::from_world(world, rng, dt);
-    self.handle_collisions(&mut game.aircraft);
+    for event in self.events.iter() {
+      if let Event::UiEvent(UIEvent::TimeScale(scale)) = event {
+        game.time_scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+      }
+    }
+
+    // Each tick covers more or less game time when the game is running
+    // faster or slower than real time.
+    let dt = dt * game.time_scale();
+    let mut bundle = Bundle::from_world(world, rng, dt, now);
+    let separation_losses = self.handle_collisions(&mut game.aircraft_mut());
+    if let Some(score) = &mut game.points.score {
+      score.separation_losses += separation_losses;
+      for event in self.events.iter() {
+        match event {
+          Event::Aircraft(AircraftEvent {
+            kind: EventKind::CompleteFlight,
+            ..
+          }) => score.completed_flights += 1,
+          Event::Aircraft(AircraftEvent {
+            kind: EventKind::GoAround,
+            ..
+          }) => score.go_arounds += 1,
+          _ => {}
+        }
+      }
+    }
 
     if !self.events.is_empty() {
       tracing::trace!("tick events: {:?}", self.events);
     }
-    for aircraft in game.aircraft.iter_mut() {
+    for aircraft in game.aircraft_mut().iter_mut() {
       // Capture the previous state
       bundle.prev = aircraft.clone();
 
@@ -124,12 +207,27 @@ impl Engine {
         }
       }
 
+      // Frozen aircraft stay where they are until they are released.
+      if aircraft.frozen {
+        continue;
+      }
+
       // Run through all effects
       AircraftUpdateLandingEffect::run(aircraft, &mut bundle);
       AircraftUpdateFlyingEffect::run(aircraft, &mut bundle);
+      AircraftUpdateHoldingEffect::run(aircraft, &mut bundle);
+      AircraftUpdateRolloutEffect::run(aircraft, &mut bundle);
       AircraftUpdateTaxiingEffect::run(aircraft, &mut bundle);
       AircraftUpdateFromTargetsEffect::run(aircraft, &mut bundle);
+      AircraftUpdateLevelOffEffect::run(aircraft, &mut bundle);
+      AircraftUpdateMinSafeAltitudeEffect::run(aircraft, &mut bundle);
       AircraftUpdatePositionEffect::run(aircraft, &mut bundle);
+      AircraftUpdateApproachCalloutsEffect::run(aircraft, &mut bundle);
+      AircraftUpdateTransponderEffect::run(aircraft, &mut bundle);
+      AircraftUpdateDeferredTargetsEffect::run(aircraft, &mut bundle);
+      AircraftUpdateBoardingEffect::run(aircraft, &mut bundle);
+      AircraftUpdateLineUpTimeoutEffect::run(aircraft, &mut bundle);
+      AircraftUpdateFuelEffect::run(aircraft, &mut bundle);
     }
 
     for event in bundle.events.iter() {
@@ -138,25 +236,39 @@ impl Engine {
           kind: EventKind::SuccessfulTakeoff,
           ..
         }) => {
-          game.points.takeoff_rate.mark();
+          game.points.takeoff_rate.mark(now);
           game.points.takeoffs += 1;
         }
         Event::Aircraft(AircraftEvent {
           kind: EventKind::SuccessfulLanding,
           ..
         }) => {
-          game.points.landing_rate.mark();
+          game.points.landing_rate.mark(now);
           game.points.landings += 1;
         }
         _ => {}
       }
     }
 
-    game.points.landing_rate.calc_rate();
-    game.points.takeoff_rate.calc_rate();
+    game.points.landing_rate.calc_rate(now);
+    game.points.takeoff_rate.calc_rate(now);
+
+    let stopped = game
+      .aircraft()
+      .iter()
+      .filter(|a| {
+        matches!(a.state, AircraftState::Taxiing { .. }) && a.speed == 0.0
+      })
+      .count();
+    if let Some(score) = &mut game.points.score {
+      score.ground_delay += stopped as f32 * dt;
+      score.calc_total();
+    }
 
     self.space_inbounds(world, game);
-    self.taxi_collisions(&mut game.aircraft, &mut bundle);
+    self.taxi_collisions(&mut game.aircraft_mut(), &mut bundle);
+    self.handle_final_spacing(&mut game.aircraft_mut(), &mut bundle);
+    self.update_departure_queues(game, &mut bundle);
 
     // Capture the left over events and actions for next time
     if !bundle.events.is_empty() {
@@ -167,27 +279,26 @@ impl Engine {
     self.events.clone()
   }
 
-  pub fn handle_collisions(&mut self, aircrafts: &mut [Aircraft]) {
-    let mut collisions: HashSet<Intern<String>> = HashSet::new();
-    for pair in aircrafts.iter().combinations(2) {
-      let aircraft = pair.first().unwrap();
-      let other_aircraft = pair.last().unwrap();
-
-      let distance = aircraft.pos.distance_squared(other_aircraft.pos);
-      let vertical_distance =
-        (aircraft.altitude - other_aircraft.altitude).abs();
+  /// Indexes the events of the last tick.
+  pub fn event_index(&self) -> EventIndex {
+    EventIndex::new(&self.events)
+  }
 
-      if matches!(aircraft.state, AircraftState::Flying { enroute: false, .. })
-        && matches!(
-          other_aircraft.state,
-          AircraftState::Flying { enroute: false, .. }
-        )
-        && aircraft.altitude > 1000.0
-        && distance <= (NAUTICALMILES_TO_FEET * 4.0).powf(2.0)
-        && vertical_distance < 1000.0
-      {
+  /// Marks the aircraft that are too close to each other, returning how many
+  /// pairs of them have newly lost separation.
+  pub fn handle_collisions(&mut self, aircrafts: &mut [Aircraft]) -> usize {
+    let mut collisions: HashSet<Intern<String>> = HashSet::new();
+    let mut separation_losses = 0;
+    let sorted = sorted_by_id(aircrafts);
+    for (aircraft, other_aircraft) in nearby_pairs(&sorted, SEPARATION_DISTANCE)
+    {
+      if is_in_conflict(aircraft, other_aircraft) {
         collisions.insert(aircraft.id);
         collisions.insert(other_aircraft.id);
+
+        if !(aircraft.is_colliding && other_aircraft.is_colliding) {
+          separation_losses += 1;
+        }
       }
     }
 
@@ -201,19 +312,22 @@ impl Engine {
 
       aircraft.is_colliding = is_colliding;
     });
+
+    separation_losses
   }
 
   pub fn space_inbounds(&mut self, world: &World, game: &mut Game) {
-    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
     struct DistanceTime {
       index: usize,
       distance: f32,
       speed: f32,
+      wake: WakeCategory,
     }
 
     // Aircraft spacing system
     let mut reports: Vec<DistanceTime> = game
-      .aircraft
+      .aircraft()
       .iter()
       .enumerate()
       .filter(|(_, a)| {
@@ -231,6 +345,7 @@ impl Engine {
           index,
           distance,
           speed,
+          wake: a.wake_category(),
         }
       })
       .collect();
@@ -238,38 +353,106 @@ impl Engine {
     reports.sort_by(|a, b| b.distance.partial_cmp(&a.distance).unwrap());
 
     if let Some(closest) = reports.pop() {
-      let default_speed = 300.0;
-      let minutes_apart = 1.0;
-      let min_distance = NAUTICALMILES_TO_FEET
-        * (((default_speed * ENROUTE_TIME_MULTIPLIER) / 60.0) * minutes_apart);
-
       let mut last = closest;
       for report in reports.iter_mut().rev() {
         let diff = report.distance - last.distance;
-        let percent = diff / min_distance;
-        let speed = percent * default_speed;
-
-        report.speed = speed;
+        report.speed = inbound_spacing_speed(diff, last.wake, report.wake);
 
         last = *report;
       }
 
       for report in reports.iter() {
-        if let Some(aircraft) = game.aircraft.get_mut(report.index) {
+        if let Some(aircraft) = game.aircraft_mut().get_mut(report.index) {
           aircraft.target.speed = report.speed.clamp(250.0, 400.0);
         }
       }
     }
   }
 
+  /// Sends aircraft around when they are closer than [`MIN_FINAL_SPACING`]
+  /// behind another on final for the same runway.
+  pub fn handle_final_spacing(
+    &mut self,
+    aircrafts: &mut [Aircraft],
+    bundle: &mut Bundle,
+  ) {
+    // The runway of each aircraft on final, and how far out they are.
+    let finals: Vec<(&Aircraft, &Runway, f32)> = sorted_by_id(aircrafts)
+      .into_iter()
+      .filter(|a| a.is_on_localizer())
+      .filter_map(|a| match &a.state {
+        AircraftState::Landing { runway, .. } => {
+          Some((a, runway, a.pos.distance(runway.start())))
+        }
+        _ => None,
+      })
+      .collect();
+
+    let mut too_close: HashSet<Intern<String>> = HashSet::new();
+    for pair in finals.iter().combinations(2) {
+      let (a, a_runway, a_distance) = pair[0];
+      let (b, b_runway, b_distance) = pair[1];
+      // Runways at different airports can share an id, so only those with
+      // the same threshold are the same runway.
+      let same_runway =
+        a_runway.id == b_runway.id && a_runway.start() == b_runway.start();
+      if same_runway && (a_distance - b_distance).abs() < MIN_FINAL_SPACING {
+        let behind = if a_distance > b_distance { a } else { b };
+        too_close.insert(behind.id);
+      }
+    }
+
+    for aircraft in aircrafts.iter_mut() {
+      if !too_close.contains(&aircraft.id) {
+        continue;
+      }
+      let AircraftState::Landing { runway, state } = &mut aircraft.state else {
+        continue;
+      };
+
+      bundle.events.push(
+        AircraftEvent {
+          id: aircraft.id,
+          kind: EventKind::GoAround,
+        }
+        .into(),
+      );
+      bundle.events.push(
+        AircraftEvent {
+          id: aircraft.id,
+          kind: EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            CommandReply::GoAroundTraffic {
+              runway: runway.id.to_string(),
+            },
+            vec![],
+          )),
+        }
+        .into(),
+      );
+
+      *state = LandingState::GoAround;
+    }
+  }
+
   pub fn taxi_collisions(
     &mut self,
     aircrafts: &mut [Aircraft],
     bundle: &mut Bundle,
   ) {
+    // Each aircraft looks ahead as far as the airport it is at allows.
+    let world = bundle.world;
+    let in_cone = |aircraft: &Aircraft, other: &Aircraft| {
+      closest_airport(&world.airspace, aircraft.pos)
+        .map(|airport| airport.taxi_collision_cone)
+        .unwrap_or_default()
+        .contains(aircraft.pos, aircraft.heading, other.pos)
+    };
+
     let mut collisions: HashSet<Intern<String>> = HashSet::new();
-    for pair in aircrafts
-      .iter()
+    for pair in sorted_by_id(aircrafts)
+      .into_iter()
       .filter(|a| {
         matches!(
           a.state,
@@ -280,38 +463,31 @@ impl Engine {
     {
       let aircraft = pair.first().unwrap();
       let other_aircraft = pair.last().unwrap();
-      let distance = aircraft.pos.distance_squared(other_aircraft.pos);
 
-      if distance <= 250.0_f32.powf(2.0) * 2.0 {
-        if delta_angle(
-          aircraft.heading,
-          angle_between_points(aircraft.pos, other_aircraft.pos),
-        )
-        .abs()
-          <= 45.0
-        {
-          collisions.insert(aircraft.id);
-        }
-
-        if delta_angle(
-          other_aircraft.heading,
-          angle_between_points(other_aircraft.pos, aircraft.pos),
-        )
-        .abs()
-          <= 45.0
-        {
-          collisions.insert(other_aircraft.id);
-        }
+      if in_cone(aircraft, other_aircraft) {
+        collisions.insert(aircraft.id);
+      }
+      if in_cone(other_aircraft, aircraft) {
+        collisions.insert(other_aircraft.id);
       }
     }
 
-    for aircraft in aircrafts.iter_mut() {
+    // Push events in id order too, so they don't depend on insertion order.
+    let mut order: Vec<usize> = (0..aircrafts.len()).collect();
+    order.sort_by(|a, b| aircrafts[*a].id.cmp(&aircrafts[*b].id));
+
+    for i in order {
+      let aircraft = &mut aircrafts[i];
       if let AircraftState::Taxiing { state, .. } = &mut aircraft.state {
         if collisions.contains(&aircraft.id) && state == &TaxiingState::Armed {
           *state = TaxiingState::Stopped;
+          aircraft.stop_reason = Some(TaxiStopReason::CollisionAhead);
           bundle.events.push(Event::Aircraft(AircraftEvent::new(
             aircraft.id,
-            EventKind::TaxiHold { and_state: false },
+            EventKind::TaxiHold {
+              and_state: false,
+              reason: Some(TaxiStopReason::CollisionAhead),
+            },
           )));
         } else if !collisions.contains(&aircraft.id)
           && matches!(state, TaxiingState::Override | TaxiingState::Stopped)
@@ -328,4 +504,1052 @@ impl Engine {
       }
     }
   }
+
+  pub fn update_departure_queues(
+    &mut self,
+    game: &mut Game,
+    bundle: &mut Bundle,
+  ) {
+    let world = bundle.world;
+    let now = bundle.now;
+
+    // Drop any aircraft that have departed or are no longer waiting for the
+    // runway that they were queued for.
+    let mut departure_queues = core::mem::take(&mut game.departure_queues);
+    let mut departed = Vec::new();
+    for (airport, runways) in departure_queues.iter_mut() {
+      for (runway, queue) in runways.iter_mut() {
+        queue.retain(|id| {
+          let aircraft = game.aircraft_by_id(*id);
+          if aircraft
+            .is_some_and(|a| matches!(a.state, AircraftState::Flying { .. }))
+          {
+            departed.push((*airport, *runway));
+          }
+
+          aircraft.and_then(|a| a.departure_runway()) == Some(*runway)
+        });
+      }
+      runways.retain(|_, queue| !queue.is_empty());
+    }
+    for (airport, runway) in departed {
+      game
+        .last_departures
+        .entry(airport)
+        .or_default()
+        .insert(runway, now);
+    }
+    departure_queues.retain(|_, runways| !runways.is_empty());
+
+    // Enqueue aircraft that have just arrived at the hold short line.
+    for aircraft in game.aircraft().iter() {
+      if !aircraft.at_hold_short() {
+        continue;
+      }
+      let Some(runway) = aircraft.departure_runway() else {
+        continue;
+      };
+      let Some(airport) = closest_airport(&world.airspace, aircraft.pos) else {
+        continue;
+      };
+
+      let queue = departure_queues
+        .entry(airport.id)
+        .or_default()
+        .entry(runway)
+        .or_default();
+      if !queue.contains(&aircraft.id) {
+        queue.push(aircraft.id);
+      }
+    }
+    game.departure_queues = departure_queues;
+
+    // Runways with an arrival on short final, which departures that haven't
+    // entered the runway yet have to wait for.
+    let arrivals: HashSet<(Intern<String>, Intern<String>)> = game
+      .aircraft()
+      .iter()
+      .filter_map(|aircraft| match &aircraft.state {
+        AircraftState::Landing { runway, .. }
+          if is_on_short_final(aircraft.pos, runway) =>
+        {
+          closest_airport(&world.airspace, runway.pos)
+            .map(|airport| (airport.id, runway.id))
+        }
+        _ => None,
+      })
+      .collect();
+
+    // Runways that departed an aircraft too recently for the next to go.
+    let spacing: HashSet<(Intern<String>, Intern<String>)> = game
+      .last_departures
+      .iter()
+      .flat_map(|(airport, runways)| {
+        let interval = world
+          .airspace
+          .airports
+          .iter()
+          .find(|a| a.id == *airport)
+          .map(|a| a.min_departure_interval())
+          .unwrap_or_default();
+        runways
+          .iter()
+          .filter(move |(_, departed)| now < **departed + interval)
+          .map(move |(runway, _)| (*airport, *runway))
+      })
+      .collect();
+
+    type QueuePosition = ((Intern<String>, Intern<String>), usize);
+    let positions: HashMap<Intern<String>, QueuePosition> = game
+      .departure_queues
+      .iter()
+      .flat_map(|(airport, runways)| {
+        runways.iter().flat_map(move |(runway, queue)| {
+          queue
+            .iter()
+            .enumerate()
+            .map(move |(p, id)| (*id, ((*airport, *runway), p)))
+        })
+      })
+      .collect();
+    for aircraft in game.aircraft_mut().iter_mut() {
+      let position = positions.get(&aircraft.id).copied();
+      aircraft.queue_position = position.map(|(_, p)| p);
+
+      let cleared = aircraft.cleared_for_departure();
+      if let AircraftState::Taxiing {
+        state, waypoints, ..
+      } = &mut aircraft.state
+      {
+        let arrival = position.is_some_and(|(key, _)| arrivals.contains(&key))
+          && !waypoints.is_empty();
+        let spaced = position.is_some_and(|(key, _)| spacing.contains(&key))
+          && !waypoints.is_empty();
+
+        match position {
+          // Hold anyone cleared out of turn until those ahead have gone,
+          // anyone cleared ahead of an arrival until it has landed, and
+          // anyone cleared too soon after the last departure.
+          Some((_, p))
+            if (p > 0 || arrival || spaced)
+              && cleared
+              && *state != TaxiingState::Queued =>
+          {
+            *state = TaxiingState::Queued;
+            bundle.events.push(
+              AircraftEvent::new(
+                aircraft.id,
+                EventKind::TaxiHold {
+                  and_state: false,
+                  reason: Some(TaxiStopReason::RunwayOccupied),
+                },
+              )
+              .into(),
+            );
+          }
+          // Release the next aircraft if it was already cleared, once there
+          // is a gap between arrivals and the last departure has had time to
+          // get clear.
+          Some((_, 0))
+            if *state == TaxiingState::Queued && !arrival && !spaced =>
+          {
+            *state = TaxiingState::Armed;
+            bundle.events.push(
+              AircraftEvent::new(aircraft.id, EventKind::TaxiContinue).into(),
+            );
+          }
+          _ => {}
+        }
+      }
+    }
+  }
+}
+
+/// Whether an aircraft at `pos` is within [`SHORT_FINAL`] of the threshold
+/// of `runway` and hasn't crossed it yet.
+fn is_on_short_final(pos: Vec2, runway: &Runway) -> bool {
+  let threshold = runway.start();
+  let ahead =
+    (pos - threshold).dot(move_point(Vec2::ZERO, runway.heading, 1.0));
+
+  ahead < 0.0 && pos.distance_squared(threshold) <= SHORT_FINAL.powf(2.0)
+}
+
+/// How fast an inbound `gap` feet behind the one ahead of it flies so that
+/// they end up a minute apart, or further apart where the one ahead leaves
+/// wake turbulence for the one behind.
+fn inbound_spacing_speed(
+  gap: f32,
+  leader: WakeCategory,
+  follower: WakeCategory,
+) -> f32 {
+  let default_speed = 300.0;
+  let minutes_apart = wake_separation(leader, follower) / APPROACH_SEPARATION;
+  let min_distance = NAUTICALMILES_TO_FEET
+    * (((default_speed * ENROUTE_TIME_MULTIPLIER) / 60.0) * minutes_apart);
+
+  gap / min_distance * default_speed
+}
+
+/// How close behind another aircraft on final we can be before we go around,
+/// in feet.
+pub const MIN_FINAL_SPACING: f32 = NAUTICALMILES_TO_FEET * 2.5;
+
+/// How close aircraft can be laterally before they lose separation, in feet.
+pub const SEPARATION_DISTANCE: f32 = NAUTICALMILES_TO_FEET * 4.0;
+
+/// Whether two aircraft are too close to each other in our airspace.
+fn is_in_conflict(aircraft: &Aircraft, other_aircraft: &Aircraft) -> bool {
+  let distance = aircraft.pos.distance_squared(other_aircraft.pos);
+  let vertical_distance = (aircraft.altitude - other_aircraft.altitude).abs();
+
+  matches!(aircraft.state, AircraftState::Flying { enroute: false, .. })
+    && matches!(
+      other_aircraft.state,
+      AircraftState::Flying { enroute: false, .. }
+    )
+    && aircraft.altitude > 1000.0
+    && distance <= SEPARATION_DISTANCE.powf(2.0)
+    && vertical_distance < 1000.0
+}
+
+/// The pairs of aircraft that are within `cell_size` of each other, and
+/// maybe a few more. Aircraft are bucketed into a grid of `cell_size`
+/// squares so that only those in neighbouring cells are compared, rather
+/// than every pair. Pairs are in the same order as the aircraft given.
+fn nearby_pairs<'a>(
+  aircrafts: &[&'a Aircraft],
+  cell_size: f32,
+) -> Vec<(&'a Aircraft, &'a Aircraft)> {
+  let cell_of = |pos: Vec2| {
+    (
+      (pos.x / cell_size).floor() as i32,
+      (pos.y / cell_size).floor() as i32,
+    )
+  };
+
+  let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+  for (i, aircraft) in aircrafts.iter().enumerate() {
+    grid.entry(cell_of(aircraft.pos)).or_default().push(i);
+  }
+
+  let mut pairs = Vec::new();
+  for (i, aircraft) in aircrafts.iter().enumerate() {
+    let (x, y) = cell_of(aircraft.pos);
+    let mut others: Vec<usize> = (x - 1..=x + 1)
+      .cartesian_product(y - 1..=y + 1)
+      .filter_map(|cell| grid.get(&cell))
+      .flatten()
+      .copied()
+      .filter(|j| *j > i)
+      .collect();
+    others.sort_unstable();
+
+    pairs.extend(others.into_iter().map(|j| (*aircraft, aircrafts[j])));
+  }
+
+  pairs
+}
+
+/// Aircraft in order of their ids, so that pairwise checks visit them the
+/// same way no matter the order they were added in.
+fn sorted_by_id(aircrafts: &[Aircraft]) -> Vec<&Aircraft> {
+  let mut sorted: Vec<&Aircraft> = aircrafts.iter().collect();
+  sorted.sort_by_key(|a| a.id);
+  sorted
+}
+
+#[cfg(test)]
+mod tests {
+  use turborand::{SeededCore, TurboRand};
+
+  use crate::{
+    angle_between_points, calculate_ils_altitude,
+    command::{CommandReply, Task},
+    entities::{
+      airport::Airport,
+      airspace::Airspace,
+      world::{Score, COMPLETED_FLIGHT_SCORE, SEPARATION_LOSS_PENALTY},
+    },
+    pathfinder::{Node, NodeBehavior, NodeKind},
+  };
+
+  use super::*;
+
+  fn departing_aircraft(id: &str, x: f32, y: f32) -> Aircraft {
+    Aircraft {
+      id: Intern::from_ref(id),
+      pos: Vec2::new(x, y),
+      speed: 20.0,
+      state: AircraftState::Taxiing {
+        current: Node::new(
+          Intern::from_ref("A"),
+          NodeKind::Taxiway,
+          NodeBehavior::GoTo,
+          Vec2::new(x, y),
+        ),
+        waypoints: vec![Node::new(
+          Intern::from_ref("27"),
+          NodeKind::Runway,
+          NodeBehavior::HoldShort,
+          Vec2::new(x, 0.0),
+        )],
+        state: TaxiingState::Armed,
+      },
+      ..Default::default()
+    }
+    .with_synced_targets()
+  }
+
+  #[test]
+  fn departures_follow_hold_short_order() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::ZERO,
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // Inserted out of order: "A" reaches the hold short line first.
+    game.push_aircraft(departing_aircraft("C", 1000.0, -1200.0));
+    game.push_aircraft(departing_aircraft("A", -1000.0, -400.0));
+    game.push_aircraft(departing_aircraft("B", 0.0, -800.0));
+
+    for _ in 0..120 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    }
+
+    let positions: Vec<(&str, Option<usize>)> = game
+      .aircraft()
+      .iter()
+      .map(|a| (a.id.as_str(), a.queue_position))
+      .collect();
+    assert_eq!(
+      positions,
+      vec![("C", Some(2)), ("A", Some(0)), ("B", Some(1))]
+    );
+
+    for id in ["C", "B", "A"] {
+      engine.events.push(
+        AircraftEvent::new(
+          Intern::from_ref(id),
+          EventKind::Takeoff(Intern::from_ref("27")),
+        )
+        .into(),
+      );
+    }
+
+    let mut departed = Vec::new();
+    for _ in 0..300 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      for event in events {
+        if let Event::Aircraft(AircraftEvent {
+          id,
+          kind: EventKind::SuccessfulTakeoff,
+        }) = event
+        {
+          departed.push(id.to_string());
+        }
+      }
+    }
+
+    assert_eq!(departed, vec!["A", "B", "C"]);
+  }
+
+  #[test]
+  fn departures_queue_per_airport_from_the_hold_short_line() {
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::ZERO,
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let mut ksfo = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    ksfo.add_runway(runway.clone());
+    let oakland = Vec2::new(0.0, 50000.0);
+    let mut koak = Airport::new(Intern::from_ref("KOAK"), oakland);
+    koak.add_runway(Runway {
+      pos: oakland,
+      ..runway
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![ksfo, koak],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    game.push_aircraft(departing_aircraft("A", 0.0, -1500.0));
+    let mut b = departing_aircraft("B", 0.0, -400.0);
+    b.pos += oakland;
+    if let AircraftState::Taxiing { waypoints, .. } = &mut b.state {
+      waypoints[0].value += oakland;
+    }
+    game.push_aircraft(b);
+    engine.events.push(
+      AircraftEvent::new(
+        Intern::from_ref("A"),
+        EventKind::Takeoff(Intern::from_ref("27")),
+      )
+      .into(),
+    );
+
+    // Cleared, but still taxiing to the hold short line.
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    let a = game.aircraft_by_id(Intern::from_ref("A")).unwrap();
+    assert_eq!(a.queue_position, None);
+
+    for _ in 0..40 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    }
+
+    // Both are first in line at their own airport's runway 27.
+    for id in ["A", "B"] {
+      let aircraft = game.aircraft_by_id(Intern::from_ref(id)).unwrap();
+      assert_eq!(aircraft.queue_position, Some(0), "{id}");
+    }
+    assert_eq!(game.departure_queues.len(), 2);
+  }
+
+  #[test]
+  fn departures_are_released_between_arrivals() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::ZERO,
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let runway = airport.runways[0].clone();
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // Arrivals on the final for runway 27, six miles apart.
+    for (id, miles) in [("ARR1", 2.0), ("ARR2", 8.0), ("ARR3", 14.0)] {
+      let distance = NAUTICALMILES_TO_FEET * miles;
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          pos: runway.start() + Vec2::new(distance, 0.0),
+          heading: 270.0,
+          speed: 160.0,
+          altitude: calculate_ils_altitude(distance).min(4000.0),
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+      engine.events.push(
+        AircraftEvent::new(Intern::from_ref(id), EventKind::Land(runway.id))
+          .into(),
+      );
+    }
+
+    let departure = Intern::from_ref("DEP1");
+    game.push_aircraft(departing_aircraft("DEP1", 0.0, -800.0));
+    engine.events.push(
+      AircraftEvent::new(departure, EventKind::Takeoff(runway.id)).into(),
+    );
+
+    let mut held = false;
+    let mut departed = false;
+    for _ in 0..300 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      let aircraft = game.aircraft_by_id(departure).unwrap();
+      held |= aircraft.stop_reason == Some(TaxiStopReason::RunwayOccupied);
+
+      if events.iter().any(|e| {
+        matches!(
+          e,
+          Event::Aircraft(AircraftEvent {
+            id,
+            kind: EventKind::SuccessfulTakeoff,
+          }) if *id == departure
+        )
+      }) {
+        // Nobody was about to land when it went.
+        assert!(!game.aircraft().iter().any(|a| matches!(
+          &a.state,
+          AircraftState::Landing { runway, .. }
+            if is_on_short_final(a.pos, runway)
+        )));
+        departed = true;
+        break;
+      }
+    }
+
+    assert!(held, "departure was not held for the first arrival");
+    assert!(departed, "departure was never released");
+  }
+
+  #[test]
+  fn departures_are_spaced_by_interval() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::ZERO,
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    airport.min_departure_interval = 90.0;
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    game.push_aircraft(departing_aircraft("A", -1000.0, -400.0));
+    game.push_aircraft(departing_aircraft("B", 0.0, -800.0));
+    for id in ["A", "B"] {
+      engine.events.push(
+        AircraftEvent::new(
+          Intern::from_ref(id),
+          EventKind::Takeoff(Intern::from_ref("27")),
+        )
+        .into(),
+      );
+    }
+
+    let mut departed = Vec::new();
+    let mut now = Duration::ZERO;
+    for _ in 0..300 {
+      let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+      for event in events {
+        if let Event::Aircraft(AircraftEvent {
+          id,
+          kind: EventKind::SuccessfulTakeoff,
+        }) = event
+        {
+          departed.push((id.to_string(), now));
+        }
+      }
+      now += Duration::from_secs(1);
+    }
+
+    let [(first, first_at), (second, second_at)] = departed.as_slice() else {
+      panic!("expected two departures, got {departed:?}");
+    };
+    assert_eq!((first.as_str(), second.as_str()), ("A", "B"));
+    assert!(
+      *second_at - *first_at >= Duration::from_secs(90),
+      "departed {:?} apart",
+      *second_at - *first_at
+    );
+  }
+
+  #[test]
+  fn departure_spacing_is_per_airport() {
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::ZERO,
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let mut ksfo = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    ksfo.add_runway(runway.clone());
+    ksfo.min_departure_interval = 90.0;
+    let oakland = Vec2::new(0.0, 50000.0);
+    let mut koak = Airport::new(Intern::from_ref("KOAK"), oakland);
+    koak.add_runway(Runway {
+      pos: oakland,
+      ..runway
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![ksfo, koak],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    game.push_aircraft(departing_aircraft("A", 0.0, -400.0));
+    let mut b = departing_aircraft("B", 0.0, -800.0);
+    b.pos += oakland;
+    if let AircraftState::Taxiing { waypoints, .. } = &mut b.state {
+      waypoints[0].value += oakland;
+    }
+    game.push_aircraft(b);
+    for id in ["A", "B"] {
+      engine.events.push(
+        AircraftEvent::new(
+          Intern::from_ref(id),
+          EventKind::Takeoff(Intern::from_ref("27")),
+        )
+        .into(),
+      );
+    }
+
+    let mut departed = Vec::new();
+    let mut now = Duration::ZERO;
+    for _ in 0..120 {
+      let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+      for event in events {
+        if let Event::Aircraft(AircraftEvent {
+          id,
+          kind: EventKind::SuccessfulTakeoff,
+        }) = event
+        {
+          departed.push((id.to_string(), now));
+        }
+      }
+      now += Duration::from_secs(1);
+    }
+
+    // KSFO's interval doesn't hold up KOAK's runway of the same name.
+    let [(first, first_at), (second, second_at)] = departed.as_slice() else {
+      panic!("expected two departures, got {departed:?}");
+    };
+    assert_eq!((first.as_str(), second.as_str()), ("A", "B"));
+    assert!(*second_at - *first_at < Duration::from_secs(90));
+  }
+
+  #[test]
+  fn time_scale_speeds_up_the_game() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let flying = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      heading: 90.0,
+      speed: 250.0,
+      altitude: 10000.0,
+      ..Default::default()
+    }
+    .with_synced_targets();
+    let dt = 1.0 / 15.0;
+
+    let mut real_time = Game::default();
+    real_time.push_aircraft(flying.clone());
+    let mut engine = Engine::default();
+    for _ in 0..20 {
+      engine.tick(&world, &mut real_time, &mut rng, dt, Duration::ZERO);
+    }
+
+    let mut fast_forward = Game::default();
+    fast_forward.push_aircraft(flying);
+    let mut engine = Engine::default();
+    engine
+      .events
+      .push(Event::UiEvent(UICommand::SetTimeScale(2.0).into()));
+    for _ in 0..10 {
+      engine.tick(&world, &mut fast_forward, &mut rng, dt, Duration::ZERO);
+    }
+
+    assert_eq!(fast_forward.time_scale, 2.0);
+    let real_time = real_time.aircraft()[0].pos;
+    let fast_forward = fast_forward.aircraft()[0].pos;
+    assert!(real_time.x > 0.0);
+    assert!(
+      real_time.distance(fast_forward) < 1.0,
+      "{real_time} != {fast_forward}"
+    );
+  }
+
+  #[test]
+  fn time_scale_is_clamped() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+
+    engine
+      .events
+      .push(Event::UiEvent(UICommand::SetTimeScale(100.0).into()));
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    assert_eq!(game.time_scale, MAX_TIME_SCALE);
+
+    engine.events.push(Event::UiEvent(UIEvent::TimeScale(0.0)));
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    assert_eq!(game.time_scale, MIN_TIME_SCALE);
+  }
+
+  #[test]
+  fn taxi_collisions_ignore_insertion_order() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+
+    let mut aircraft: Vec<Aircraft> = [("A", 0.0), ("B", 200.0), ("C", 400.0)]
+      .into_iter()
+      .map(|(id, x)| departing_aircraft(id, x, 0.0))
+      .collect();
+    // Each faces its neighbour, so all of them are in conflict.
+    for (i, other) in [(0, 1), (1, 2), (2, 1)] {
+      aircraft[i].heading =
+        angle_between_points(aircraft[i].pos, aircraft[other].pos);
+    }
+
+    let mut outcomes = Vec::new();
+    for order in [[0, 1, 2], [2, 0, 1], [1, 2, 0]] {
+      let mut shuffled: Vec<Aircraft> =
+        order.iter().map(|i| aircraft[*i].clone()).collect();
+      let mut bundle =
+        Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+      Engine::default().taxi_collisions(&mut shuffled, &mut bundle);
+
+      shuffled.sort_by_key(|a| a.id);
+      let states: Vec<AircraftState> =
+        shuffled.into_iter().map(|a| a.state).collect();
+      outcomes.push((states, bundle.events));
+    }
+
+    assert_eq!(outcomes[0].1.len(), 3);
+    assert_eq!(outcomes[0], outcomes[1]);
+    assert_eq!(outcomes[0], outcomes[2]);
+  }
+
+  #[test]
+  fn taxi_stop_reasons() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // "A" is taxiing right behind "B".
+    game.push_aircraft(departing_aircraft("A", 0.0, -2000.0));
+    game.push_aircraft(departing_aircraft("B", 0.0, -1800.0));
+    game.push_aircraft(departing_aircraft("C", 3000.0, -2000.0));
+    engine.events.push(
+      AircraftEvent::new(Intern::from_ref("C"), Task::TaxiHold.into()).into(),
+    );
+
+    for _ in 0..2 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    }
+
+    let reasons: Vec<(&str, Option<TaxiStopReason>)> = game
+      .aircraft()
+      .iter()
+      .map(|a| (a.id.as_str(), a.stop_reason))
+      .collect();
+    assert_eq!(
+      reasons,
+      vec![
+        ("A", Some(TaxiStopReason::CollisionAhead)),
+        ("B", None),
+        ("C", Some(TaxiStopReason::PlayerHold)),
+      ]
+    );
+  }
+
+  #[test]
+  fn held_taxis_stay_held() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // "A" stops at the edge of its cone behind "B", which is held.
+    game.push_aircraft(departing_aircraft("A", 0.0, -2000.0));
+    game.push_aircraft(departing_aircraft("B", 0.0, -1650.0));
+    engine.events.push(
+      AircraftEvent::new(Intern::from_ref("B"), Task::TaxiHold.into()).into(),
+    );
+
+    for _ in 0..60 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      assert!(!events.iter().any(|e| matches!(
+        e,
+        Event::Aircraft(AircraftEvent {
+          kind: EventKind::TaxiContinue,
+          ..
+        })
+      )));
+    }
+
+    let a = game.aircraft_by_id(Intern::from_ref("A")).unwrap();
+    assert_eq!(a.stop_reason, Some(TaxiStopReason::CollisionAhead));
+    assert_eq!(a.speed, 0.0);
+  }
+
+  #[test]
+  fn larger_collision_cone_stops_earlier() {
+    let stopped = |world: &World| {
+      let mut rng = Rng::with_seed(0);
+      let mut aircraft = vec![
+        departing_aircraft("A", 0.0, 0.0),
+        departing_aircraft("B", 0.0, 500.0),
+      ];
+      let mut bundle = Bundle::from_world(world, &mut rng, 1.0, Duration::ZERO);
+      Engine::default().taxi_collisions(&mut aircraft, &mut bundle);
+
+      aircraft[0].stop_reason == Some(TaxiStopReason::CollisionAhead)
+    };
+
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.taxi_collision_cone.distance = 600.0;
+    let dense = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    assert!(!stopped(&World::default()));
+    assert!(stopped(&dense));
+  }
+
+  #[test]
+  fn event_index_buckets_events() {
+    let callout = |id: &str, text: &str| {
+      AircraftEvent::new(
+        Intern::from_ref(id),
+        EventKind::Callout(CommandWithFreq::new(
+          id.to_string(),
+          118.5,
+          CommandReply::WithCallsign {
+            text: text.to_string(),
+          },
+          Vec::new(),
+        )),
+      )
+      .into()
+    };
+    let aal = Intern::from_ref("AAL1234");
+    let skw = Intern::from_ref("SKW5678");
+    let events: Vec<Event> = vec![
+      callout("AAL1234", "Climbing"),
+      AircraftEvent::new(aal, EventKind::Altitude(3000.0)).into(),
+      Event::UiEvent(UIEvent::Pause),
+      AircraftEvent::new(skw, EventKind::Delete).into(),
+      callout("SKW5678", "Goodbye"),
+      AircraftEvent::new(aal, EventKind::CompleteFlight).into(),
+    ];
+
+    let index = EventIndex::new(&events);
+    let callouts: Vec<CommandWithFreq> = events
+      .iter()
+      .filter_map(|e| match e {
+        Event::Aircraft(AircraftEvent {
+          kind: EventKind::Callout(command),
+          ..
+        }) => Some(command.clone()),
+        _ => None,
+      })
+      .collect();
+
+    assert_eq!(callouts.len(), 2);
+    assert_eq!(index.callouts, callouts);
+    assert_eq!(index.deletes, vec![skw]);
+    assert_eq!(index.completed_flights, vec![aal]);
+
+    let engine = Engine { events };
+    assert_eq!(engine.event_index(), index);
+  }
+
+  #[test]
+  fn grid_conflicts_match_every_pair() {
+    let rng = Rng::with_seed(0);
+    let area = NAUTICALMILES_TO_FEET * 100.0;
+    let aircrafts: Vec<Aircraft> = (0..200)
+      .map(|i| Aircraft {
+        id: Intern::from(format!("AAL{i:04}")),
+        pos: Vec2::new((rng.f32() - 0.5) * area, (rng.f32() - 0.5) * area),
+        altitude: 2000.0 + rng.f32() * 4000.0,
+        state: AircraftState::Flying {
+          enroute: false,
+          waypoints: Vec::new(),
+        },
+        ..Default::default()
+      })
+      .collect();
+    let sorted = sorted_by_id(&aircrafts);
+
+    let conflicts = |pairs: &[(&Aircraft, &Aircraft)]| {
+      pairs
+        .iter()
+        .filter(|(a, b)| is_in_conflict(a, b))
+        .map(|(a, b)| (a.id, b.id))
+        .collect::<Vec<_>>()
+    };
+    let every_pair: Vec<(&Aircraft, &Aircraft)> =
+      sorted.iter().copied().tuple_combinations().collect();
+    let nearby = nearby_pairs(&sorted, SEPARATION_DISTANCE);
+
+    assert!(!conflicts(&every_pair).is_empty());
+    assert_eq!(conflicts(&nearby), conflicts(&every_pair));
+
+    // Far fewer pairs are compared than with every pair.
+    assert!(
+      nearby.len() * 10 < every_pair.len(),
+      "compared {} of {} pairs",
+      nearby.len(),
+      every_pair.len()
+    );
+  }
+
+  #[test]
+  fn catching_up_on_final_goes_around() {
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // A runway of the same name at another airport.
+    let other_runway = Runway {
+      pos: Vec2::new(0.0, 50000.0),
+      ..runway.clone()
+    };
+    for (id, runway, miles) in [
+      ("AAL1234", &runway, 4.0),
+      ("SKW5678", &runway, 5.0),
+      ("UAL9012", &other_runway, 4.5),
+    ] {
+      let pos = move_point(runway.start(), 90.0, NAUTICALMILES_TO_FEET * miles);
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          pos,
+          speed: 180.0,
+          heading: 270.0,
+          altitude: calculate_ils_altitude(pos.distance(runway.start())),
+          state: AircraftState::Landing {
+            runway: runway.clone(),
+            state: LandingState::Glideslope,
+          },
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+    }
+
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+
+    let landing_state = |id: &str| match &game
+      .aircraft_by_id(Intern::from_ref(id))
+      .unwrap()
+      .state
+    {
+      AircraftState::Landing { state, .. } => Some(*state),
+      _ => None,
+    };
+    // The one ahead carries on with its approach.
+    assert!(landing_state("AAL1234")
+      .is_some_and(|state| state != LandingState::GoAround));
+    assert_eq!(landing_state("SKW5678"), Some(LandingState::GoAround));
+    assert!(landing_state("UAL9012")
+      .is_some_and(|state| state != LandingState::GoAround));
+    assert!(engine.events.iter().any(|e| matches!(
+      e,
+      Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::GoAroundTraffic { .. },
+          ..
+        }),
+        ..
+      })
+    )));
+  }
+
+  #[test]
+  fn wake_turbulence_spaces_inbounds_further_apart() {
+    let gap = NAUTICALMILES_TO_FEET * 60.0;
+    let medium_behind_medium =
+      inbound_spacing_speed(gap, WakeCategory::Medium, WakeCategory::Medium);
+    let light_behind_heavy =
+      inbound_spacing_speed(gap, WakeCategory::Heavy, WakeCategory::Light);
+    assert!(light_behind_heavy < medium_behind_medium);
+
+    // Pairs without a wake minimum are spaced the same.
+    assert_eq!(
+      inbound_spacing_speed(gap, WakeCategory::Heavy, WakeCategory::Medium),
+      medium_behind_medium
+    );
+  }
+
+  #[test]
+  fn score_tracks_separation_and_flights() {
+    let world = World::default();
+    let mut game = Game::default();
+    game.points.score = Some(Score::default());
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    for (id, x) in [("AAL1234", 0.0), ("SKW5678", NAUTICALMILES_TO_FEET)] {
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          pos: Vec2::new(x, 0.0),
+          altitude: 5000.0,
+          state: AircraftState::Flying {
+            enroute: false,
+            waypoints: Vec::new(),
+          },
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+    }
+    engine.events = vec![AircraftEvent::new(
+      Intern::from_ref("JBU9012"),
+      EventKind::CompleteFlight,
+    )
+    .into()];
+
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    let score = game.points.score.clone().unwrap();
+    assert_eq!(score.separation_losses, 1);
+    assert_eq!(score.completed_flights, 1);
+    assert_eq!(
+      score.total,
+      COMPLETED_FLIGHT_SCORE - SEPARATION_LOSS_PENALTY
+    );
+
+    // Staying in conflict isn't another loss of separation.
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    let score = game.points.score.clone().unwrap();
+    assert_eq!(score.separation_losses, 1);
+    assert_eq!(
+      score.total,
+      COMPLETED_FLIGHT_SCORE - SEPARATION_LOSS_PENALTY
+    );
+  }
 }
@@ -1,21 +1,32 @@
-use std::f32::consts::PI;
+use glam::Vec2;
 
 use crate::{
-  add_degrees, angle_between_points, calculate_ils_altitude,
-  closest_point_on_line,
-  command::{CommandReply, CommandWithFreq},
+  angle_between_points, calculate_ils_altitude, closest_point_on_line,
+  command::{CommandReply, CommandWithFreq, HoldDirection, MessageOrigin},
   delta_angle,
   engine::Bundle,
-  inverse_degrees, move_point, normalize_angle,
+  intercept_heading, inverse_degrees, mach_to_knots, move_point,
+  normalize_angle,
   pathfinder::{NodeBehavior, NodeKind},
-  Line, KNOT_TO_FEET_PER_SECOND, NAUTICALMILES_TO_FEET,
+  turn_anticipation, turn_radius, Line, KNOT_TO_FEET_PER_SECOND,
+  NAUTICALMILES_TO_FEET,
 };
 
 use super::{
+  braking_speed,
   events::{AircraftEvent, EventKind},
-  Aircraft, AircraftState, LandingState,
+  turn_speed, Aircraft, AircraftState, ApproachMode, DeferredTarget,
+  FlightSegment, HoldLeg, LandingState, NavMode, TaxiStopReason,
+  HOLD_SHORT_DISTANCE, TAXI_SPEED,
 };
 
+/// The altitude we level off at to circle to another runway, in feet.
+const CIRCLING_ALTITUDE: f32 = 1000.0;
+/// How far out from the runway we join the final when circling, in feet.
+const CIRCLING_FINAL: f32 = NAUTICALMILES_TO_FEET * 4.0;
+/// How far to the side of the final we turn base when circling, in feet.
+const CIRCLING_BASE: f32 = NAUTICALMILES_TO_FEET * 3.0;
+
 pub trait AircraftEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle);
 }
@@ -25,8 +36,24 @@ impl AircraftEffect for AircraftUpdateFromTargetsEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
     let dt = aircraft.dt_enroute(bundle.dt);
 
+    // A Mach number is a faster true airspeed the lower we are, but never
+    // faster than we can fly. Once we are no longer flying, it is dropped.
+    if let Some(mach) = aircraft.target.mach {
+      if let AircraftState::Flying { .. } = aircraft.state {
+        let max_speed = aircraft.kind.stats().max_speed;
+        aircraft.target.speed =
+          mach_to_knots(mach, aircraft.altitude).min(max_speed);
+      } else {
+        aircraft.target.mach = None;
+      }
+    }
+
     // In feet per second
-    let climb_speed = aircraft.dt_climb_speed(dt);
+    let climb_speed = if aircraft.target.altitude > aircraft.altitude {
+      aircraft.dt_climb_speed(dt)
+    } else {
+      aircraft.dt_descent_speed(dt)
+    };
     // In degrees per second
     let turn_speed = aircraft.dt_turn_speed(dt);
     // In knots per second
@@ -83,15 +110,263 @@ impl AircraftEffect for AircraftUpdateFromTargetsEffect {
   }
 }
 
+pub struct AircraftUpdateLevelOffEffect;
+impl AircraftEffect for AircraftUpdateLevelOffEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+      return;
+    }
+
+    let cruise = aircraft.flight_plan.altitude;
+    if aircraft.target.altitude != cruise || bundle.prev.altitude == cruise {
+      return;
+    }
+
+    let climbed_through =
+      bundle.prev.altitude < cruise && aircraft.altitude >= cruise;
+    let descended_through =
+      bundle.prev.altitude > cruise && aircraft.altitude <= cruise;
+
+    if climbed_through || descended_through {
+      // Snap to cruise so we don't overshoot and hunt back and forth.
+      aircraft.altitude = cruise;
+      aircraft.target.altitude = cruise;
+
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            CommandReply::LevelOff { altitude: cruise },
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
+    }
+  }
+}
+
+pub struct AircraftUpdateMinSafeAltitudeEffect;
+impl AircraftEffect for AircraftUpdateMinSafeAltitudeEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let airspace = &bundle.world.airspace;
+    let Some(min_safe_altitude) = airspace.min_safe_altitude else {
+      return;
+    };
+
+    // Aircraft on approach are expected to descend below the MSA.
+    if !matches!(aircraft.state, AircraftState::Flying { .. })
+      || !airspace.contains_point(aircraft.pos)
+    {
+      return;
+    }
+
+    // Only warn as we descend through the MSA, not on every tick below it.
+    if bundle.prev.altitude >= min_safe_altitude
+      && aircraft.altitude < min_safe_altitude
+    {
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(
+            CommandWithFreq::new(
+              aircraft.id.to_string(),
+              aircraft.frequency,
+              CommandReply::LowAltitude {
+                altitude: min_safe_altitude,
+              },
+              Vec::new(),
+            )
+            .with_origin(MessageOrigin::System),
+          ),
+        )
+        .into(),
+      );
+    }
+  }
+}
+
+pub struct AircraftUpdateTransponderEffect;
+impl AircraftEffect for AircraftUpdateTransponderEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    if aircraft
+      .ident_until
+      .is_some_and(|until| bundle.now >= until)
+    {
+      aircraft.ident_until = None;
+    }
+  }
+}
+
+pub struct AircraftUpdateBoardingEffect;
+impl AircraftEffect for AircraftUpdateBoardingEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    if !matches!(aircraft.state, AircraftState::Parked { active: true, .. }) {
+      return;
+    }
+
+    if aircraft
+      .boarding_until
+      .is_some_and(|until| bundle.now >= until)
+    {
+      aircraft.boarding_until = None;
+
+      let airport = aircraft.flight_plan.arriving.to_string();
+      let reply = if aircraft.needs_clearance(&bundle.world.airspace) {
+        CommandReply::RequestClearance { airport }
+      } else {
+        CommandReply::ReadyForDeparture { airport }
+      };
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            reply,
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
+    }
+  }
+}
+
+pub struct AircraftUpdateLineUpTimeoutEffect;
+impl AircraftEffect for AircraftUpdateLineUpTimeoutEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let runway = match &aircraft.state {
+      AircraftState::Taxiing { current, .. }
+        if current.kind == NodeKind::Runway
+          && current.behavior == NodeBehavior::LineUp =>
+      {
+        current.name
+      }
+      _ => {
+        aircraft.lined_up_until = None;
+        return;
+      }
+    };
+
+    if aircraft
+      .lined_up_until
+      .is_some_and(|until| bundle.now >= until)
+    {
+      aircraft.lined_up_until = None;
+
+      let vacating = bundle.world.airspace.vacate_after_line_up_timeout;
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            CommandReply::LineUpTimeout {
+              runway: runway.to_string(),
+              vacating,
+            },
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
+
+      if vacating {
+        bundle.events.push(
+          AircraftEvent::new(aircraft.id, EventKind::VacateRunway).into(),
+        );
+      }
+    }
+  }
+}
+
+pub struct AircraftUpdateFuelEffect;
+impl AircraftEffect for AircraftUpdateFuelEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let segment = aircraft.segment(&bundle.world.airspace);
+    let flow = aircraft.fuel_flow(segment);
+    let cruise_flow = aircraft.fuel_flow(FlightSegment::Enroute);
+
+    let dt = aircraft.dt_enroute(bundle.dt);
+    let before = aircraft.fuel;
+    aircraft.fuel = (aircraft.fuel - flow * dt).max(0.0);
+
+    let reserve =
+      cruise_flow * bundle.world.airspace.fuel_reserve().as_secs_f32();
+    let mut callout = None;
+    if before > reserve && aircraft.fuel <= reserve {
+      callout = Some(CommandReply::MinimumFuel {
+        minutes: aircraft.fuel / cruise_flow / 60.0,
+      });
+    }
+
+    if before > 0.0 && aircraft.fuel == 0.0 {
+      callout = Some(CommandReply::FuelExhausted);
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::ResumeOwnNavigation { diversion: true },
+        )
+        .into(),
+      );
+    }
+
+    if let Some(reply) = callout {
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            reply,
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
+    }
+  }
+}
+
+pub struct AircraftUpdateDeferredTargetsEffect;
+impl AircraftEffect for AircraftUpdateDeferredTargetsEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let now = bundle.now;
+    let due =
+      |deferred: &Option<DeferredTarget>| deferred.is_some_and(|d| now >= d.at);
+
+    if due(&aircraft.deferred_altitude) {
+      if let Some(deferred) = aircraft.deferred_altitude.take() {
+        bundle.events.push(
+          AircraftEvent::new(aircraft.id, EventKind::Altitude(deferred.value))
+            .into(),
+        );
+      }
+    }
+    if due(&aircraft.deferred_speed) {
+      if let Some(deferred) = aircraft.deferred_speed.take() {
+        bundle.events.push(
+          AircraftEvent::new(aircraft.id, EventKind::Speed(deferred.value))
+            .into(),
+        );
+      }
+    }
+  }
+}
+
 pub struct AircraftUpdatePositionEffect;
 impl AircraftEffect for AircraftUpdatePositionEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
     let dt = aircraft.dt_enroute(bundle.dt);
 
+    let ground_speed = aircraft.ground_speed(bundle.world.airspace.wind);
     let pos = move_point(
       aircraft.pos,
       aircraft.heading,
-      aircraft.speed * KNOT_TO_FEET_PER_SECOND * dt,
+      ground_speed * KNOT_TO_FEET_PER_SECOND * dt,
     );
 
     if pos != aircraft.pos {
@@ -100,14 +375,28 @@ impl AircraftEffect for AircraftUpdatePositionEffect {
   }
 }
 
+/// The wind along and across the runway that we are landing on, in knots.
+#[derive(Debug, Clone, Copy)]
+struct RunwayWind {
+  headwind: f32,
+  crosswind: f32,
+}
+
 pub struct AircraftUpdateLandingEffect;
 impl AircraftUpdateLandingEffect {
-  fn state_before_turn(aircraft: &mut Aircraft, _: &mut Bundle, dt: f32) {
-    let degrees_per_sec = aircraft.dt_turn_speed(dt);
+  fn state_before_turn(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let radius = turn_radius(
+      aircraft.ground_speed(bundle.world.airspace.wind),
+      aircraft.dt_turn_speed(1.0),
+    );
     let AircraftState::Landing { runway, state } = &mut aircraft.state else {
       unreachable!("outer function asserts that aircraft is landing")
     };
 
+    if *state == LandingState::Circling {
+      return;
+    }
+
     let ils_line = Line::new(
       move_point(runway.end(), runway.heading, 500.0),
       move_point(
@@ -117,16 +406,11 @@ impl AircraftUpdateLandingEffect {
       ),
     );
 
-    let turning_radius = 360.0 / degrees_per_sec;
-    let turning_radius =
-      turning_radius * aircraft.speed * KNOT_TO_FEET_PER_SECOND * dt;
-    let turning_radius = turning_radius / (2.0 * PI);
-    let turning_radius = turning_radius * 2.0;
-
-    let delta_ang = delta_angle(aircraft.heading, runway.heading);
-    let percent_of = delta_ang.abs() / 180.0;
-    let percent_of = (percent_of * PI + PI * 1.5).sin() / 2.0 + 0.5;
-    let turn_distance = turning_radius * percent_of;
+    // Start the turn onto the final early enough to roll out on it.
+    let turn_distance = turn_anticipation(
+      radius,
+      delta_angle(aircraft.heading, runway.heading).abs(),
+    );
     let turn_distance = turn_distance.powf(2.0);
 
     let closest_point =
@@ -150,13 +434,13 @@ impl AircraftUpdateLandingEffect {
       && (angle_to_runway.round() != runway.heading
         || distance_to_point.round() != 0.0)
     {
-      if angle_to_runway > runway.heading {
-        aircraft.target.heading = add_degrees(runway.heading, 20.0);
-      }
-
-      if angle_to_runway < runway.heading {
-        aircraft.target.heading = add_degrees(runway.heading, -20.0);
-      }
+      // The ILS line runs outwards from the runway, so flip it to intercept
+      // it inbound.
+      aircraft.target.heading = intercept_heading(
+        aircraft.pos,
+        Line::new(ils_line.1, ils_line.0),
+        20.0,
+      );
 
       *state = LandingState::Correcting;
     }
@@ -194,7 +478,11 @@ impl AircraftUpdateLandingEffect {
     }
   }
 
-  fn state_go_around(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+  fn state_go_around(
+    aircraft: &mut Aircraft,
+    bundle: &mut Bundle,
+    wind: RunwayWind,
+  ) {
     let AircraftState::Landing { runway, state } = &mut aircraft.state else {
       unreachable!("outer function asserts that aircraft is landing")
     };
@@ -206,35 +494,52 @@ impl AircraftUpdateLandingEffect {
     let distance_to_runway = aircraft.pos.distance(runway.start());
     let target_altitude = calculate_ils_altitude(distance_to_runway);
 
-    // If we are too high, go around.
-    if aircraft.altitude - target_altitude > 100.0 {
-      bundle.events.push(
-        AircraftEvent {
-          id: aircraft.id,
-          kind: EventKind::GoAround,
-        }
-        .into(),
-      );
-      bundle.events.push(
-        AircraftEvent {
-          id: aircraft.id,
-          kind: EventKind::Callout(CommandWithFreq::new(
-            aircraft.id.to_string(),
-            aircraft.frequency,
-            CommandReply::GoAround {
-              runway: runway.id.to_string(),
-            },
-            vec![],
-          )),
-        }
-        .into(),
-      );
+    // When circling, the wind matters for the runway we break off to.
+    let airspace = &bundle.world.airspace;
+    let tailwind = aircraft.circle_to_land.is_none()
+      && -wind.headwind > airspace.max_tailwind();
+    let crosswind = aircraft.circle_to_land.is_none()
+      && wind.crosswind > aircraft.kind.stats().max_crosswind;
+
+    // If we are too high, or the wind is too far behind or across us, go
+    // around.
+    let reply = if crosswind {
+      CommandReply::GoAroundCrosswind {
+        runway: runway.id.to_string(),
+      }
+    } else if aircraft.altitude - target_altitude > 100.0 || tailwind {
+      CommandReply::GoAround {
+        runway: runway.id.to_string(),
+        heading: runway.magnetic_heading(bundle.world.magnetic_variation),
+      }
+    } else {
+      return;
+    };
 
-      *state = LandingState::GoAround;
-    }
+    bundle.events.push(
+      AircraftEvent {
+        id: aircraft.id,
+        kind: EventKind::GoAround,
+      }
+      .into(),
+    );
+    bundle.events.push(
+      AircraftEvent {
+        id: aircraft.id,
+        kind: EventKind::Callout(CommandWithFreq::new(
+          aircraft.id.to_string(),
+          aircraft.frequency,
+          reply,
+          vec![],
+        )),
+      }
+      .into(),
+    );
+
+    *state = LandingState::GoAround;
   }
 
-  fn state_glideslope(aircraft: &mut Aircraft, dt: f32) {
+  fn state_glideslope(aircraft: &mut Aircraft, wind: RunwayWind, dt: f32) {
     let climb_speed = aircraft.dt_climb_speed(dt);
 
     let AircraftState::Landing { runway, state } = &mut aircraft.state else {
@@ -257,7 +562,9 @@ impl AircraftUpdateLandingEffect {
     let seconds_for_descent = aircraft.altitude / (climb_speed / dt);
 
     let target_speed_ft_s = distance_to_runway / seconds_for_descent;
-    let target_knots = target_speed_ft_s / KNOT_TO_FEET_PER_SECOND;
+    // Fly fast enough to cover the ground we need to, despite the wind.
+    let target_knots =
+      target_speed_ft_s / KNOT_TO_FEET_PER_SECOND + wind.headwind;
 
     let target_altitude = calculate_ils_altitude(distance_to_runway);
 
@@ -276,17 +583,170 @@ impl AircraftUpdateLandingEffect {
       }
     }
   }
+
+  fn state_circle_break(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let AircraftState::Landing { state, .. } = &aircraft.state else {
+      unreachable!("outer function asserts that aircraft is landing")
+    };
+
+    if !matches!(state, LandingState::Localizer | LandingState::Glideslope)
+      || aircraft.altitude > CIRCLING_ALTITUDE
+    {
+      return;
+    }
+
+    let Some(land_runway) = aircraft.circle_to_land.take() else {
+      return;
+    };
+
+    if let Some(runway) = bundle
+      .world
+      .airspace
+      .airports
+      .iter()
+      .flat_map(|a| a.runways.iter())
+      .find(|r| r.id == land_runway)
+    {
+      aircraft.target.altitude = CIRCLING_ALTITUDE;
+      aircraft.target.speed = aircraft.speed;
+      aircraft.state = AircraftState::Landing {
+        runway: runway.clone(),
+        state: LandingState::Circling,
+      };
+    }
+  }
+
+  fn state_circling(aircraft: &mut Aircraft) {
+    let AircraftState::Landing { runway, state } = &mut aircraft.state else {
+      unreachable!("outer function asserts that aircraft is landing")
+    };
+
+    if *state != LandingState::Circling {
+      return;
+    }
+
+    let outbound = inverse_degrees(runway.heading);
+    let final_fix = move_point(runway.start(), outbound, CIRCLING_FINAL);
+
+    // Turn base from whichever side of the final we are on.
+    let side = if delta_angle(
+      runway.heading,
+      angle_between_points(final_fix, aircraft.pos),
+    ) < 0.0
+    {
+      -90.0
+    } else {
+      90.0
+    };
+    let base_fix = move_point(
+      final_fix,
+      normalize_angle(runway.heading + side),
+      CIRCLING_BASE,
+    );
+    let base_heading = angle_between_points(base_fix, final_fix);
+
+    // How far we are past the final fix, along the extended centerline.
+    let past_final =
+      (aircraft.pos - final_fix).dot(move_point(Vec2::ZERO, outbound, 1.0));
+
+    if past_final < -NAUTICALMILES_TO_FEET * 0.5 {
+      // Downwind
+      aircraft.target.heading = angle_between_points(aircraft.pos, base_fix);
+    } else {
+      // Base, until we are heading for the final and the approach can take
+      // over.
+      aircraft.target.heading = base_heading;
+      if delta_angle(aircraft.heading, base_heading).abs() < 5.0 {
+        *state = LandingState::BeforeTurn;
+      }
+    }
+  }
 }
 
 impl AircraftEffect for AircraftUpdateLandingEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
     let dt = aircraft.dt_enroute(bundle.dt);
 
-    if let AircraftState::Landing { .. } = &aircraft.state {
+    if let AircraftState::Landing { runway, .. } = &aircraft.state {
+      let airspace = &bundle.world.airspace;
+      let wind = RunwayWind {
+        headwind: airspace.headwind(runway.heading),
+        crosswind: airspace.crosswind(runway.heading),
+      };
+
       Self::state_touchdown(aircraft, bundle);
-      Self::state_go_around(aircraft, bundle);
-      Self::state_before_turn(aircraft, bundle, dt);
-      Self::state_glideslope(aircraft, dt);
+      Self::state_go_around(aircraft, bundle, wind);
+      // Going around takes over from the approach until it is handled.
+      if let AircraftState::Landing {
+        state: LandingState::GoAround,
+        ..
+      } = aircraft.state
+      {
+        return;
+      }
+      Self::state_circle_break(aircraft, bundle);
+      Self::state_circling(aircraft);
+      Self::state_before_turn(aircraft, bundle);
+      Self::state_glideslope(aircraft, wind, dt);
+    }
+  }
+}
+
+/// Calls out the approach lights coming into view on final, and decision
+/// height once we are low enough to commit to the landing.
+pub struct AircraftUpdateApproachCalloutsEffect;
+impl AircraftEffect for AircraftUpdateApproachCalloutsEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    let AircraftState::Landing { runway, state } = &aircraft.state else {
+      return;
+    };
+
+    if *state != LandingState::Glideslope {
+      return;
+    }
+
+    let airspace = &bundle.world.airspace;
+    let threshold = runway.start();
+    let prev_distance = bundle.prev.pos.distance(threshold);
+    let distance = aircraft.pos.distance(threshold);
+
+    // The lights come into view at the published distance, or closer in poor
+    // visibility.
+    let in_sight = |distance: f32| {
+      distance <= runway.minimums.sight_distance
+        && airspace.is_visible(distance)
+    };
+
+    let decision_height = runway.minimums.decision_height;
+    let reached_minimums = bundle.prev.altitude > decision_height
+      && aircraft.altitude <= decision_height
+      && in_sight(distance);
+
+    let mut replies = Vec::new();
+    if !in_sight(prev_distance) && in_sight(distance) {
+      replies.push(CommandReply::RunwayInSight {
+        runway: runway.id.to_string(),
+      });
+    }
+    if reached_minimums {
+      replies.push(CommandReply::Minimums {
+        runway: runway.id.to_string(),
+      });
+    }
+
+    for reply in replies {
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            reply,
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
     }
   }
 }
@@ -294,13 +754,20 @@ impl AircraftEffect for AircraftUpdateLandingEffect {
 pub struct AircraftUpdateFlyingEffect;
 impl AircraftEffect for AircraftUpdateFlyingEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
-    if aircraft.altitude < 2000.0 {
+    // Our hold steers us instead, until we leave it.
+    if aircraft.altitude < 2000.0 || aircraft.hold.is_some() {
       return;
     }
 
     let dt = aircraft.dt_enroute(bundle.dt);
     let speed_in_feet = aircraft.speed * KNOT_TO_FEET_PER_SECOND * dt;
-    if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+    if let AircraftState::Flying { waypoints, enroute } = &mut aircraft.state {
+      // Inside the airspace, wait for vectors instead of flying our route.
+      if !*enroute && aircraft.approach_mode == ApproachMode::Vectors {
+        aircraft.nav_mode = NavMode::Heading;
+        return;
+      }
+
       if let Some(current) = waypoints.last() {
         let heading = angle_between_points(aircraft.pos, current.value.to);
 
@@ -325,10 +792,117 @@ impl AircraftEffect for AircraftUpdateFlyingEffect {
   }
 }
 
+/// Flies the legs of our holding pattern, turning the way the hold is flown
+/// rather than the shortest way.
+pub struct AircraftUpdateHoldingEffect;
+impl AircraftEffect for AircraftUpdateHoldingEffect {
+  fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+    if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+      aircraft.hold = None;
+      return;
+    }
+
+    let dt = aircraft.dt_enroute(bundle.dt);
+    let speed_in_feet = aircraft.speed * KNOT_TO_FEET_PER_SECOND * dt;
+    let Some(hold) = &mut aircraft.hold else {
+      return;
+    };
+    let sign = match hold.direction {
+      HoldDirection::Right => 1.0,
+      HoldDirection::Left => -1.0,
+    };
+    // Steering 90 degrees at a time keeps us turning the way of the hold
+    // until we are close enough to take the shortest way.
+    let turn_to = |current: f32, heading: f32| {
+      if delta_angle(current, heading).abs() < 90.0 {
+        heading
+      } else {
+        normalize_angle(current + sign * 90.0)
+      }
+    };
+
+    let to_fix = angle_between_points(aircraft.pos, hold.pos);
+    let at_fix =
+      aircraft.pos.distance_squared(hold.pos) <= speed_in_feet.powf(2.0);
+    match hold.leg {
+      HoldLeg::Entry => {
+        aircraft.target.heading = to_fix;
+        if at_fix {
+          hold.leg = HoldLeg::Outbound;
+          hold.elapsed = 0.0;
+        }
+      }
+      HoldLeg::Outbound => {
+        let outbound = inverse_degrees(hold.inbound_course);
+        aircraft.target.heading = turn_to(aircraft.heading, outbound);
+
+        // The outbound leg is timed once we have rolled out of the turn.
+        if delta_angle(aircraft.heading, outbound).abs() < 1.0 {
+          hold.elapsed += dt;
+          if hold.elapsed >= hold.leg_seconds {
+            hold.leg = HoldLeg::Inbound;
+          }
+        }
+      }
+      HoldLeg::Inbound => {
+        aircraft.target.heading = turn_to(aircraft.heading, to_fix);
+        if at_fix {
+          hold.leg = HoldLeg::Outbound;
+          hold.elapsed = 0.0;
+        }
+      }
+    }
+  }
+}
+
+/// Brakes after touching down so that we turn off onto our exit no faster
+/// than we can make the turn, and are down to taxi speed once clear of the
+/// runway.
+pub struct AircraftUpdateRolloutEffect;
+impl AircraftEffect for AircraftUpdateRolloutEffect {
+  fn run(aircraft: &mut Aircraft, _: &mut Bundle) {
+    if aircraft.altitude > 0.0 || aircraft.speed <= TAXI_SPEED {
+      return;
+    }
+
+    let AircraftState::Taxiing { waypoints, .. } = &aircraft.state else {
+      return;
+    };
+
+    // Waypoints are flown from the back.
+    let mut points = waypoints.iter().rev().map(|w| w.value).peekable();
+    let mut from = aircraft.pos;
+    let mut distance = 0.0;
+    let mut target = aircraft.speed;
+    while let Some(point) = points.next() {
+      distance += from.distance(point);
+
+      // Slow down enough to make the turn at each waypoint, and to be at
+      // taxi speed by the last.
+      let final_speed = match points.peek() {
+        Some(next) => turn_speed(delta_angle(
+          angle_between_points(from, point),
+          angle_between_points(point, *next),
+        )),
+        None => TAXI_SPEED,
+      };
+      target = target.min(braking_speed(distance, final_speed));
+      from = point;
+    }
+
+    // With nowhere left to turn off, stop where we are.
+    aircraft.target.speed = if waypoints.is_empty() { 0.0 } else { target };
+  }
+}
+
 pub struct AircraftUpdateTaxiingEffect;
 impl AircraftEffect for AircraftUpdateTaxiingEffect {
   fn run(aircraft: &mut Aircraft, bundle: &mut Bundle) {
     let speed_in_feet = aircraft.speed * KNOT_TO_FEET_PER_SECOND * bundle.dt;
+    if aircraft.speed > 0.0 {
+      aircraft.stop_reason = None;
+    }
+
     if let AircraftState::Taxiing {
       waypoints, current, ..
     } = &mut aircraft.state
@@ -350,10 +924,15 @@ impl AircraftEffect for AircraftUpdateTaxiingEffect {
         }
         // Only hold if we are not stopped and we are at or below taxi speed.
       } else if aircraft.speed > 0.0 && aircraft.speed <= 20.0 {
+        let reason = (current.behavior == NodeBehavior::HoldShort)
+          .then_some(TaxiStopReason::HoldShort);
         bundle.events.push(
           AircraftEvent {
             id: aircraft.id,
-            kind: EventKind::TaxiHold { and_state: true },
+            kind: EventKind::TaxiHold {
+              and_state: true,
+              reason,
+            },
           }
           .into(),
         );
@@ -362,6 +941,8 @@ impl AircraftEffect for AircraftUpdateTaxiingEffect {
           NodeBehavior::GoTo => {}
           NodeBehavior::HoldShort => {}
           NodeBehavior::Park => {
+            // Our clearance was for the flight that we just finished.
+            aircraft.ifr_cleared = false;
             aircraft.state = AircraftState::Parked {
               at: current.clone(),
               // Only become inactive if we are arriving at the player's airspace.
@@ -380,6 +961,11 @@ impl AircraftEffect for AircraftUpdateTaxiingEffect {
           // Runway specific
           NodeBehavior::LineUp => {
             if current.kind == NodeKind::Runway {
+              if aircraft.lined_up_until.is_none() {
+                aircraft.lined_up_until =
+                  Some(bundle.now + bundle.world.airspace.line_up_timeout());
+              }
+
               if let Some(runway) = bundle
                 .world
                 .airspace
@@ -417,11 +1003,14 @@ impl AircraftEffect for AircraftUpdateTaxiingEffect {
           NodeBehavior::GoTo => {}
           NodeBehavior::Park => {}
           NodeBehavior::HoldShort => {
-            if distance <= 250.0_f32.powf(2.0) {
+            if distance <= HOLD_SHORT_DISTANCE.powf(2.0) {
               bundle.events.push(
                 AircraftEvent {
                   id: aircraft.id,
-                  kind: EventKind::TaxiHold { and_state: true },
+                  kind: EventKind::TaxiHold {
+                    and_state: true,
+                    reason: Some(TaxiStopReason::HoldShort),
+                  },
                 }
                 .into(),
               );
@@ -443,3 +1032,1014 @@ impl AircraftEffect for AircraftUpdateTaxiingEffect {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use internment::Intern;
+  use turborand::{rng::Rng, SeededCore};
+
+  use crate::{
+    command::Task,
+    engine::{Engine, Event},
+    entities::{
+      aircraft::{
+        AircraftKind, FlightPlan, TaxiingState, BOARDING_DURATION,
+        HIGH_SPEED_EXIT_SPEED, ROLLOUT_DECELERATION,
+      },
+      airport::{Airport, Runway, Taxiway},
+      airspace::{Airspace, Wind},
+      world::{Connection, ConnectionState, Game, World},
+    },
+    pathfinder::{new_vor, Node},
+  };
+
+  use super::*;
+
+  #[test]
+  fn climb_to_cruise_levels_off_once() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 2500.0,
+      flight_plan: FlightPlan {
+        altitude: 3000.0,
+        ..Default::default()
+      },
+      ..Default::default()
+    }
+    .with_synced_targets();
+    aircraft.target.altitude = 3000.0;
+    game.push_aircraft(aircraft);
+
+    let mut level_offs = 0;
+    let mut altitudes = Vec::new();
+    for _ in 0..60 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      level_offs += events
+        .iter()
+        .filter(|e| {
+          matches!(
+            e,
+            Event::Aircraft(AircraftEvent {
+              kind: EventKind::Callout(CommandWithFreq {
+                reply: CommandReply::LevelOff { .. },
+                ..
+              }),
+              ..
+            })
+          )
+        })
+        .count();
+      altitudes.push(game.aircraft()[0].altitude);
+    }
+
+    assert_eq!(level_offs, 1);
+    assert!(altitudes.iter().all(|a| *a <= 3000.0));
+    assert!(altitudes.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(game.aircraft()[0].altitude, 3000.0);
+  }
+
+  #[test]
+  fn descending_below_msa_warns_once() {
+    let world = World {
+      airspace: Airspace {
+        radius: NAUTICALMILES_TO_FEET * 30.0,
+        min_safe_altitude: Some(3000.0),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 4000.0,
+      state: AircraftState::Flying {
+        waypoints: Vec::new(),
+        enroute: true,
+      },
+      ..Default::default()
+    }
+    .with_synced_targets();
+    aircraft.target.altitude = 1000.0;
+    game.push_aircraft(aircraft);
+
+    let mut warnings = 0;
+    for _ in 0..60 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      warnings += events
+        .iter()
+        .filter(|e| {
+          matches!(
+            e,
+            Event::Aircraft(AircraftEvent {
+              kind: EventKind::Callout(CommandWithFreq {
+                reply: CommandReply::LowAltitude { .. },
+                origin: MessageOrigin::System,
+                ..
+              }),
+              ..
+            })
+          )
+        })
+        .count();
+    }
+
+    assert_eq!(game.aircraft()[0].altitude, 1000.0);
+    assert_eq!(warnings, 1);
+  }
+
+  #[test]
+  fn base_turn_rolls_out_on_final() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // On a base leg south of the final for runway 27, twelve miles out.
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: Vec2::new(
+          3500.0 + NAUTICALMILES_TO_FEET * 12.0,
+          -NAUTICALMILES_TO_FEET * 3.0,
+        ),
+        heading: 0.0,
+        speed: 180.0,
+        altitude: 4000.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::Land(Intern::from_ref("27"))).into(),
+    );
+
+    let mut cross_track = None;
+    for _ in 0..2000 {
+      engine.tick(&world, &mut game, &mut rng, 0.25, Duration::ZERO);
+      let aircraft = game.aircraft_by_id(id).unwrap();
+      if delta_angle(aircraft.heading, 270.0).abs() < 1.0 {
+        cross_track = Some(aircraft.pos.y.abs());
+        break;
+      }
+    }
+
+    let cross_track = cross_track.expect("never turned onto the final");
+    assert!(cross_track < 200.0, "rolled out {cross_track} feet off");
+  }
+
+  #[test]
+  fn circle_to_land_on_another_runway() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    for (id, heading) in [("9", 90.0), ("27", 270.0)] {
+      airport.add_runway(Runway {
+        id: Intern::from_ref(id),
+        heading,
+        length: 7000.0,
+        ..Default::default()
+      });
+    }
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // Four miles out on the final for runway 9, just below the glideslope.
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: Vec2::new(-3500.0 - NAUTICALMILES_TO_FEET * 4.0, 0.0),
+        heading: 90.0,
+        speed: 160.0,
+        altitude: 2900.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine.events.push(
+      AircraftEvent::new(
+        id,
+        EventKind::CircleToLand {
+          approach_runway: Intern::from_ref("9"),
+          land_runway: Intern::from_ref("27"),
+        },
+      )
+      .into(),
+    );
+
+    let mut descended_on_approach = false;
+    let mut circled = false;
+    let mut lined_up = false;
+    for _ in 0..2000 {
+      engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+      let aircraft = game.aircraft_by_id(id).unwrap();
+      let AircraftState::Landing { runway, state } = &aircraft.state else {
+        continue;
+      };
+
+      match (runway.id.as_str(), state) {
+        ("9", LandingState::Glideslope) => {
+          assert!(aircraft.pos.y.abs() < 100.0);
+          descended_on_approach |= aircraft.altitude < 2000.0;
+        }
+        ("27", LandingState::Circling) => {
+          assert!(descended_on_approach);
+          assert!(aircraft.altitude >= CIRCLING_ALTITUDE - 50.0);
+          circled = true;
+        }
+        ("27", LandingState::Localizer | LandingState::Glideslope) => {
+          assert!(circled);
+          lined_up = true;
+          break;
+        }
+        _ => {}
+      }
+    }
+
+    assert!(lined_up);
+  }
+
+  #[test]
+  fn approach_callouts_in_good_visibility() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("9"),
+      heading: 90.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        visibility: Some(10.0),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    // Four miles out on the final for runway 9, just below the glideslope.
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: Vec2::new(-3500.0 - NAUTICALMILES_TO_FEET * 4.0, 0.0),
+        heading: 90.0,
+        speed: 160.0,
+        altitude: 2900.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::Land(Intern::from_ref("9"))).into(),
+    );
+
+    let mut callouts = Vec::new();
+    for _ in 0..2000 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+      for event in events {
+        if let Event::Aircraft(AircraftEvent {
+          kind: EventKind::Callout(CommandWithFreq { reply, .. }),
+          ..
+        }) = event
+        {
+          if matches!(
+            reply,
+            CommandReply::RunwayInSight { .. } | CommandReply::Minimums { .. }
+          ) {
+            callouts.push(reply);
+          }
+        }
+      }
+
+      let aircraft = game.aircraft_by_id(id).unwrap();
+      if !matches!(aircraft.state, AircraftState::Landing { .. })
+        || aircraft.altitude <= 0.0
+      {
+        break;
+      }
+    }
+
+    let runway = "9".to_string();
+    assert_eq!(
+      callouts,
+      vec![
+        CommandReply::RunwayInSight {
+          runway: runway.clone()
+        },
+        CommandReply::Minimums { runway },
+      ]
+    );
+  }
+
+  #[test]
+  fn landing_rolls_out_onto_high_speed_exit() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("9"),
+      heading: 90.0,
+      length: 8000.0,
+      ..Default::default()
+    });
+    // Too close to make at landing speed.
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("A"),
+      Vec2::new(-1000.0, -500.0),
+      Vec2::new(-1000.0, 500.0),
+    ));
+    // A high-speed exit, angled off the runway.
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("B"),
+      Vec2::new(800.0, -30.0),
+      Vec2::new(2300.0, 970.0),
+    ));
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("C"),
+      Vec2::new(3000.0, -500.0),
+      Vec2::new(3000.0, 500.0),
+    ));
+    airport.calculate_waypoints();
+    let runway = airport.runways[0].clone();
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: Vec2::new(-3900.0, 0.0),
+        heading: 90.0,
+        speed: 140.0,
+        state: AircraftState::Landing {
+          runway,
+          state: LandingState::Glideslope,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine
+      .events
+      .push(AircraftEvent::new(id, EventKind::Touchdown).into());
+
+    let mut exit_speed = None;
+    for _ in 0..2000 {
+      engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+      let aircraft = game.aircraft_by_id(id).unwrap();
+      let AircraftState::Taxiing {
+        current, waypoints, ..
+      } = &aircraft.state
+      else {
+        continue;
+      };
+
+      if current.kind == NodeKind::Taxiway {
+        exit_speed.get_or_insert(aircraft.speed);
+      }
+      if waypoints.is_empty() && aircraft.speed == 0.0 {
+        break;
+      }
+    }
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    let AircraftState::Taxiing { current, .. } = &aircraft.state else {
+      panic!("expected to be taxiing, but was {:?}", aircraft.state);
+    };
+    assert_eq!(current.name.as_str(), "B");
+    assert_eq!(aircraft.speed, 0.0);
+    // Clear of the runway, not stopped on it.
+    assert!(aircraft.pos.y > 100.0, "stopped at {}", aircraft.pos);
+    // Braking lags the target by up to a tick, and the exit is reached
+    // within a tick of the waypoint, so allow for a second of braking.
+    assert!(exit_speed.is_some_and(|s| {
+      s > TAXI_SPEED && s <= HIGH_SPEED_EXIT_SPEED + ROLLOUT_DECELERATION
+    }));
+  }
+
+  #[test]
+  fn ready_callout_at_end_of_boarding() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(Aircraft {
+      id,
+      state: AircraftState::Parked {
+        at: Node::new(
+          Intern::from_ref("A1"),
+          NodeKind::Gate,
+          NodeBehavior::Park,
+          Vec2::ZERO,
+        ),
+        active: true,
+      },
+      boarding_until: Some(BOARDING_DURATION),
+      ..Default::default()
+    });
+
+    let ready = |events: &[Event]| {
+      events.iter().any(|e| {
+        matches!(
+          e,
+          Event::Aircraft(AircraftEvent {
+            kind: EventKind::Callout(CommandWithFreq {
+              reply: CommandReply::ReadyForDeparture { .. },
+              ..
+            }),
+            ..
+          })
+        )
+      })
+    };
+
+    let mut now = Duration::ZERO;
+    while now < BOARDING_DURATION {
+      let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+      assert!(!ready(&events), "called ready at {now:?}");
+      assert!(game.aircraft_by_id(id).unwrap().is_boarding());
+      now += Duration::from_secs(1);
+    }
+
+    let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+    assert!(ready(&events));
+    assert!(!game.aircraft_by_id(id).unwrap().is_boarding());
+
+    // It only calls once.
+    let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+    assert!(!ready(&events));
+  }
+
+  #[test]
+  fn vectored_arrivals_wait_for_headings() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let route = vec![new_vor(Intern::from_ref("FIX"), Vec2::new(20000.0, 0.0))];
+    for (id, approach_mode) in [
+      ("AAL1234", ApproachMode::PilotNav),
+      ("SKW5678", ApproachMode::Vectors),
+    ] {
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          speed: 250.0,
+          altitude: 7000.0,
+          state: AircraftState::Flying {
+            waypoints: route.clone(),
+            enroute: false,
+          },
+          approach_mode,
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+    }
+    let pilot_nav = Intern::from_ref("AAL1234");
+    let vectors = Intern::from_ref("SKW5678");
+
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+
+    // One follows the route, and the other holds its heading with the route
+    // kept for later.
+    assert_eq!(game.aircraft_by_id(pilot_nav).unwrap().target.heading, 90.0);
+    let aircraft = game.aircraft_by_id(vectors).unwrap();
+    assert_eq!(aircraft.target.heading, 0.0);
+    assert_eq!(aircraft.nav_mode, NavMode::Heading);
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Flying { waypoints, .. } if waypoints.len() == 1
+    ));
+
+    engine
+      .events
+      .push(AircraftEvent::new(vectors, EventKind::Heading(180.0)).into());
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    assert_eq!(game.aircraft_by_id(vectors).unwrap().target.heading, 180.0);
+  }
+
+  #[test]
+  fn reminder_when_lined_up_too_long() {
+    let world = World {
+      airspace: Airspace {
+        line_up_timeout: Some(30.0),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        speed: 10.0,
+        state: AircraftState::Taxiing {
+          current: Node::new(
+            Intern::from_ref("A"),
+            NodeKind::Taxiway,
+            NodeBehavior::GoTo,
+            Vec2::ZERO,
+          ),
+          waypoints: vec![Node::new(
+            Intern::from_ref("27"),
+            NodeKind::Runway,
+            NodeBehavior::LineUp,
+            Vec2::ZERO,
+          )],
+          state: TaxiingState::Armed,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    let reminders = |events: &[Event]| {
+      events
+        .iter()
+        .filter(|e| {
+          matches!(
+            e,
+            Event::Aircraft(AircraftEvent {
+              kind: EventKind::Callout(CommandWithFreq {
+                reply: CommandReply::LineUpTimeout { runway, vacating: false },
+                ..
+              }),
+              ..
+            }) if runway == "27"
+          )
+        })
+        .count()
+    };
+
+    let mut now = Duration::ZERO;
+    while now < Duration::from_secs(30) {
+      let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+      assert_eq!(reminders(&events), 0, "reminded at {now:?}");
+      now += Duration::from_secs(1);
+    }
+    assert!(game.aircraft_by_id(id).unwrap().lined_up_until.is_some());
+
+    let mut count = 0;
+    while now < Duration::from_secs(60) {
+      let events = engine.tick(&world, &mut game, &mut rng, 1.0, now);
+      count += reminders(&events);
+      now += Duration::from_secs(1);
+    }
+    assert_eq!(count, 1);
+  }
+
+  #[test]
+  fn cruise_burns_a_plausible_amount_of_fuel() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let capacity = AircraftKind::A21N.stats().fuel_capacity;
+    game.push_aircraft(
+      Aircraft {
+        id: Intern::from_ref("AAL1234"),
+        speed: 450.0,
+        altitude: 35000.0,
+        state: AircraftState::Flying {
+          waypoints: Vec::new(),
+          enroute: false,
+        },
+        kind: AircraftKind::A21N,
+        fuel: capacity,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    for _ in 0..600 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    }
+
+    // An A321neo burns around 5,000 pounds an hour in the cruise.
+    let burned = capacity - game.aircraft()[0].fuel;
+    assert!((600.0..1200.0).contains(&burned), "burned {burned} pounds");
+  }
+
+  #[test]
+  fn exhausted_fuel_diverts_to_the_nearest_airport() {
+    let mut airport =
+      Airport::new(Intern::from_ref("KSFO"), Vec2::new(0.0, 40000.0));
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      pos: Vec2::new(0.0, 40000.0),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let connection = |id, x, state| Connection {
+      id: Intern::from_ref(id),
+      state,
+      pos: Vec2::new(x, 0.0),
+      transition: Vec2::new(x, 0.0),
+    };
+    let world = World {
+      airspace: Airspace {
+        fuel_reserve: Some(1.0),
+        airports: vec![airport],
+        ..Default::default()
+      },
+      // The inactive one is nearer, but nobody is there to take us.
+      connections: vec![
+        connection("KLAX", 200000.0, ConnectionState::Active),
+        connection("KSJC", 10000.0, ConnectionState::Inactive),
+      ],
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    let mut aircraft = Aircraft {
+      id,
+      speed: 250.0,
+      altitude: 7000.0,
+      state: AircraftState::Flying {
+        waypoints: Vec::new(),
+        enroute: false,
+      },
+      flight_plan: FlightPlan::new(
+        Intern::from_ref("KSFO"),
+        Intern::from_ref("KLAX"),
+      ),
+      kind: AircraftKind::A21N,
+      ..Default::default()
+    };
+    aircraft.fuel = aircraft.fuel_flow(FlightSegment::Enroute) * 90.0;
+    game.push_aircraft(aircraft.with_synced_targets());
+
+    let mut callouts = Vec::new();
+    for _ in 0..120 {
+      let events =
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      callouts.extend(events.into_iter().filter_map(|e| match e {
+        Event::Aircraft(AircraftEvent {
+          kind: EventKind::Callout(command),
+          ..
+        }) => Some(command.reply),
+        _ => None,
+      }));
+    }
+
+    assert!(
+      matches!(
+        callouts.as_slice(),
+        [CommandReply::MinimumFuel { minutes }, CommandReply::FuelExhausted]
+          if *minutes <= 1.0
+      ),
+      "{callouts:?}"
+    );
+
+    // Our own airport is nearer than any active connection, so we fly the
+    // approach there.
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.fuel, 0.0);
+    assert_eq!(aircraft.flight_plan.arriving.as_str(), "KSFO");
+    assert_eq!(aircraft.expected_runway, Some(Intern::from_ref("27")));
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("expected to be flying, but was {:?}", aircraft.state);
+    };
+    assert_eq!(
+      waypoints.first().map(|w| w.value.then.as_slice()),
+      Some([EventKind::Land(Intern::from_ref("27"))].as_slice())
+    );
+  }
+
+  #[test]
+  fn holds_stay_near_the_fix() {
+    let fix = Intern::from_ref("KLAX");
+    let world = World {
+      connections: vec![Connection {
+        id: fix,
+        pos: Vec2::ZERO,
+        transition: Vec2::ZERO,
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: Vec2::new(0.0, -20000.0),
+        speed: 250.0,
+        altitude: 7000.0,
+        state: AircraftState::Flying {
+          waypoints: vec![new_vor(Intern::from_ref("FIX"), Vec2::ZERO)],
+          enroute: false,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine.events.push(
+      AircraftEvent::new(
+        id,
+        Task::Hold {
+          fix,
+          direction: HoldDirection::Left,
+          leg_seconds: 60.0,
+        }
+        .into(),
+      )
+      .into(),
+    );
+
+    let mut legs = Vec::new();
+    for _ in 0..1200 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      let aircraft = game.aircraft_by_id(id).unwrap();
+      let leg = aircraft.hold.as_ref().unwrap().leg;
+      if legs.last() != Some(&leg) {
+        legs.push(leg);
+      }
+      if leg != HoldLeg::Entry {
+        assert!(
+          aircraft.pos.length() < NAUTICALMILES_TO_FEET * 10.0,
+          "strayed to {}",
+          aircraft.pos
+        );
+      }
+    }
+
+    // Around the racetrack a few times.
+    assert!(legs.len() > 5, "only flew {legs:?}");
+    assert!(legs[1..].chunks(2).all(|c| c[0] == HoldLeg::Outbound));
+
+    // Resuming our route leaves the hold.
+    engine
+      .events
+      .push(AircraftEvent::new(id, Task::ResumeOwnNavigation.into()).into());
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert!(aircraft.hold.is_none());
+  }
+
+  #[test]
+  fn wind_on_final_changes_ground_speed() {
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let start = move_point(runway.start(), 90.0, NAUTICALMILES_TO_FEET * 5.0);
+
+    let fly_final = |wind: Wind| {
+      let world = World {
+        airspace: Airspace {
+          wind,
+          ..Default::default()
+        },
+        ..Default::default()
+      };
+      let mut game = Game::default();
+      let mut engine = Engine::default();
+      let mut rng = Rng::with_seed(0);
+
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref("AAL1234"),
+          pos: start,
+          speed: 180.0,
+          heading: 270.0,
+          altitude: calculate_ils_altitude(start.distance(runway.start())),
+          state: AircraftState::Landing {
+            runway: runway.clone(),
+            state: LandingState::Glideslope,
+          },
+          fuel: AircraftKind::A21N.stats().fuel_capacity,
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+
+      for _ in 0..60 {
+        engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      }
+      game.aircraft()[0].clone()
+    };
+
+    let calm = fly_final(Wind::default());
+    let headwind = fly_final(Wind {
+      heading: 270.0,
+      speed: 20.0,
+    });
+    assert!(matches!(calm.state, AircraftState::Landing { .. }));
+    assert!(matches!(headwind.state, AircraftState::Landing { .. }));
+
+    // The headwind takes twenty knots off our ground speed, so we fly that
+    // much faster through the air to keep to the glideslope.
+    let wind = Wind {
+      heading: 270.0,
+      speed: 20.0,
+    };
+    assert_eq!(
+      headwind.ground_speed(wind),
+      headwind.speed - 20.0,
+      "ground speed should reflect the headwind"
+    );
+    assert!(
+      (headwind.speed - calm.speed - 20.0).abs() < 1.0,
+      "flew {} knots into the wind and {} in calm air",
+      headwind.speed,
+      calm.speed
+    );
+    assert!(start.distance(calm.pos) - start.distance(headwind.pos) < 500.0);
+
+    // Too strong a tailwind sends us around.
+    let tailwind = fly_final(Wind {
+      heading: 90.0,
+      speed: 15.0,
+    });
+    assert!(matches!(tailwind.state, AircraftState::Flying { .. }));
+  }
+
+  #[test]
+  fn crosswind_beyond_our_limit_goes_around() {
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let start = move_point(runway.start(), 90.0, NAUTICALMILES_TO_FEET * 5.0);
+    // A direct crosswind, from the north.
+    let world = World {
+      airspace: Airspace {
+        wind: Wind {
+          heading: 360.0,
+          speed: 40.0,
+        },
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    let fly_final = |kind: AircraftKind| {
+      let mut game = Game::default();
+      let mut engine = Engine::default();
+      let mut rng = Rng::with_seed(0);
+
+      game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref("AAL1234"),
+          pos: start,
+          speed: 160.0,
+          heading: 270.0,
+          altitude: calculate_ils_altitude(start.distance(runway.start())),
+          state: AircraftState::Landing {
+            runway: runway.clone(),
+            state: LandingState::Glideslope,
+          },
+          fuel: kind.stats().fuel_capacity,
+          kind,
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+      (game.aircraft()[0].clone(), engine.events)
+    };
+
+    let (regional, events) = fly_final(AircraftKind::CRJ7);
+    assert!(matches!(
+      regional.state,
+      AircraftState::Landing {
+        state: LandingState::GoAround,
+        ..
+      }
+    ));
+    assert!(events.iter().any(|e| matches!(
+      e,
+      Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::GoAroundCrosswind { .. },
+          ..
+        }),
+        ..
+      })
+    )));
+
+    let (jumbo, _) = fly_final(AircraftKind::B747);
+    assert!(matches!(
+      jumbo.state,
+      AircraftState::Landing { state, .. } if state != LandingState::GoAround
+    ));
+  }
+
+  #[test]
+  fn go_around_reads_back_magnetic_runway_heading() {
+    let world = World {
+      magnetic_variation: 10.0,
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    };
+    let start = move_point(runway.start(), 90.0, NAUTICALMILES_TO_FEET * 5.0);
+    game.push_aircraft(
+      Aircraft {
+        id: Intern::from_ref("AAL1234"),
+        pos: start,
+        speed: 160.0,
+        heading: 270.0,
+        // Far too high to make the runway.
+        altitude: calculate_ils_altitude(start.distance(runway.start()))
+          + 1000.0,
+        state: AircraftState::Landing {
+          runway,
+          state: LandingState::Glideslope,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    let reply = engine.events.iter().find_map(|e| match e {
+      Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq { reply, .. }),
+        ..
+      }) => Some(reply.clone()),
+      _ => None,
+    });
+    assert_eq!(
+      reply,
+      Some(CommandReply::GoAround {
+        runway: "27".to_string(),
+        heading: 260.0,
+      })
+    );
+  }
+}
@@ -3,18 +3,27 @@ use internment::Intern;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-  angle_between_points,
-  command::{CommandReply, CommandWithFreq, Task},
+  angle_between_points, calculate_ils_altitude,
+  command::{Clearance, CommandReply, CommandWithFreq, HoldDirection, Task},
+  delta_angle,
   engine::{Bundle, Event},
-  entities::world::closest_airport,
-  heading_to_direction,
+  entities::{
+    airport::{Airport, Runway},
+    world::{closest_airport, ConnectionState},
+  },
+  heading_to_direction, inverse_degrees, magnetic_to_true, move_point,
   pathfinder::{
     display_node_vec2, display_vec_node_vec2, new_vor, Node, NodeBehavior,
-    NodeKind, Pathfinder,
+    NodeKind, NodeVORData, Pathfinder,
   },
+  NAUTICALMILES_TO_FEET,
 };
 
-use super::{Aircraft, AircraftState, LandingState, TaxiingState};
+use super::{
+  braking_speed, is_valid_squawk, turn_speed, Aircraft, AircraftState,
+  ApproachMode, DeferredTarget, Hold, HoldLeg, LandingState, NavMode,
+  TaxiStopReason, TaxiingState, IDENT_DURATION, RUNWAY_EXIT_CLEARANCE,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EventKind {
@@ -22,32 +31,77 @@ pub enum EventKind {
   Speed(f32),
   SpeedAtOrBelow(f32),
   SpeedAtOrAbove(f32),
+  SpeedAtDiscretion(f32),
+  Mach(f32),
   Frequency(f32),
   NamedFrequency(String),
+  Monitor(String),
+  AutoTune,
 
   // Flying
   Heading(f32),
+  MaintainPresentHeading,
   Altitude(f32),
   AltitudeAtOrBelow(f32),
   AltitudeAtOrAbove(f32),
-  ResumeOwnNavigation,
+  AltitudeAtDiscretion(f32),
+  Climb(f32),
+  Descend(f32),
+  /// Flies to our arrival airport, or to the nearest one instead if we are
+  /// diverting.
+  ResumeOwnNavigation {
+    diversion: bool,
+  },
+  ResumeArrival,
+  Direct {
+    fix: Intern<String>,
+    radial: Option<f32>,
+    distance: Option<f32>,
+  },
+  DirectNearest,
+  DirectApproach {
+    fix: Intern<String>,
+    runway: Intern<String>,
+  },
+  Hold {
+    fix: Intern<String>,
+    direction: HoldDirection,
+    leg_seconds: f32,
+  },
+  ExpectRunway(Intern<String>),
+  ExpectVectors,
+  SetRoute(String),
+  Procedure(Intern<String>),
 
   // Transitions
   Land(Intern<String>),
+  CircleToLand {
+    approach_runway: Intern<String>,
+    land_runway: Intern<String>,
+  },
   GoAround,
   Touchdown,
+  /// Taxis off the runway we are lined up on, at the next exit.
+  VacateRunway,
   Takeoff(Intern<String>),
   EnRoute(bool),
   FlipFlightPlan,
 
   // Taxiing
+  IssueClearance(u16),
   Taxi(Vec<Node<()>>),
   TaxiContinue,
-  TaxiHold { and_state: bool },
+  TaxiHold {
+    and_state: bool,
+    reason: Option<TaxiStopReason>,
+  },
   LineUp(Intern<String>),
 
   // Requests
   Ident,
+  SayAgain,
+  Squawk(u16),
+  SquawkIdent(u16),
 
   // Callouts
   Callout(CommandWithFreq),
@@ -56,6 +110,13 @@ pub enum EventKind {
   // External
   Delete,
   CompleteFlight,
+  #[cfg(debug_assertions)]
+  Reposition {
+    pos: Vec2,
+    altitude: f32,
+    heading: f32,
+    speed: f32,
+  },
 
   // Points
   SuccessfulTakeoff,
@@ -66,20 +127,83 @@ impl From<Task> for EventKind {
   fn from(value: Task) -> Self {
     match value {
       Task::Altitude(x) => EventKind::Altitude(x),
+      Task::AltitudeAtDiscretion(x) => EventKind::AltitudeAtDiscretion(x),
+      Task::AutoTune => EventKind::AutoTune,
+      Task::Climb(x) => EventKind::Climb(x),
+      Task::CircleToLand {
+        approach_runway,
+        land_runway,
+      } => EventKind::CircleToLand {
+        approach_runway,
+        land_runway,
+      },
+      Task::Descend(x) => EventKind::Descend(x),
+      Task::Direct {
+        fix,
+        radial,
+        distance,
+      } => EventKind::Direct {
+        fix,
+        radial,
+        distance,
+      },
+      Task::DirectNearest => EventKind::DirectNearest,
+      Task::DirectApproach { fix, runway } => {
+        EventKind::DirectApproach { fix, runway }
+      }
+      Task::ExpectRunway(x) => EventKind::ExpectRunway(x),
+      Task::ExpectVectors => EventKind::ExpectVectors,
       Task::Frequency(x) => EventKind::Frequency(x),
       Task::GoAround => EventKind::GoAround,
       Task::Heading(x) => EventKind::Heading(x),
+      Task::Hold {
+        fix,
+        direction,
+        leg_seconds,
+      } => EventKind::Hold {
+        fix,
+        direction,
+        leg_seconds,
+      },
+      Task::MaintainPresentHeading => EventKind::MaintainPresentHeading,
       Task::Ident => EventKind::Ident,
+      Task::IssueClearance(x) => EventKind::IssueClearance(x),
       Task::Land(x) => EventKind::Land(x),
+      Task::Mach(x) => EventKind::Mach(x),
+      Task::Monitor(x) => EventKind::Monitor(x),
       Task::NamedFrequency(x) => EventKind::NamedFrequency(x),
-      Task::ResumeOwnNavigation => EventKind::ResumeOwnNavigation,
+      Task::ResumeOwnNavigation => {
+        EventKind::ResumeOwnNavigation { diversion: false }
+      }
+      Task::ResumeArrival => EventKind::ResumeArrival,
+      Task::SetRoute(x) => EventKind::SetRoute(x),
+      Task::Procedure(x) => EventKind::Procedure(x),
+      Task::SayAgain => EventKind::SayAgain,
       Task::Speed(x) => EventKind::Speed(x),
+      Task::SpeedAtDiscretion(x) => EventKind::SpeedAtDiscretion(x),
+      Task::Squawk(x) => EventKind::Squawk(x),
+      Task::SquawkIdent(x) => EventKind::SquawkIdent(x),
       Task::Takeoff(x) => EventKind::Takeoff(x),
       Task::Taxi(x) => EventKind::Taxi(x),
       Task::TaxiContinue => EventKind::TaxiContinue,
-      Task::TaxiHold => EventKind::TaxiHold { and_state: true },
+      Task::TaxiHold => EventKind::TaxiHold {
+        and_state: true,
+        reason: Some(TaxiStopReason::PlayerHold),
+      },
       Task::LineUp(x) => EventKind::LineUp(x),
       Task::Delete => EventKind::Delete,
+      #[cfg(debug_assertions)]
+      Task::Reposition {
+        pos,
+        altitude,
+        heading,
+        speed,
+      } => EventKind::Reposition {
+        pos,
+        altitude,
+        heading,
+        speed,
+      },
     }
   }
 }
@@ -106,35 +230,53 @@ impl AircraftEventHandler for HandleAircraftEvent {
     match event {
       // Any
       EventKind::Speed(speed) => {
-        aircraft.target.speed = *speed;
+        aircraft.deferred_speed = None;
+        aircraft.target.mach = None;
+        match aircraft.min_speed() {
+          Some(minimum) if *speed < minimum => {
+            aircraft.target.speed = minimum;
+            push_refusal(
+              aircraft,
+              bundle,
+              CommandReply::UnableSpeed {
+                speed: *speed,
+                minimum,
+              },
+            );
+          }
+          _ => aircraft.target.speed = *speed,
+        }
       }
       EventKind::SpeedAtOrBelow(speed) => {
         if aircraft.target.speed > *speed {
           aircraft.target.speed = *speed;
+          aircraft.target.mach = None;
         }
       }
       EventKind::SpeedAtOrAbove(speed) => {
         if aircraft.target.speed < *speed {
           aircraft.target.speed = *speed;
+          aircraft.target.mach = None;
         }
       }
+      EventKind::SpeedAtDiscretion(speed) => {
+        aircraft.deferred_speed = Some(DeferredTarget::new(*speed, bundle));
+      }
+      EventKind::Mach(mach) => {
+        aircraft.deferred_speed = None;
+        aircraft.target.mach = Some(*mach);
+      }
       EventKind::Heading(heading) => {
-        if let AircraftState::Flying { enroute, .. } = aircraft.state {
-          aircraft.target.heading = *heading;
-
-          // Cancel waypoints of not enroute
-          if !enroute {
-            aircraft.state = AircraftState::Flying {
-              enroute: false,
-              waypoints: Vec::new(),
-            };
-          }
-        } else if let AircraftState::Landing { .. } = &aircraft.state {
-          aircraft.target.heading = *heading;
-        }
+        let heading =
+          magnetic_to_true(*heading, bundle.world.magnetic_variation);
+        handle_heading_event(aircraft, heading);
+      }
+      EventKind::MaintainPresentHeading => {
+        handle_heading_event(aircraft, aircraft.heading);
       }
       EventKind::Altitude(altitude) => {
-        aircraft.target.altitude = *altitude;
+        aircraft.deferred_altitude = None;
+        handle_altitude_event(aircraft, bundle, *altitude)
       }
       EventKind::AltitudeAtOrBelow(altitude) => {
         if aircraft.target.altitude > *altitude {
@@ -146,68 +288,197 @@ impl AircraftEventHandler for HandleAircraftEvent {
           aircraft.target.altitude = *altitude;
         }
       }
+      EventKind::AltitudeAtDiscretion(altitude) => {
+        aircraft.deferred_altitude =
+          Some(DeferredTarget::new(*altitude, bundle));
+      }
+      EventKind::Climb(altitude) => {
+        if *altitude < aircraft.altitude {
+          push_refusal(
+            aircraft,
+            bundle,
+            CommandReply::UnableClimb {
+              altitude: *altitude,
+            },
+          );
+        } else {
+          aircraft.deferred_altitude = None;
+          handle_altitude_event(aircraft, bundle, *altitude)
+        }
+      }
+      EventKind::Descend(altitude) => {
+        if *altitude > aircraft.altitude {
+          push_refusal(
+            aircraft,
+            bundle,
+            CommandReply::UnableDescend {
+              altitude: *altitude,
+            },
+          );
+        } else {
+          aircraft.deferred_altitude = None;
+          handle_altitude_event(aircraft, bundle, *altitude)
+        }
+      }
       EventKind::Frequency(frequency) => {
         aircraft.frequency = *frequency;
       }
       EventKind::NamedFrequency(frq) => {
+        if let Some(frequency) =
+          bundle.world.airspace.frequencies.try_from_string(frq)
+        {
+          aircraft.frequency = frequency;
+
+          // Check in with the new controller.
+          bundle.events.push(
+            AircraftEvent::new(
+              aircraft.id,
+              EventKind::Callout(CommandWithFreq::new(
+                aircraft.id.to_string(),
+                frequency,
+                CommandReply::CheckIn {
+                  controller: frq.clone(),
+                },
+                Vec::new(),
+              )),
+            )
+            .into(),
+          );
+        }
+      }
+      EventKind::Monitor(frq) => {
         if let Some(frequency) =
           bundle.world.airspace.frequencies.try_from_string(frq)
         {
           aircraft.frequency = frequency;
         }
       }
+      EventKind::AutoTune => {
+        aircraft.frequency =
+          aircraft.controller_frequency(&bundle.world.airspace);
+      }
 
       // Flying
-      EventKind::ResumeOwnNavigation => {
-        if let AircraftState::Flying { enroute, .. } = aircraft.state {
-          let arrival = bundle
-            .world
-            .connections
+      EventKind::ResumeOwnNavigation { diversion } => {
+        aircraft.approach_mode = ApproachMode::PilotNav;
+        aircraft.nav_mode = NavMode::LNav;
+        aircraft.hold = None;
+        handle_resume_own_navigation_event(aircraft, bundle, *diversion);
+      }
+      EventKind::ResumeArrival => {
+        aircraft.approach_mode = ApproachMode::PilotNav;
+        aircraft.nav_mode = NavMode::LNav;
+        aircraft.hold = None;
+        if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+          let mut route = core::mem::take(&mut aircraft.vectored_from);
+
+          // Rejoin at the closest fix ahead of us, leaving out the ones that
+          // are now behind. Waypoints are flown from the back.
+          let rejoin = route
             .iter()
-            .find(|a| a.id == aircraft.flight_plan.arriving);
-
-          if let Some(arrival) = arrival {
-            aircraft.target.speed = 300.0;
-            aircraft.target.altitude = 13000.0;
-            aircraft.state = AircraftState::Flying {
-              enroute,
-              waypoints: vec![
-                new_vor(arrival.id, arrival.pos)
-                  .with_name(Intern::from_ref("APRT"))
-                  .with_behavior(vec![
-                    EventKind::CompleteFlight,
-                    EventKind::Delete,
-                  ]),
-                new_vor(arrival.id, arrival.transition)
-                  .with_name(Intern::from_ref("TRSN"))
-                  .with_behavior(vec![EventKind::EnRoute(true)]),
-              ],
+            .enumerate()
+            .filter(|(_, w)| {
+              let bearing = angle_between_points(aircraft.pos, w.value.to);
+              delta_angle(aircraft.heading, bearing).abs() <= 90.0
+            })
+            .min_by(|(_, a), (_, b)| {
+              a.value
+                .to
+                .distance_squared(aircraft.pos)
+                .total_cmp(&b.value.to.distance_squared(aircraft.pos))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+          route.truncate(rejoin + 1);
+
+          if !route.is_empty() {
+            *waypoints = route;
+          }
+        }
+        aircraft.replan_route();
+      }
+      EventKind::Direct {
+        fix,
+        radial,
+        distance,
+      } => handle_direct_event(aircraft, bundle, *fix, *radial, *distance),
+      EventKind::DirectNearest => {
+        aircraft.approach_mode = ApproachMode::PilotNav;
+        aircraft.nav_mode = NavMode::LNav;
+        aircraft.hold = None;
+        if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+          if let Some(nearest) = bundle.world.nearest_waypoint(aircraft.pos) {
+            // Fly there next, then carry on with the rest of the route.
+            if waypoints.last().map(|w| w.name) != Some(nearest.name) {
+              waypoints.push(nearest);
             }
           }
         }
+        aircraft.replan_route();
+      }
+
+      EventKind::ExpectRunway(runway) => {
+        handle_expect_runway_event(aircraft, bundle, *runway)
+      }
+      EventKind::ExpectVectors => {
+        aircraft.approach_mode = ApproachMode::Vectors;
+        // Enroute, we keep flying our route until we reach the airspace.
+        if !matches!(
+          aircraft.state,
+          AircraftState::Flying { enroute: true, .. }
+        ) {
+          aircraft.nav_mode = NavMode::Heading;
+        }
       }
+      EventKind::SetRoute(route) => {
+        aircraft.approach_mode = ApproachMode::PilotNav;
+        aircraft.nav_mode = NavMode::LNav;
+        aircraft.hold = None;
+        handle_set_route_event(aircraft, bundle, route);
+      }
+      EventKind::Procedure(id) => handle_procedure_event(aircraft, bundle, *id),
+      EventKind::Hold {
+        fix,
+        direction,
+        leg_seconds,
+      } => handle_hold_event(aircraft, bundle, *fix, *direction, *leg_seconds),
 
       // Transitions
       EventKind::Land(runway) => handle_land_event(aircraft, bundle, *runway),
+      EventKind::DirectApproach { fix, runway } => {
+        handle_direct_approach_event(aircraft, bundle, *fix, *runway)
+      }
+      EventKind::CircleToLand {
+        approach_runway,
+        land_runway,
+      } => handle_circle_to_land_event(
+        aircraft,
+        bundle,
+        *approach_runway,
+        *land_runway,
+      ),
       EventKind::GoAround => {
-        if let AircraftState::Landing { .. } = aircraft.state {
+        if let AircraftState::Landing { runway, .. } = &aircraft.state {
+          let missed_approach = runway.missed_approach;
+
           aircraft.state = AircraftState::Flying {
             waypoints: Vec::new(),
             enroute: false,
           };
+          aircraft.circle_to_land = None;
           aircraft.sync_targets_to_vals();
 
           bundle.events.push(
             AircraftEvent {
               id: aircraft.id,
-              kind: EventKind::AltitudeAtOrAbove(3000.0),
+              kind: EventKind::AltitudeAtOrAbove(missed_approach.altitude),
             }
             .into(),
           );
           bundle.events.push(
             AircraftEvent {
               id: aircraft.id,
-              kind: EventKind::SpeedAtOrAbove(210.0),
+              kind: EventKind::SpeedAtOrAbove(missed_approach.speed),
             }
             .into(),
           );
@@ -218,6 +489,9 @@ impl AircraftEventHandler for HandleAircraftEvent {
           handle_touchdown_event(aircraft, bundle);
         }
       }
+      EventKind::VacateRunway => {
+        handle_vacate_runway_event(aircraft, bundle);
+      }
       EventKind::Takeoff(runway) => {
         if let AircraftState::Taxiing { .. } = aircraft.state {
           handle_takeoff_event(aircraft, bundle, *runway);
@@ -242,6 +516,68 @@ impl AircraftEventHandler for HandleAircraftEvent {
       }
 
       // Taxiing
+      EventKind::IssueClearance(squawk) => {
+        if let AircraftState::Parked { .. } = aircraft.state {
+          if is_valid_squawk(*squawk) {
+            aircraft.squawk = Some(*squawk);
+            aircraft.ifr_cleared = true;
+            bundle.events.push(
+              AircraftEvent::new(
+                aircraft.id,
+                EventKind::Callout(CommandWithFreq::new(
+                  aircraft.id.to_string(),
+                  aircraft.frequency,
+                  CommandReply::ClearanceReadback {
+                    clearance: Clearance {
+                      airport: aircraft.flight_plan.arriving.to_string(),
+                      altitude: aircraft.flight_plan.altitude,
+                      squawk: *squawk,
+                    },
+                  },
+                  Vec::new(),
+                )),
+              )
+              .into(),
+            );
+          } else {
+            bundle.events.push(
+              AircraftEvent::new(
+                aircraft.id,
+                EventKind::Callout(CommandWithFreq::new(
+                  aircraft.id.to_string(),
+                  aircraft.frequency,
+                  CommandReply::WithCallsign {
+                    text: format!(
+                      "Unable, squawk {squawk:04} is not a valid code"
+                    ),
+                  },
+                  Vec::new(),
+                )),
+              )
+              .into(),
+            );
+          }
+        }
+      }
+      EventKind::Taxi(..)
+        if aircraft.needs_clearance(&bundle.world.airspace) =>
+      {
+        bundle.events.push(
+          AircraftEvent::new(
+            aircraft.id,
+            EventKind::Callout(CommandWithFreq::new(
+              aircraft.id.to_string(),
+              aircraft.frequency,
+              CommandReply::WithCallsign {
+                text: "Unable to taxi, we still need our IFR clearance"
+                  .to_string(),
+              },
+              Vec::new(),
+            )),
+          )
+          .into(),
+        );
+      }
       EventKind::Taxi(waypoints) => {
         if let AircraftState::Taxiing { .. } | AircraftState::Parked { .. } =
           aircraft.state
@@ -256,7 +592,9 @@ impl AircraftEventHandler for HandleAircraftEvent {
       EventKind::TaxiContinue => {
         if let AircraftState::Taxiing { state, .. } = &mut aircraft.state {
           match state {
-            TaxiingState::Armed | TaxiingState::Override => {}
+            TaxiingState::Armed
+            | TaxiingState::Override
+            | TaxiingState::Queued => {}
             TaxiingState::Holding => {
               *state = TaxiingState::Armed;
             }
@@ -265,13 +603,20 @@ impl AircraftEventHandler for HandleAircraftEvent {
             }
           }
 
-          aircraft.target.speed = 20.0;
+          // Queued aircraft wait for their turn to depart.
+          if *state != TaxiingState::Queued {
+            aircraft.target.speed = 20.0;
+          }
         }
       }
-      EventKind::TaxiHold { and_state: force } => {
+      EventKind::TaxiHold {
+        and_state: force,
+        reason,
+      } => {
         if let AircraftState::Taxiing { state, .. } = &mut aircraft.state {
           aircraft.target.speed = 0.0;
           aircraft.speed = 0.0;
+          aircraft.stop_reason = *reason;
 
           if *force {
             *state = TaxiingState::Holding;
@@ -313,6 +658,36 @@ impl AircraftEventHandler for HandleAircraftEvent {
         );
       }
 
+      EventKind::SayAgain => {
+        if let Some(readback) = &aircraft.last_readback {
+          bundle.events.push(
+            AircraftEvent::new(
+              aircraft.id,
+              EventKind::Callout(CommandWithFreq::new(
+                aircraft.id.to_string(),
+                aircraft.frequency,
+                CommandReply::Blank {
+                  text: readback.clone(),
+                },
+                Vec::new(),
+              )),
+            )
+            .into(),
+          );
+        }
+      }
+      EventKind::Squawk(code) => {
+        if is_valid_squawk(*code) {
+          aircraft.squawk = Some(*code);
+        }
+      }
+      EventKind::SquawkIdent(code) => {
+        if is_valid_squawk(*code) {
+          aircraft.squawk = Some(*code);
+          aircraft.ident_until = Some(bundle.now + IDENT_DURATION);
+        }
+      }
+
       // Callouts are handled outside of the engine.
       EventKind::Callout(..) => {}
       EventKind::CalloutInAirspace => {
@@ -346,6 +721,19 @@ impl AircraftEventHandler for HandleAircraftEvent {
           .push(AircraftEvent::new(aircraft.id, EventKind::Delete).into());
       }
       EventKind::CompleteFlight => {}
+      #[cfg(debug_assertions)]
+      EventKind::Reposition {
+        pos,
+        altitude,
+        heading,
+        speed,
+      } => {
+        aircraft.pos = *pos;
+        aircraft.altitude = *altitude;
+        aircraft.heading = *heading;
+        aircraft.speed = *speed;
+        aircraft.sync_targets_to_vals();
+      }
 
       // Points
       // Points are handled within the engine itself.
@@ -355,221 +743,2527 @@ impl AircraftEventHandler for HandleAircraftEvent {
   }
 }
 
-pub fn handle_land_event(
-  aircraft: &mut Aircraft,
-  bundle: &mut Bundle,
-  runway_id: Intern<String>,
-) {
-  if let AircraftState::Flying { .. } = aircraft.state {
-    if let Some(runway) = bundle
-      .world
-      .airspace
-      .airports
-      .iter()
-      .flat_map(|a| a.runways.iter())
-      .find(|r| r.id == runway_id)
-    {
-      aircraft.state = AircraftState::Landing {
-        runway: runway.clone(),
-        state: LandingState::default(),
-      };
+/// Flies `heading` (which is true), dropping our waypoints unless we are
+/// enroute.
+pub fn handle_heading_event(aircraft: &mut Aircraft, heading: f32) {
+  aircraft.hold = None;
+  if let AircraftState::Flying { enroute, waypoints } = &mut aircraft.state {
+    aircraft.target.heading = heading;
+
+    // Cancel waypoints of not enroute, keeping them to resume later.
+    if !*enroute {
+      aircraft.nav_mode = NavMode::Heading;
+      if !waypoints.is_empty() {
+        aircraft.vectored_from = core::mem::take(waypoints);
+      }
+      aircraft.replan_route();
     }
+  } else if let AircraftState::Landing { .. } = &aircraft.state {
+    aircraft.target.heading = heading;
   }
 }
 
-pub fn handle_touchdown_event(aircraft: &mut Aircraft, bundle: &mut Bundle) {
-  let AircraftState::Landing { runway, .. } = &mut aircraft.state else {
-    unreachable!("outer function asserts that aircraft is landing")
-  };
+/// Files `route`, keeping it as written for the strip, and flies the fixes
+/// in it that are known waypoints. Other tokens, such as airways and
+/// procedures, are only kept in the text.
+pub fn handle_set_route_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  route: &str,
+) {
+  aircraft.flight_plan.route = route.to_string();
 
-  aircraft.target.altitude = 0.0;
-  aircraft.altitude = 0.0;
-  aircraft.target.heading = runway.heading;
-  aircraft.heading = runway.heading;
+  let fixes: Vec<Node<NodeVORData>> = route
+    .split_whitespace()
+    .map(Intern::from_ref)
+    // We have already left where we departed from.
+    .filter(|name| *name != aircraft.flight_plan.departing)
+    .filter_map(|name| {
+      bundle
+        .world
+        .find_waypoint(name)
+        .map(|pos| new_vor(name, pos))
+    })
+    .collect();
 
-  aircraft.target.speed = 0.0;
+  fly_fixes(aircraft, fixes);
+}
 
-  aircraft.state = AircraftState::Taxiing {
-    current: Node {
-      name: runway.id,
-      kind: NodeKind::Runway,
-      behavior: NodeBehavior::GoTo,
-      value: aircraft.pos,
-    },
-    waypoints: Vec::new(),
-    state: TaxiingState::Override,
-  };
+/// Flies `fixes`, in order, in place of our waypoints.
+fn fly_fixes(aircraft: &mut Aircraft, mut fixes: Vec<Node<NodeVORData>>) {
+  if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+    // We still leave the airspace or complete our flight at the end of our
+    // route, so keep flying to where we were going to.
+    let exit = waypoints.first().cloned();
+    if let Some(exit) = &exit {
+      fixes.retain(|f| f.value.to != exit.value.to);
+    }
+    if fixes.is_empty() {
+      return;
+    }
 
-  bundle.events.push(
-    AircraftEvent {
-      id: aircraft.id,
-      kind: EventKind::SuccessfulLanding,
+    // Such as going en route, from the fixes that the route replaces.
+    let behaviors = waypoints
+      .iter()
+      .skip(1)
+      .flat_map(|w| w.value.then.iter().cloned());
+    if let Some(last) = fixes.last_mut() {
+      last.value.then.extend(behaviors);
     }
-    .into(),
-  );
+
+    // Waypoints are flown from the back.
+    *waypoints = exit.into_iter().chain(fixes.into_iter().rev()).collect();
+    aircraft.flight_plan.plan_route(aircraft.pos, waypoints);
+  }
 }
 
-pub fn handle_taxi_event(
+/// Flies direct to `fix`, skipping the fixes before it if it is on our
+/// route. Given a `radial` and `distance`, we fly to the point that far out
+/// on that radial instead, which is added ahead of the rest of our route.
+pub fn handle_direct_event(
   aircraft: &mut Aircraft,
   bundle: &mut Bundle,
-  waypoint_strings: &[Node<()>],
-  pathfinder: &Pathfinder,
+  fix: Intern<String>,
+  radial: Option<f32>,
+  distance: Option<f32>,
 ) {
-  if let AircraftState::Taxiing { current, .. }
-  | AircraftState::Parked { at: current, .. } = &aircraft.state
-  {
-    let mut destinations = waypoint_strings.iter().peekable();
-    let mut all_waypoints: Vec<Node<Vec2>> = Vec::new();
-
-    if destinations.peek().map(|d| d.name_and_kind_eq(current)) == Some(true) {
-      tracing::info!(
-        "Skipping {} as we are there.",
-        display_node_vec2(current)
-      );
-      destinations.next();
-    }
-
-    let mut pos = aircraft.pos;
-    let mut heading = aircraft.heading;
-    let mut curr: Node<Vec2> = current.clone();
-    for destination in destinations {
-      let path = pathfinder.path_to(
-        Node {
-          name: curr.name,
-          kind: curr.kind,
-          behavior: curr.behavior,
-          value: (),
-        },
-        destination.clone(),
-        pos,
-        heading,
-      );
+  if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+    return;
+  }
 
-      if let Some(path) = path {
-        pos = path.final_pos;
-        heading = path.final_heading;
-        curr = path.path.last().unwrap().clone();
+  let Some(pos) = bundle.world.find_waypoint(fix) else {
+    callout(
+      aircraft,
+      bundle,
+      CommandReply::UnknownFix {
+        fix: fix.to_string(),
+      },
+    );
+    return;
+  };
 
-        all_waypoints.extend(path.path);
-      } else {
-        tracing::error!(
-          "Failed to find path for destination: {:?}, from: {:?}",
-          destination,
-          curr
-        );
-        return;
-      }
+  aircraft.approach_mode = ApproachMode::PilotNav;
+  aircraft.nav_mode = NavMode::LNav;
+  aircraft.hold = None;
+  let AircraftState::Flying { waypoints, .. } = &mut aircraft.state else {
+    return;
+  };
+  match radial.zip(distance) {
+    Some((radial, distance)) => {
+      let bearing = magnetic_to_true(radial, bundle.world.magnetic_variation);
+      let name = Intern::from(format!("{fix}/{radial:03}/{distance}"));
+      waypoints.push(new_vor(
+        name,
+        move_point(pos, bearing, distance * NAUTICALMILES_TO_FEET),
+      ));
     }
-
-    // If our destination is a gate, set our destination to that gate
-    // (otherwise it will be the enterance on the apron but not the gate)
-    if let Some(last) = all_waypoints.last() {
-      if last.kind == NodeKind::Gate {
-        if let Some(airport) =
-          closest_airport(&bundle.world.airspace, aircraft.pos)
-        {
-          if let Some(gate) = airport
-            .terminals
-            .iter()
-            .flat_map(|t| t.gates.iter())
-            .find(|g| g.id == last.name)
-          {
-            all_waypoints.push(Node::new(
-              last.name,
-              last.kind,
-              NodeBehavior::Park,
-              gate.pos,
-            ));
-          }
-        }
+    None => {
+      // Waypoints are flown from the back.
+      match waypoints.iter().rposition(|w| w.name == fix) {
+        Some(index) => waypoints.truncate(index + 1),
+        None => waypoints.push(new_vor(fix, pos)),
       }
     }
+  }
+  aircraft.replan_route();
+}
 
-    if all_waypoints.is_empty() {
-      return;
-    }
-
-    all_waypoints.reverse();
+/// Flies the fixes of the SID or STAR named `id`, in order.
+pub fn handle_procedure_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  id: Intern<String>,
+) {
+  if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+    return;
+  }
 
-    tracing::info!(
-      "Initiating taxi for {}: {:?}",
-      aircraft.id,
-      display_vec_node_vec2(&all_waypoints)
+  let world = bundle.world;
+  let Some(procedure) = world.find_procedure(id) else {
+    callout(
+      aircraft,
+      bundle,
+      CommandReply::UnknownProcedure {
+        procedure: id.to_string(),
+      },
     );
+    return;
+  };
 
-    let current = current.clone();
-    if let AircraftState::Taxiing { waypoints, .. } = &mut aircraft.state {
-      *waypoints = all_waypoints;
-    } else {
-      aircraft.state = AircraftState::Taxiing {
-        current: current.clone(),
-        waypoints: all_waypoints,
-        state: TaxiingState::default(),
-      };
-    }
+  let mut fixes: Vec<Node<NodeVORData>> = Vec::new();
+  for name in procedure.fixes.iter() {
+    let Some(pos) = world.find_waypoint(*name) else {
+      callout(
+        aircraft,
+        bundle,
+        CommandReply::UnknownFix {
+          fix: name.to_string(),
+        },
+      );
+      return;
+    };
+    fixes.push(new_vor(*name, pos));
   }
 
-  bundle.events.push(
-    AircraftEvent {
-      id: aircraft.id,
-      kind: EventKind::TaxiContinue,
-    }
-    .into(),
-  );
+  aircraft.approach_mode = ApproachMode::PilotNav;
+  aircraft.nav_mode = NavMode::LNav;
+  aircraft.hold = None;
+  fly_fixes(aircraft, fixes);
 }
 
-pub fn handle_takeoff_event(
+pub fn handle_expect_runway_event(
   aircraft: &mut Aircraft,
   bundle: &mut Bundle,
   runway_id: Intern<String>,
 ) {
-  if let AircraftState::Taxiing {
-    current, waypoints, ..
-  } = &mut aircraft.state
-  {
-    // If we are at the runway
-    if let Some(runway) = bundle
+  if let AircraftState::Flying { .. } = aircraft.state {
+    if bundle
       .world
       .airspace
       .airports
       .iter()
       .flat_map(|a| a.runways.iter())
-      .find(|r| r.id == runway_id)
+      .any(|r| r.id == runway_id)
     {
-      if NodeKind::Runway == current.kind && current.name == runway_id {
-        aircraft.target.speed = aircraft.flight_plan.speed;
-        aircraft.target.altitude = aircraft.flight_plan.altitude;
-        aircraft.heading = runway.heading;
-        aircraft.target.heading = runway.heading;
+      aircraft.expected_runway = Some(runway_id);
 
-        aircraft.state = AircraftState::Flying {
-          enroute: false,
-          waypoints: Vec::new(),
-        };
-
-        bundle.events.push(
-          AircraftEvent {
-            id: aircraft.id,
-            kind: EventKind::SuccessfulTakeoff,
-          }
+      bundle.events.push(
+        AircraftEvent::new(
+          aircraft.id,
+          EventKind::Callout(CommandWithFreq::new(
+            aircraft.id.to_string(),
+            aircraft.frequency,
+            CommandReply::ExpectRunway {
+              runway: runway_id.to_string(),
+            },
+            Vec::new(),
+          )),
+        )
+        .into(),
+      );
+    }
+  }
+}
+
+pub fn handle_altitude_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  altitude: f32,
+) {
+  let airspace = &bundle.world.airspace;
+  let controlled =
+    !matches!(aircraft.state, AircraftState::Flying { enroute: true, .. })
+      && airspace.contains_point(aircraft.pos);
+
+  // Stay within the vertical limits of the airspace we are controlled in.
+  if controlled && !airspace.contains_altitude(altitude) {
+    let altitude = airspace.clamp_altitude(altitude);
+    aircraft.target.altitude = altitude;
+
+    bundle.events.push(
+      AircraftEvent::new(
+        aircraft.id,
+        EventKind::Callout(CommandWithFreq::new(
+          aircraft.id.to_string(),
+          aircraft.frequency,
+          CommandReply::UnableAltitude { altitude },
+          Vec::new(),
+        )),
+      )
+      .into(),
+    );
+  } else {
+    aircraft.target.altitude = altitude;
+  }
+}
+
+/// Tells ATC that we won't follow an altitude or speed instruction as given.
+fn push_refusal(aircraft: &Aircraft, bundle: &mut Bundle, reply: CommandReply) {
+  bundle.events.push(
+    AircraftEvent::new(
+      aircraft.id,
+      EventKind::Callout(CommandWithFreq::new(
+        aircraft.id.to_string(),
+        aircraft.frequency,
+        reply,
+        Vec::new(),
+      )),
+    )
+    .into(),
+  );
+}
+
+/// The runway facing the other way to `runway`, such as 9 for 27, if the
+/// airport has one.
+fn reciprocal_runway(airport: &Airport, runway: &Runway) -> Option<String> {
+  airport
+    .runways
+    .iter()
+    .find(|r| delta_angle(runway.heading, r.heading).abs() > 170.0)
+    .map(|r| r.id.to_string())
+}
+
+/// Without a named runway, use the one we were told to expect, or else the
+/// one most into the wind at the airport closest to `near`.
+fn runway_or_default(
+  aircraft: &Aircraft,
+  bundle: &Bundle,
+  runway_id: Intern<String>,
+  near: Vec2,
+) -> Intern<String> {
+  if !runway_id.is_empty() {
+    return runway_id;
+  }
+
+  let airspace = &bundle.world.airspace;
+  aircraft
+    .expected_runway
+    .or_else(|| {
+      closest_airport(airspace, near)
+        .and_then(|a| a.best_runway(airspace.wind))
+        .map(|r| r.id)
+    })
+    .unwrap_or(runway_id)
+}
+
+pub fn handle_land_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  runway_id: Intern<String>,
+) {
+  let runway_id = runway_or_default(aircraft, bundle, runway_id, aircraft.pos);
+
+  if let AircraftState::Flying { .. } = aircraft.state {
+    let airspace = &bundle.world.airspace;
+    if let Some((airport, runway)) = airspace
+      .airports
+      .iter()
+      .flat_map(|a| a.runways.iter().map(move |r| (a, r)))
+      .find(|(_, r)| r.id == runway_id)
+    {
+      if airspace.tailwind(runway.heading) > airspace.max_tailwind() {
+        let reciprocal = reciprocal_runway(airport, runway);
+
+        bundle.events.push(
+          AircraftEvent::new(
+            aircraft.id,
+            EventKind::Callout(CommandWithFreq::new(
+              aircraft.id.to_string(),
+              aircraft.frequency,
+              CommandReply::UnableTailwind {
+                runway: runway.id.to_string(),
+                or: reciprocal,
+              },
+              Vec::new(),
+            )),
+          )
           .into(),
         );
+        return;
+      }
+
+      aircraft.expected_runway = None;
+      aircraft.circle_to_land = None;
+      aircraft.state = AircraftState::Landing {
+        runway: runway.clone(),
+        state: LandingState::default(),
+      };
+    }
+  }
+}
+
+/// Flies to the airspace that we are arriving at. When diverting, that is the
+/// nearest active connection, unless one of our own airports is nearer, in
+/// which case we fly the approach to its runway most into the wind.
+pub fn handle_resume_own_navigation_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  diversion: bool,
+) {
+  let AircraftState::Flying { enroute, .. } = aircraft.state else {
+    return;
+  };
+  let world = &bundle.world;
+
+  if diversion {
+    let nearest = world
+      .connections
+      .iter()
+      .filter(|c| matches!(c.state, ConnectionState::Active))
+      .min_by(|a, b| {
+        a.pos
+          .distance_squared(aircraft.pos)
+          .total_cmp(&b.pos.distance_squared(aircraft.pos))
+      });
+    let radius =
+      nearest.map_or(f32::INFINITY, |c| c.pos.distance(aircraft.pos));
+    let local = world
+      .airports_within(aircraft.pos, radius)
+      .into_iter()
+      .find_map(|a| Some((a, a.best_runway(world.airspace.wind)?)));
+
+    if let Some((airport, runway)) = local {
+      // Where we would start down the glideslope.
+      let distance = NAUTICALMILES_TO_FEET * 10.0;
+      let final_fix =
+        move_point(runway.start(), inverse_degrees(runway.heading), distance);
+
+      aircraft.flight_plan.arriving = airport.id;
+      aircraft.expected_runway = Some(runway.id);
+      aircraft.target.altitude = calculate_ils_altitude(distance);
+      aircraft.state = AircraftState::Flying {
+        enroute,
+        waypoints: vec![new_vor(airport.id, final_fix)
+          .with_behavior(vec![EventKind::Land(runway.id)])],
+      };
+      return;
+    }
+
+    if let Some(nearest) = nearest {
+      aircraft.flight_plan.arriving = nearest.id;
+    }
+  }
+
+  let arrival = world
+    .connections
+    .iter()
+    .find(|a| a.id == aircraft.flight_plan.arriving);
+
+  if let Some(arrival) = arrival {
+    aircraft.target.speed = 300.0;
+    aircraft.target.altitude = world.airspace.altitudes.departure;
+    aircraft.state = AircraftState::Flying {
+      enroute,
+      waypoints: vec![
+        new_vor(arrival.id, arrival.pos)
+          .with_name(Intern::from_ref("APRT"))
+          .with_behavior(vec![EventKind::CompleteFlight, EventKind::Delete]),
+        new_vor(arrival.id, arrival.transition)
+          .with_name(Intern::from_ref("TRSN"))
+          .with_behavior(vec![EventKind::EnRoute(true)]),
+      ],
+    }
+  }
+}
+
+/// Flies to `fix` and holds over it, keeping our waypoints for when we are
+/// told to resume them.
+pub fn handle_hold_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  fix: Intern<String>,
+  direction: HoldDirection,
+  leg_seconds: f32,
+) {
+  if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+    return;
+  }
+  let Some(pos) = bundle.world.find_waypoint(fix) else {
+    callout(
+      aircraft,
+      bundle,
+      CommandReply::UnknownFix {
+        fix: fix.to_string(),
+      },
+    );
+    return;
+  };
+
+  aircraft.nav_mode = NavMode::LNav;
+  aircraft.hold = Some(Hold {
+    fix,
+    pos,
+    inbound_course: angle_between_points(aircraft.pos, pos),
+    direction,
+    leg_seconds,
+    leg: HoldLeg::Entry,
+    elapsed: 0.0,
+  });
+}
+
+/// Flies direct to `fix`, where we are cleared for the approach to
+/// `runway_id`.
+pub fn handle_direct_approach_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  fix: Intern<String>,
+  runway_id: Intern<String>,
+) {
+  if !matches!(aircraft.state, AircraftState::Flying { .. }) {
+    return;
+  }
+
+  let Some(pos) = bundle.world.find_waypoint(fix) else {
+    callout(
+      aircraft,
+      bundle,
+      CommandReply::UnknownFix {
+        fix: fix.to_string(),
+      },
+    );
+    return;
+  };
+  let runway_id = runway_or_default(aircraft, bundle, runway_id, pos);
+
+  let runway_exists = bundle
+    .world
+    .airspace
+    .airports
+    .iter()
+    .flat_map(|a| a.runways.iter())
+    .any(|r| r.id == runway_id);
+  if !runway_exists {
+    callout(
+      aircraft,
+      bundle,
+      CommandReply::UnknownRunway {
+        runway: runway_id.to_string(),
+      },
+    );
+    return;
+  }
+
+  if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+    *waypoints =
+      vec![new_vor(fix, pos).with_behavior(vec![EventKind::Land(runway_id)])];
+  }
+  aircraft.expected_runway = Some(runway_id);
+  aircraft.approach_mode = ApproachMode::PilotNav;
+  aircraft.nav_mode = NavMode::LNav;
+  aircraft.hold = None;
+  aircraft.replan_route();
+}
+
+/// Tells ATC `reply` on our frequency.
+fn callout(aircraft: &Aircraft, bundle: &mut Bundle, reply: CommandReply) {
+  bundle.events.push(
+    AircraftEvent::new(
+      aircraft.id,
+      EventKind::Callout(CommandWithFreq::new(
+        aircraft.id.to_string(),
+        aircraft.frequency,
+        reply,
+        Vec::new(),
+      )),
+    )
+    .into(),
+  );
+}
+
+pub fn handle_circle_to_land_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  approach_runway: Intern<String>,
+  land_runway: Intern<String>,
+) {
+  if let AircraftState::Flying { .. } = aircraft.state {
+    let airspace = &bundle.world.airspace;
+    let runways = || airspace.airports.iter().flat_map(|a| a.runways.iter());
+    let approach = runways().find(|r| r.id == approach_runway);
+    let land = runways().find(|r| r.id == land_runway);
+
+    if let (Some(approach), Some(land)) = (approach, land) {
+      // The wind only matters for the runway we land on.
+      if airspace.tailwind(land.heading) > airspace.max_tailwind() {
         bundle.events.push(
-          AircraftEvent {
-            id: aircraft.id,
-            kind: EventKind::ResumeOwnNavigation,
-          }
+          AircraftEvent::new(
+            aircraft.id,
+            EventKind::Callout(CommandWithFreq::new(
+              aircraft.id.to_string(),
+              aircraft.frequency,
+              CommandReply::UnableTailwind {
+                runway: land.id.to_string(),
+                or: None,
+              },
+              Vec::new(),
+            )),
+          )
           .into(),
         );
-      } else if let Some(runway) = waypoints.first_mut() {
-        if runway.kind == NodeKind::Runway && runway.name == runway_id {
-          runway.behavior = NodeBehavior::Takeoff;
+        return;
+      }
 
-          bundle.events.push(
-            AircraftEvent::new(aircraft.id, EventKind::TaxiContinue).into(),
-          );
-        }
+      aircraft.expected_runway = None;
+      aircraft.circle_to_land = Some(land.id);
+      aircraft.state = AircraftState::Landing {
+        runway: approach.clone(),
+        state: LandingState::default(),
+      };
+    }
+  }
+}
+
+pub fn handle_touchdown_event(aircraft: &mut Aircraft, bundle: &mut Bundle) {
+  let AircraftState::Landing { runway, .. } = &mut aircraft.state else {
+    unreachable!("outer function asserts that aircraft is landing")
+  };
+
+  aircraft.target.altitude = 0.0;
+  aircraft.altitude = 0.0;
+  aircraft.target.heading = runway.heading;
+  aircraft.heading = runway.heading;
+
+  aircraft.target.speed = 0.0;
+
+  // Roll out to an exit so that we don't stop on the runway.
+  let waypoints = closest_airport(&bundle.world.airspace, aircraft.pos)
+    .and_then(|airport| {
+      runway_exit(&airport.pathfinder, runway, aircraft.pos, aircraft.speed)
+    })
+    .unwrap_or_default();
+
+  aircraft.state = AircraftState::Taxiing {
+    current: Node {
+      name: runway.id,
+      kind: NodeKind::Runway,
+      behavior: NodeBehavior::GoTo,
+      value: aircraft.pos,
+    },
+    waypoints,
+    state: TaxiingState::Override,
+  };
+
+  bundle.events.push(
+    AircraftEvent {
+      id: aircraft.id,
+      kind: EventKind::SuccessfulLanding,
+    }
+    .into(),
+  );
+}
+
+pub fn handle_vacate_runway_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+) {
+  let AircraftState::Taxiing {
+    current,
+    waypoints,
+    state,
+  } = &mut aircraft.state
+  else {
+    return;
+  };
+  if current.kind != NodeKind::Runway {
+    return;
+  }
+
+  let exit =
+    closest_airport(&bundle.world.airspace, aircraft.pos).and_then(|airport| {
+      let runway = airport.runways.iter().find(|r| r.id == current.name)?;
+      runway_exit(&airport.pathfinder, runway, aircraft.pos, 0.0)
+    });
+
+  if let Some(exit) = exit {
+    current.behavior = NodeBehavior::GoTo;
+    *waypoints = exit;
+    *state = TaxiingState::Armed;
+
+    bundle
+      .events
+      .push(AircraftEvent::new(aircraft.id, EventKind::TaxiContinue).into());
+  }
+}
+
+/// Picks the first exit ahead of us that we can brake in time to turn off
+/// onto, or the last one if we can't make any of them. Returns the waypoints
+/// to taxi clear of the runway by, with the next one last.
+fn runway_exit(
+  pathfinder: &Pathfinder,
+  runway: &Runway,
+  pos: Vec2,
+  speed: f32,
+) -> Option<Vec<Node<Vec2>>> {
+  let runway_node =
+    Node::new(runway.id, NodeKind::Runway, NodeBehavior::GoTo, ());
+  let direction = (runway.end() - runway.start()).normalize_or_zero();
+  let remaining = (runway.end() - pos).dot(direction);
+
+  let mut exits: Vec<(f32, bool, Vec<Node<Vec2>>)> = pathfinder
+    .connections(&runway_node)
+    .into_iter()
+    .filter(|(node, _)| node.kind == NodeKind::Taxiway)
+    .filter_map(|(taxiway, intersection)| {
+      let distance = (intersection - pos).dot(direction);
+      if distance <= 0.0 || distance > remaining {
+        return None;
       }
+
+      // Turn off towards whichever end of the taxiway is the gentler turn.
+      let (turn, end) = [taxiway.value.0, taxiway.value.1]
+        .into_iter()
+        .filter(|end| end.distance_squared(intersection) > 1.0)
+        .map(|end| {
+          let heading = angle_between_points(intersection, end);
+          (delta_angle(runway.heading, heading).abs(), end)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+      let can_make = braking_speed(distance, turn_speed(turn)) >= speed;
+      let clear = intersection.move_towards(end, RUNWAY_EXIT_CLEARANCE);
+      let waypoints = vec![
+        Node::new(taxiway.name, taxiway.kind, NodeBehavior::GoTo, clear),
+        Node::new(taxiway.name, taxiway.kind, NodeBehavior::GoTo, intersection),
+      ];
+
+      Some((distance, can_make, waypoints))
+    })
+    .collect();
+  exits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+  let index = exits
+    .iter()
+    .position(|(_, can_make, _)| *can_make)
+    .or(exits.len().checked_sub(1))?;
+
+  Some(exits.swap_remove(index).2)
+}
+
+pub fn handle_taxi_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  waypoint_strings: &[Node<()>],
+  pathfinder: &Pathfinder,
+) {
+  if let AircraftState::Taxiing { current, .. }
+  | AircraftState::Parked { at: current, .. } = &aircraft.state
+  {
+    let mut destinations = waypoint_strings.iter().peekable();
+    let mut all_waypoints: Vec<Node<Vec2>> = Vec::new();
+
+    if destinations.peek().map(|d| d.name_and_kind_eq(current)) == Some(true) {
+      tracing::info!(
+        "Skipping {} as we are there.",
+        display_node_vec2(current)
+      );
+      destinations.next();
     }
+
+    let mut pos = aircraft.pos;
+    let mut heading = aircraft.heading;
+    let mut curr: Node<Vec2> = current.clone();
+    for destination in destinations {
+      let path = pathfinder.path_to(
+        Node {
+          name: curr.name,
+          kind: curr.kind,
+          behavior: curr.behavior,
+          value: (),
+        },
+        destination.clone(),
+        pos,
+        heading,
+      );
+
+      if let Some(path) = path {
+        pos = path.final_pos;
+        heading = path.final_heading;
+        curr = path.path.last().unwrap().clone();
+
+        all_waypoints.extend(path.path);
+      } else {
+        tracing::error!(
+          "Failed to find path for destination: {:?}, from: {:?}",
+          destination,
+          curr
+        );
+
+        let kind = format!("{:?}", destination.kind).to_lowercase();
+        let reason = if pathfinder.contains(destination) {
+          format!("no route to {kind} {}", destination.name)
+        } else {
+          format!("{kind} {} does not exist", destination.name)
+        };
+        bundle.events.push(
+          AircraftEvent::new(
+            aircraft.id,
+            EventKind::Callout(CommandWithFreq::new(
+              aircraft.id.to_string(),
+              aircraft.frequency,
+              CommandReply::UnableTaxi { reason },
+              Vec::new(),
+            )),
+          )
+          .into(),
+        );
+        return;
+      }
+    }
+
+    // If our destination is a gate or helipad, set our destination to it
+    // (otherwise it will be the enterance on the apron but not the gate)
+    if let Some(last) = all_waypoints.last() {
+      if matches!(last.kind, NodeKind::Gate | NodeKind::Helipad) {
+        if let Some(airport) =
+          closest_airport(&bundle.world.airspace, aircraft.pos)
+        {
+          let pos = airport.terminals.iter().find_map(|t| match last.kind {
+            NodeKind::Helipad => {
+              t.helipads.iter().find(|h| h.id == last.name).map(|h| h.pos)
+            }
+            _ => t.gates.iter().find(|g| g.id == last.name).map(|g| g.pos),
+          });
+          if let Some(pos) = pos {
+            all_waypoints.push(Node::new(
+              last.name,
+              last.kind,
+              NodeBehavior::Park,
+              pos,
+            ));
+          }
+        }
+      }
+    }
+
+    if all_waypoints.is_empty() {
+      return;
+    }
+
+    all_waypoints.reverse();
+
+    tracing::info!(
+      "Initiating taxi for {}: {:?}",
+      aircraft.id,
+      display_vec_node_vec2(&all_waypoints)
+    );
+
+    let current = current.clone();
+    if let AircraftState::Taxiing { waypoints, .. } = &mut aircraft.state {
+      *waypoints = all_waypoints;
+    } else {
+      aircraft.state = AircraftState::Taxiing {
+        current: current.clone(),
+        waypoints: all_waypoints,
+        state: TaxiingState::default(),
+      };
+    }
+  }
+
+  bundle.events.push(
+    AircraftEvent {
+      id: aircraft.id,
+      kind: EventKind::TaxiContinue,
+    }
+    .into(),
+  );
+}
+
+pub fn handle_takeoff_event(
+  aircraft: &mut Aircraft,
+  bundle: &mut Bundle,
+  runway_id: Intern<String>,
+) {
+  if let AircraftState::Taxiing {
+    current, waypoints, ..
+  } = &mut aircraft.state
+  {
+    // If we are at the runway
+    let airspace = &bundle.world.airspace;
+    if let Some((airport, runway)) = airspace
+      .airports
+      .iter()
+      .flat_map(|a| a.runways.iter().map(move |r| (a, r)))
+      .find(|(_, r)| r.id == runway_id)
+    {
+      if NodeKind::Runway == current.kind && current.name == runway_id {
+        if airspace.tailwind(runway.heading) > airspace.max_tailwind() {
+          let reply = CommandReply::UnableTailwind {
+            runway: runway.id.to_string(),
+            or: reciprocal_runway(airport, runway),
+          };
+          push_refusal(aircraft, bundle, reply);
+          return;
+        }
+
+        aircraft.target.speed = aircraft.flight_plan.speed;
+        aircraft.target.altitude = aircraft.flight_plan.altitude;
+        aircraft.heading = runway.heading;
+        aircraft.target.heading = runway.heading;
+
+        aircraft.state = AircraftState::Flying {
+          enroute: false,
+          waypoints: Vec::new(),
+        };
+
+        bundle.events.push(
+          AircraftEvent {
+            id: aircraft.id,
+            kind: EventKind::SuccessfulTakeoff,
+          }
+          .into(),
+        );
+        bundle.events.push(
+          AircraftEvent {
+            id: aircraft.id,
+            kind: EventKind::ResumeOwnNavigation { diversion: false },
+          }
+          .into(),
+        );
+      } else if let Some(runway) = waypoints.first_mut() {
+        if runway.kind == NodeKind::Runway && runway.name == runway_id {
+          runway.behavior = NodeBehavior::Takeoff;
+
+          bundle.events.push(
+            AircraftEvent::new(aircraft.id, EventKind::TaxiContinue).into(),
+          );
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use turborand::{rng::Rng, SeededCore};
+
+  use crate::{
+    engine::Engine,
+    entities::{
+      aircraft::{AircraftKind, FlightPlan, FlightSegment, DISCRETION_DELAY},
+      airport::{
+        Airport, Gate, MissedApproach, Procedure, Runway, Taxiway, Terminal,
+      },
+      airspace::{Airspace, Frequencies, Wind},
+      strip::FlightStrip,
+      world::{Connection, Game, World},
+    },
+    Line,
+  };
+
+  use super::*;
+
+  #[test]
+  fn go_around_climbs_to_missed_approach_altitude() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      missed_approach: MissedApproach {
+        altitude: 4000.0,
+        speed: 220.0,
+      },
+      ..Default::default()
+    };
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 180.0,
+      altitude: 1500.0,
+      state: AircraftState::Landing {
+        runway,
+        state: LandingState::Glideslope,
+      },
+      ..Default::default()
+    };
+    aircraft.sync_targets_to_vals();
+    game.push_aircraft(aircraft);
+
+    engine.events.push(
+      AircraftEvent::new(Intern::from_ref("AAL1234"), EventKind::GoAround)
+        .into(),
+    );
+    engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+    engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+
+    let aircraft = game.aircraft().first().unwrap();
+    assert!(matches!(aircraft.state, AircraftState::Flying { .. }));
+    assert_eq!(aircraft.target.altitude, 4000.0);
+    assert_eq!(aircraft.target.speed, 220.0);
+  }
+
+  #[test]
+  fn auto_tune_follows_flight_segments() {
+    let world = World {
+      airspace: Airspace {
+        id: Intern::from_ref("KSFO"),
+        radius: 10000.0,
+        frequencies: Frequencies {
+          approach: 118.5,
+          departure: 119.0,
+          tower: 120.5,
+          ground: 121.9,
+          center: 132.3,
+        },
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let runway = Runway {
+      id: Intern::from_ref("27"),
+      ..Default::default()
+    };
+    let taxiway = Node::new(
+      Intern::from_ref("A"),
+      NodeKind::Taxiway,
+      NodeBehavior::GoTo,
+      Vec2::ZERO,
+    );
+    let hold_short = Node::new(
+      runway.id,
+      NodeKind::Runway,
+      NodeBehavior::HoldShort,
+      Vec2::ZERO,
+    );
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Parked {
+        at: taxiway.clone(),
+        active: true,
+      },
+      flight_plan: FlightPlan::new(
+        Intern::from_ref("KSFO"),
+        Intern::from_ref("KLAX"),
+      ),
+      ..Default::default()
+    };
+
+    let segments = [
+      (
+        AircraftState::Taxiing {
+          current: taxiway.clone(),
+          waypoints: vec![hold_short.clone(), taxiway.clone()],
+          state: TaxiingState::Armed,
+        },
+        121.9,
+      ),
+      (
+        AircraftState::Taxiing {
+          current: taxiway.clone(),
+          waypoints: vec![hold_short],
+          state: TaxiingState::Holding,
+        },
+        120.5,
+      ),
+      (
+        AircraftState::Flying {
+          waypoints: Vec::new(),
+          enroute: false,
+        },
+        119.0,
+      ),
+      (
+        AircraftState::Flying {
+          waypoints: Vec::new(),
+          enroute: true,
+        },
+        132.3,
+      ),
+    ];
+
+    HandleAircraftEvent::run(&mut aircraft, &EventKind::AutoTune, &mut bundle);
+    assert_eq!(aircraft.frequency, 121.9);
+
+    for (state, frequency) in segments {
+      aircraft.state = state;
+      HandleAircraftEvent::run(
+        &mut aircraft,
+        &EventKind::AutoTune,
+        &mut bundle,
+      );
+      assert_eq!(aircraft.frequency, frequency);
+    }
+
+    // Arriving back into the airspace.
+    aircraft.flip_flight_plan();
+    aircraft.state = AircraftState::Flying {
+      waypoints: Vec::new(),
+      enroute: false,
+    };
+    HandleAircraftEvent::run(&mut aircraft, &EventKind::AutoTune, &mut bundle);
+    assert_eq!(aircraft.frequency, 118.5);
+
+    aircraft.state = AircraftState::Landing {
+      runway,
+      state: LandingState::Glideslope,
+    };
+    HandleAircraftEvent::run(&mut aircraft, &EventKind::AutoTune, &mut bundle);
+    assert_eq!(aircraft.frequency, 120.5);
+  }
+
+  #[test]
+  fn cleared_to_land_uses_expected_runway() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    for (id, heading) in [("27", 270.0), ("9", 90.0)] {
+      airport.add_runway(Runway {
+        id: Intern::from_ref(id),
+        heading,
+        length: 7000.0,
+        ..Default::default()
+      });
+    }
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        speed: 180.0,
+        altitude: 3000.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::ExpectRunway(Intern::from_ref("27")))
+        .into(),
+    );
+    let events = engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+    assert!(events.iter().any(|e| matches!(
+      e,
+      Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::ExpectRunway { runway },
+          ..
+        }),
+        ..
+      }) if runway == "27"
+    )));
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.expected_runway, Some(Intern::from_ref("27")));
+    assert!(matches!(aircraft.state, AircraftState::Flying { .. }));
+
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::Land(Intern::from_ref(""))).into(),
+    );
+    engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Landing { runway, .. } if runway.id.as_str() == "27"
+    ));
+    assert_eq!(aircraft.expected_runway, None);
+  }
+
+  #[test]
+  fn squawk_ident_sets_code_and_flashes_ident() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(Aircraft {
+      id,
+      ..Default::default()
+    });
+
+    engine
+      .events
+      .push(AircraftEvent::new(id, EventKind::SquawkIdent(4321)).into());
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.squawk, Some(4321));
+    assert!(aircraft.is_identing());
+
+    // Codes are octal, so an 8 isn't valid.
+    engine
+      .events
+      .push(AircraftEvent::new(id, EventKind::Squawk(4381)).into());
+    engine.tick(&world, &mut game, &mut rng, 1.0, IDENT_DURATION);
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.squawk, Some(4321));
+    assert!(!aircraft.is_identing());
+
+    engine
+      .events
+      .push(AircraftEvent::new(id, EventKind::Squawk(7700)).into());
+    engine.tick(&world, &mut game, &mut rng, 1.0, IDENT_DURATION);
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.squawk, Some(7700));
+    assert!(!aircraft.is_identing());
+  }
+
+  #[test]
+  fn landing_with_tailwind_is_refused() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    for (id, heading) in [("27", 270.0), ("9", 90.0)] {
+      airport.add_runway(Runway {
+        id: Intern::from_ref(id),
+        heading,
+        length: 7000.0,
+        ..Default::default()
+      });
+    }
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        wind: Wind {
+          heading: 90.0,
+          speed: 15.0,
+        },
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        speed: 180.0,
+        altitude: 3000.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::Land(Intern::from_ref("27"))).into(),
+    );
+    let events = engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+    assert!(events.iter().any(|e| matches!(
+      e,
+      Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableTailwind { runway, or },
+          ..
+        }),
+        ..
+      }) if runway == "27" && or.as_deref() == Some("9")
+    )));
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert!(matches!(aircraft.state, AircraftState::Flying { .. }));
+
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::Land(Intern::from_ref("9"))).into(),
+    );
+    engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Landing { runway, .. } if runway.id.as_str() == "9"
+    ));
+  }
+
+  #[test]
+  fn monitor_tunes_without_checking_in() {
+    let world = World {
+      airspace: Airspace {
+        frequencies: Frequencies {
+          tower: 120.5,
+          ..Default::default()
+        },
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      frequency: 118.5,
+      ..Default::default()
+    };
+
+    let tower = "tower".to_string();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Monitor(tower.clone()),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.frequency, 120.5);
+    assert!(bundle.events.is_empty());
+
+    aircraft.frequency = 118.5;
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::NamedFrequency(tower),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.frequency, 120.5);
+
+    let check_ins: Vec<String> = bundle
+      .events
+      .iter()
+      .filter_map(|e| match e {
+        Event::Aircraft(AircraftEvent {
+          kind: EventKind::Callout(command),
+          ..
+        }) if command.frequency == 120.5 => {
+          Some(command.to_text(&world.airlines))
+        }
+        _ => None,
+      })
+      .collect();
+    assert_eq!(
+      check_ins,
+      vec!["Tower, American Airlines 1234, with you.".to_string()]
+    );
+  }
+
+  #[test]
+  fn altitude_above_ceiling_is_clamped() {
+    let world = World {
+      airspace: Airspace {
+        radius: 50000.0,
+        ceiling: Some(10000.0),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      altitude: 5000.0,
+      ..Default::default()
+    };
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Altitude(8000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 8000.0);
+    assert!(bundle.events.is_empty());
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Altitude(14000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 10000.0);
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableAltitude { altitude },
+          ..
+        }),
+        ..
+      })] if *altitude == 10000.0
+    ));
+
+    // Above the ceiling, we are no longer in the airspace.
+    aircraft.altitude = 12000.0;
+    assert_eq!(aircraft.segment(&world.airspace), FlightSegment::Enroute);
+  }
+
+  #[test]
+  fn climb_and_descend_require_the_right_direction() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      altitude: 8000.0,
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Descend(4000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 4000.0);
+    assert!(bundle.events.is_empty());
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Descend(10000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 4000.0);
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableDescend { altitude },
+          ..
+        }),
+        ..
+      })] if *altitude == 10000.0
+    ));
+
+    bundle.events.clear();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Climb(6000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 4000.0);
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableClimb { altitude },
+          ..
+        }),
+        ..
+      })] if *altitude == 6000.0
+    ));
+
+    bundle.events.clear();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Climb(12000.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.altitude, 12000.0);
+    assert!(bundle.events.is_empty());
+  }
+
+  #[test]
+  fn speeds_are_floored_at_the_minimum() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 8000.0,
+      kind: AircraftKind::A21N,
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Speed(100.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.speed, 140.0);
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableSpeed { speed, minimum },
+          ..
+        }),
+        ..
+      })] if *speed == 100.0 && *minimum == 140.0
+    ));
+
+    bundle.events.clear();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Speed(180.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.target.speed, 180.0);
+    assert!(bundle.events.is_empty());
+  }
+
+  #[cfg(debug_assertions)]
+  #[test]
+  fn reposition_sets_state_and_targets() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      altitude: 5000.0,
+      heading: 90.0,
+      speed: 250.0,
+      ..Default::default()
+    }
+    .with_synced_targets();
+    aircraft.target.altitude = 8000.0;
+
+    let kind: EventKind = Task::Reposition {
+      pos: Vec2::new(-12000.0, 3000.0),
+      altitude: 3000.0,
+      heading: 270.0,
+      speed: 180.0,
+    }
+    .into();
+    HandleAircraftEvent::run(&mut aircraft, &kind, &mut bundle);
+
+    assert_eq!(aircraft.pos, Vec2::new(-12000.0, 3000.0));
+    assert_eq!(
+      (aircraft.altitude, aircraft.heading, aircraft.speed),
+      (3000.0, 270.0, 180.0)
+    );
+    assert_eq!(
+      (
+        aircraft.target.altitude,
+        aircraft.target.heading,
+        aircraft.target.speed
+      ),
+      (3000.0, 270.0, 180.0)
+    );
+    assert!(bundle.events.is_empty());
+  }
+
+  #[test]
+  fn discretionary_descent_is_deferred() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        altitude: 8000.0,
+        speed: 250.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    engine.events.push(
+      AircraftEvent::new(id, EventKind::AltitudeAtDiscretion(4000.0)).into(),
+    );
+    engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.target.altitude, 8000.0);
+    let deferred = aircraft.deferred_altitude.unwrap();
+    assert_eq!(deferred.value, 4000.0);
+    assert!(deferred.at <= DISCRETION_DELAY);
+
+    let mut now = Duration::ZERO;
+    while now <= DISCRETION_DELAY + Duration::from_secs(1) {
+      now += Duration::from_secs(1);
+      engine.tick(&world, &mut game, &mut rng, 1.0, now);
+    }
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.target.altitude, 4000.0);
+    assert!(aircraft.deferred_altitude.is_none());
+  }
+
+  #[test]
+  fn present_heading_stops_following_the_route() {
+    let world = World::default();
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    game.push_aircraft(
+      Aircraft {
+        id,
+        heading: 45.0,
+        speed: 250.0,
+        altitude: 7000.0,
+        state: AircraftState::Flying {
+          waypoints: vec![
+            new_vor(Intern::from_ref("KLAX"), Vec2::new(0.0, 200000.0)),
+            new_vor(Intern::from_ref("KSFO"), Vec2::new(0.0, 100000.0)),
+          ],
+          enroute: false,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    engine
+      .events
+      .push(AircraftEvent::new(id, Task::MaintainPresentHeading.into()).into());
+    for _ in 0..60 {
+      engine.tick(&world, &mut game, &mut rng, 1.0, Duration::ZERO);
+    }
+
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    assert_eq!(aircraft.heading, 45.0);
+    assert_eq!(aircraft.target.heading, 45.0);
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Flying { waypoints, .. } if waypoints.is_empty()
+    ));
+  }
+
+  #[test]
+  fn headings_are_normalized() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = Aircraft {
+      speed: 250.0,
+      altitude: 7000.0,
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    // Headings from outside of the parser, such as the API, can be out of
+    // range.
+    for (heading, expected) in [(370.0, 10.0), (-20.0, 340.0), (360.0, 0.0)] {
+      HandleAircraftEvent::run(
+        &mut aircraft,
+        &EventKind::Heading(heading),
+        &mut bundle,
+      );
+      assert_eq!(aircraft.target.heading, expected, "{heading}");
+    }
+  }
+
+  #[test]
+  fn nav_mode_follows_headings_and_directs() {
+    let world = World {
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(0.0, 60000.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      state: AircraftState::Flying {
+        waypoints: vec![new_vor(
+          Intern::from_ref("AAA"),
+          Vec2::new(0.0, 20000.0),
+        )],
+        enroute: false,
+      },
+      ..Default::default()
+    }
+    .with_synced_targets();
+    assert_eq!(aircraft.nav_mode, NavMode::LNav);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Heading(90.0),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.nav_mode, NavMode::Heading);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::DirectNearest,
+      &mut bundle,
+    );
+    assert_eq!(aircraft.nav_mode, NavMode::LNav);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::MaintainPresentHeading,
+      &mut bundle,
+    );
+    assert_eq!(aircraft.nav_mode, NavMode::Heading);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::ResumeArrival,
+      &mut bundle,
+    );
+    assert_eq!(aircraft.nav_mode, NavMode::LNav);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::ExpectVectors,
+      &mut bundle,
+    );
+    assert_eq!(aircraft.approach_mode, ApproachMode::Vectors);
+    assert_eq!(aircraft.nav_mode, NavMode::Heading);
+  }
+
+  #[test]
+  fn resume_arrival_rejoins_at_the_next_fix() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      state: AircraftState::Flying {
+        waypoints: vec![
+          new_vor(Intern::from_ref("CCC"), Vec2::new(0.0, 60000.0)),
+          new_vor(Intern::from_ref("BBB"), Vec2::new(0.0, 40000.0)),
+          new_vor(Intern::from_ref("AAA"), Vec2::new(0.0, 20000.0)),
+        ],
+        enroute: false,
+      },
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Heading(90.0),
+      &mut bundle,
+    );
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Flying { waypoints, .. } if waypoints.is_empty()
+    ));
+    assert_eq!(aircraft.vectored_from.len(), 3);
+
+    // Vectored around and heading north again, now past AAA.
+    aircraft.pos = Vec2::new(5000.0, 30000.0);
+    aircraft.heading = 0.0;
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::ResumeArrival.into(),
+      &mut bundle,
+    );
+
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("expected to be flying");
+    };
+    let names: Vec<&str> = waypoints.iter().map(|w| w.name.as_str()).collect();
+    assert_eq!(names, vec!["CCC", "BBB"]);
+    assert!(aircraft.vectored_from.is_empty());
+  }
+
+  #[test]
+  fn reroutes_replan_the_route() {
+    let world = World {
+      connections: vec![Connection {
+        id: Intern::from_ref("KSJC"),
+        pos: Vec2::new(0.0, -40000.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let klax = new_vor(Intern::from_ref("KLAX"), Vec2::new(0.0, -200000.0));
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Flying {
+        waypoints: vec![klax.clone()],
+        enroute: false,
+      },
+      ..Default::default()
+    };
+    aircraft.flight_plan.plan_route(Vec2::ZERO, &[klax]);
+    aircraft.pos = Vec2::new(0.0, -100000.0);
+    assert!((aircraft.flight_progress() - 0.5).abs() < 0.001);
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Direct {
+        fix: Intern::from_ref("KSJC"),
+        radial: None,
+        distance: None,
+      }
+      .into(),
+      &mut bundle,
+    );
+    assert_eq!(aircraft.flight_plan.distances, vec![60000.0, 220000.0]);
+    assert_eq!(aircraft.flight_progress(), 0.0);
+
+    // Vectored off of it, there is no route left to plan.
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Heading(90.0).into(),
+      &mut bundle,
+    );
+    assert!(aircraft.flight_plan.distances.is_empty());
+    assert_eq!(aircraft.flight_progress(), 0.0);
+  }
+
+  #[test]
+  fn route_strings_load_known_fixes() {
+    let world = World {
+      airspace: Airspace {
+        airports: vec![Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO)],
+        ..Default::default()
+      },
+      connections: vec![
+        Connection {
+          id: Intern::from_ref("KSJC"),
+          pos: Vec2::new(0.0, -40000.0),
+          ..Default::default()
+        },
+        Connection {
+          id: Intern::from_ref("KLAX"),
+          pos: Vec2::new(0.0, -200000.0),
+          ..Default::default()
+        },
+      ],
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      flight_plan: FlightPlan::new(
+        Intern::from_ref("KSFO"),
+        Intern::from_ref("KLAX"),
+      ),
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    let route = "KSFO SID KSJC J80 KLAX STAR";
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::SetRoute(route.to_string()).into(),
+      &mut bundle,
+    );
+
+    assert_eq!(aircraft.flight_plan.route, route);
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("not flying");
+    };
+    // Waypoints are flown from the back.
+    let names: Vec<&str> =
+      waypoints.iter().rev().map(|w| w.name.as_str()).collect();
+    assert_eq!(names, vec!["KSJC", "KLAX"]);
+    assert_eq!(aircraft.flight_plan.distances, vec![40000.0, 200000.0]);
+
+    let strip = FlightStrip::new(&aircraft, &world.airspace);
+    assert_eq!(strip.route, route);
+
+    // A departure keeps leaving the airspace and completing its flight.
+    let klax = Vec2::new(0.0, -200000.0);
+    aircraft.state = AircraftState::Flying {
+      waypoints: vec![
+        new_vor(Intern::from_ref("KLAX"), klax)
+          .with_name(Intern::from_ref("APRT"))
+          .with_behavior(vec![EventKind::CompleteFlight, EventKind::Delete]),
+        new_vor(Intern::from_ref("KLAX"), Vec2::new(0.0, -100000.0))
+          .with_name(Intern::from_ref("TRSN"))
+          .with_behavior(vec![EventKind::EnRoute(true)]),
+      ],
+      enroute: false,
+    };
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::SetRoute("KSFO KSJC KLAX".to_string()).into(),
+      &mut bundle,
+    );
+
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("not flying");
+    };
+    let route: Vec<(&str, &[EventKind])> = waypoints
+      .iter()
+      .rev()
+      .map(|w| (w.name.as_str(), w.value.then.as_slice()))
+      .collect();
+    assert_eq!(
+      route,
+      vec![
+        ("KSJC", [EventKind::EnRoute(true)].as_slice()),
+        (
+          "APRT",
+          [EventKind::CompleteFlight, EventKind::Delete].as_slice()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn direct_to_a_radial_and_distance() {
+    let world = World {
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(0.0, -200000.0),
+        ..Default::default()
+      }],
+      magnetic_variation: 10.0,
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Direct {
+        fix: Intern::from_ref("KLAX"),
+        radial: Some(80.0),
+        distance: Some(15.0),
+      }
+      .into(),
+      &mut bundle,
+    );
+
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("not flying");
+    };
+    let fix = waypoints.last().unwrap();
+    assert_eq!(fix.name.as_str(), "KLAX/080/15");
+    // The 080 radial is 090 true with 10 degrees of east variation.
+    let klax = Vec2::new(0.0, -200000.0);
+    assert!((angle_between_points(klax, fix.value.to) - 90.0).abs() < 0.01);
+    assert!(
+      (klax.distance(fix.value.to) - 15.0 * NAUTICALMILES_TO_FEET).abs() < 1.0
+    );
+  }
+
+  #[test]
+  fn procedures_load_their_fixes_in_order() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.procedures.push(Procedure {
+      id: Intern::from_ref("PORTE3"),
+      fixes: ["KSJC", "KLAX"].map(Intern::from_ref).to_vec(),
+    });
+    airport.procedures.push(Procedure {
+      id: Intern::from_ref("SNTNA1"),
+      fixes: ["KSJC", "ZZZZ"].map(Intern::from_ref).to_vec(),
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      connections: vec![
+        Connection {
+          id: Intern::from_ref("KSJC"),
+          pos: Vec2::new(0.0, -40000.0),
+          ..Default::default()
+        },
+        Connection {
+          id: Intern::from_ref("KLAX"),
+          pos: Vec2::new(0.0, -200000.0),
+          ..Default::default()
+        },
+      ],
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let exit = new_vor(Intern::from_ref("APRT"), Vec2::new(0.0, -300000.0))
+      .with_behavior(vec![EventKind::CompleteFlight, EventKind::Delete]);
+    let transition =
+      new_vor(Intern::from_ref("TRSN"), Vec2::new(0.0, -20000.0))
+        .with_behavior(vec![EventKind::EnRoute(true)]);
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      state: AircraftState::Flying {
+        waypoints: vec![exit.clone(), transition],
+        enroute: false,
+      },
+      ..Default::default()
+    }
+    .with_synced_targets();
+
+    // A procedure with a fix that we don't know is refused as a whole.
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Procedure(Intern::from_ref("SNTNA1")).into(),
+      &mut bundle,
+    );
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnknownFix { fix },
+          ..
+        }),
+        ..
+      })] if fix == "ZZZZ"
+    ));
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Flying { waypoints, .. } if waypoints.len() == 2
+    ));
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Procedure(Intern::from_ref("PORTE3")).into(),
+      &mut bundle,
+    );
+
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("not flying");
+    };
+    // Waypoints are flown from the back, and we still end at our exit.
+    let fixes: Vec<(&str, Vec2)> = waypoints
+      .iter()
+      .rev()
+      .map(|w| (w.name.as_str(), w.value.to))
+      .collect();
+    assert_eq!(
+      fixes,
+      vec![
+        ("KSJC", Vec2::new(0.0, -40000.0)),
+        ("KLAX", Vec2::new(0.0, -200000.0)),
+        ("APRT", Vec2::new(0.0, -300000.0)),
+      ]
+    );
+    assert_eq!(waypoints[0], exit);
+    // We go en route at the end of the procedure instead of the transition.
+    assert_eq!(waypoints[1].value.then, vec![EventKind::EnRoute(true)]);
+  }
+
+  #[test]
+  fn direct_approach_routes_and_arms_together() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_runway(Runway {
+      id: Intern::from_ref("27"),
+      heading: 270.0,
+      length: 7000.0,
+      ..Default::default()
+    });
+    let world = World {
+      airspace: Airspace {
+        radius: 200000.0,
+        airports: vec![airport],
+        ..Default::default()
+      },
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(40000.0, 0.0),
+        transition: Vec2::new(40000.0, 0.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    let mut game = Game::default();
+    let mut engine = Engine::default();
+    let mut rng = Rng::with_seed(0);
+
+    let id = Intern::from_ref("AAL1234");
+    let start = Vec2::new(30000.0, 20000.0);
+    let fix = Vec2::new(40000.0, 0.0);
+    game.push_aircraft(
+      Aircraft {
+        id,
+        pos: start,
+        heading: angle_between_points(start, fix),
+        speed: 250.0,
+        altitude: 4000.0,
+        state: AircraftState::Flying {
+          waypoints: vec![new_vor(Intern::from_ref("AAA"), Vec2::ZERO)],
+          enroute: false,
+        },
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+    engine.events.push(
+      AircraftEvent::new(
+        id,
+        Task::DirectApproach {
+          fix: Intern::from_ref("KLAX"),
+          runway: Intern::from_ref("27"),
+        }
+        .into(),
+      )
+      .into(),
+    );
+
+    engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+    let aircraft = game.aircraft_by_id(id).unwrap();
+    let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+      panic!("expected to be flying");
+    };
+    assert_eq!(waypoints.len(), 1);
+    assert_eq!(waypoints[0].name.as_str(), "KLAX");
+    assert_eq!(
+      waypoints[0].value.then,
+      vec![EventKind::Land(Intern::from_ref("27"))]
+    );
+    assert_eq!(aircraft.expected_runway, Some(Intern::from_ref("27")));
+    assert_eq!(aircraft.target.heading, angle_between_points(start, fix));
+
+    // Once at the fix, we start the approach.
+    for _ in 0..1000 {
+      engine.tick(&world, &mut game, &mut rng, 0.5, Duration::ZERO);
+      if matches!(
+        game.aircraft_by_id(id).unwrap().state,
+        AircraftState::Landing { .. }
+      ) {
+        return;
+      }
+    }
+    panic!("never started the approach");
+  }
+
+  #[test]
+  fn direct_approach_refuses_unknown_fixes_and_runways() {
+    let world = World {
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(40000.0, 0.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let waypoints = vec![new_vor(Intern::from_ref("AAA"), Vec2::ZERO)];
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Flying {
+        waypoints: waypoints.clone(),
+        enroute: false,
+      },
+      ..Default::default()
+    };
+
+    for (fix, expected) in [
+      (
+        "ZZZZ",
+        CommandReply::UnknownFix {
+          fix: "ZZZZ".to_string(),
+        },
+      ),
+      (
+        "KLAX",
+        CommandReply::UnknownRunway {
+          runway: "27".to_string(),
+        },
+      ),
+    ] {
+      bundle.events.clear();
+      HandleAircraftEvent::run(
+        &mut aircraft,
+        &Task::DirectApproach {
+          fix: Intern::from_ref(fix),
+          runway: Intern::from_ref("27"),
+        }
+        .into(),
+        &mut bundle,
+      );
+
+      let replies: Vec<&CommandReply> = bundle
+        .events
+        .iter()
+        .filter_map(|e| match e {
+          Event::Aircraft(AircraftEvent {
+            kind: EventKind::Callout(command),
+            ..
+          }) => Some(&command.reply),
+          _ => None,
+        })
+        .collect();
+      assert_eq!(replies, vec![&expected]);
+      assert_eq!(
+        aircraft.state,
+        AircraftState::Flying {
+          waypoints: waypoints.clone(),
+          enroute: false,
+        }
+      );
+      assert!(aircraft.expected_runway.is_none());
+    }
+  }
+
+  /// The replies in the callouts of `bundle`.
+  fn callout_replies(bundle: &Bundle) -> Vec<CommandReply> {
+    bundle
+      .events
+      .iter()
+      .filter_map(|e| match e {
+        Event::Aircraft(AircraftEvent {
+          kind: EventKind::Callout(command),
+          ..
+        }) => Some(command.reply.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// An aircraft holding over AAA, waiting to be vectored.
+  fn holding_aircraft() -> Aircraft {
+    Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Flying {
+        waypoints: vec![new_vor(Intern::from_ref("AAA"), Vec2::ZERO)],
+        enroute: false,
+      },
+      approach_mode: ApproachMode::Vectors,
+      nav_mode: NavMode::Heading,
+      hold: Some(Hold {
+        fix: Intern::from_ref("AAA"),
+        pos: Vec2::ZERO,
+        inbound_course: 90.0,
+        direction: HoldDirection::default(),
+        leg_seconds: 60.0,
+        leg: HoldLeg::default(),
+        elapsed: 0.0,
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn direct_refuses_unknown_fixes() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = holding_aircraft();
+    let before = aircraft.clone();
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Direct {
+        fix: Intern::from_ref("ZZZZ"),
+        radial: None,
+        distance: None,
+      }
+      .into(),
+      &mut bundle,
+    );
+
+    assert_eq!(
+      callout_replies(&bundle),
+      vec![CommandReply::UnknownFix {
+        fix: "ZZZZ".to_string(),
+      }]
+    );
+    assert_eq!(aircraft, before);
+  }
+
+  #[test]
+  fn unknown_procedures_are_refused() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.procedures.push(Procedure {
+      id: Intern::from_ref("SNTNA1"),
+      fixes: vec![Intern::from_ref("ZZZZ")],
+    });
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = holding_aircraft();
+    let before = aircraft.clone();
+
+    for (id, expected) in [
+      (
+        "PORTE3",
+        CommandReply::UnknownProcedure {
+          procedure: "PORTE3".to_string(),
+        },
+      ),
+      (
+        "SNTNA1",
+        CommandReply::UnknownFix {
+          fix: "ZZZZ".to_string(),
+        },
+      ),
+    ] {
+      bundle.events.clear();
+      HandleAircraftEvent::run(
+        &mut aircraft,
+        &Task::Procedure(Intern::from_ref(id)).into(),
+        &mut bundle,
+      );
+
+      assert_eq!(callout_replies(&bundle), vec![expected]);
+      // Still holding, as if we were never told.
+      assert_eq!(aircraft, before);
+    }
+  }
+
+  #[test]
+  fn diversions_land_into_the_wind() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    for (id, heading) in [("9", 90.0), ("27", 270.0)] {
+      airport.add_runway(Runway {
+        id: Intern::from_ref(id),
+        heading,
+        length: 7000.0,
+        ..Default::default()
+      });
+    }
+
+    for (wind_heading, expected) in [(90.0, "9"), (270.0, "27")] {
+      let world = World {
+        airspace: Airspace {
+          wind: Wind {
+            heading: wind_heading,
+            speed: 15.0,
+          },
+          airports: vec![airport.clone()],
+          ..Default::default()
+        },
+        ..Default::default()
+      };
+      let mut rng = Rng::with_seed(0);
+      let mut bundle =
+        Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+      let mut aircraft = Aircraft {
+        pos: Vec2::new(0.0, 30000.0),
+        state: AircraftState::Flying {
+          waypoints: Vec::new(),
+          enroute: false,
+        },
+        ..Default::default()
+      };
+
+      HandleAircraftEvent::run(
+        &mut aircraft,
+        &EventKind::ResumeOwnNavigation { diversion: true },
+        &mut bundle,
+      );
+
+      let runway = Intern::from_ref(expected);
+      assert_eq!(aircraft.expected_runway, Some(runway), "{wind_heading}");
+      let AircraftState::Flying { waypoints, .. } = &aircraft.state else {
+        panic!("expected to be flying, but was {:?}", aircraft.state);
+      };
+      assert_eq!(
+        waypoints.first().map(|w| w.value.then.as_slice()),
+        Some([EventKind::Land(runway)].as_slice())
+      );
+    }
+  }
+
+  #[test]
+  fn hold_over_unknown_fix_is_refused() {
+    let world = World::default();
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Flying {
+        waypoints: Vec::new(),
+        enroute: false,
+      },
+      ..Default::default()
+    };
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::Hold {
+        fix: Intern::from_ref("ZZZZ"),
+        direction: HoldDirection::Right,
+        leg_seconds: 60.0,
+      }
+      .into(),
+      &mut bundle,
+    );
+
+    assert!(aircraft.hold.is_none());
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnknownFix { fix },
+          ..
+        }),
+        ..
+      })] if fix == "ZZZZ"
+    ));
+  }
+
+  #[test]
+  fn taxi_to_unknown_taxiway_is_refused() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("A"),
+      Vec2::new(0.0, 0.0),
+      Vec2::new(1000.0, 0.0),
+    ));
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("B"),
+      Vec2::new(500.0, -500.0),
+      Vec2::new(500.0, 500.0),
+    ));
+    airport.calculate_waypoints();
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let current = Node::new(
+      Intern::from_ref("A"),
+      NodeKind::Taxiway,
+      NodeBehavior::GoTo,
+      Vec2::new(200.0, 0.0),
+    );
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      pos: current.value,
+      heading: 90.0,
+      state: AircraftState::Taxiing {
+        current: current.clone(),
+        waypoints: Vec::new(),
+        state: TaxiingState::Holding,
+      },
+      ..Default::default()
+    };
+
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &EventKind::Taxi(vec![Node::new(
+        Intern::from_ref("Z"),
+        NodeKind::Taxiway,
+        NodeBehavior::GoTo,
+        (),
+      )]),
+      &mut bundle,
+    );
+
+    assert_eq!(aircraft.pos, current.value);
+    assert!(matches!(
+      &aircraft.state,
+      AircraftState::Taxiing {
+        waypoints,
+        state: TaxiingState::Holding,
+        ..
+      } if waypoints.is_empty()
+    ));
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::UnableTaxi { reason },
+          ..
+        }),
+        ..
+      })] if reason == "taxiway Z does not exist"
+    ));
+  }
+
+  #[test]
+  fn taxi_waits_for_clearance() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.requires_clearance = true;
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("T"),
+      Vec2::new(0.0, 0.0),
+      Vec2::new(1000.0, 0.0),
+    ));
+    let apron = Line::new(Vec2::new(200.0, -100.0), Vec2::new(200.0, 100.0));
+    airport.add_terminal(Terminal {
+      id: Intern::from_ref("A"),
+      a: apron.0,
+      b: apron.1,
+      c: apron.1 + Vec2::X * 100.0,
+      d: apron.0 + Vec2::X * 100.0,
+      gates: vec![Gate {
+        id: Intern::from_ref("1"),
+        pos: Vec2::new(250.0, 50.0),
+        heading: 90.0,
+        terminal: None,
+      }],
+      helipads: Vec::new(),
+      apron,
+    });
+    airport.calculate_waypoints();
+    let world = World {
+      airspace: Airspace {
+        airports: vec![airport],
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+    let mut bundle = Bundle::from_world(&world, &mut rng, 1.0, Duration::ZERO);
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      pos: Vec2::new(250.0, 50.0),
+      heading: 90.0,
+      state: AircraftState::Parked {
+        at: Node::new(
+          Intern::from_ref("A1"),
+          NodeKind::Gate,
+          NodeBehavior::Park,
+          Vec2::new(250.0, 50.0),
+        ),
+        active: true,
+      },
+      flight_plan: FlightPlan::new(
+        Intern::from_ref("KSFO"),
+        Intern::from_ref("KLAX"),
+      ),
+      ..Default::default()
+    };
+    let taxi = EventKind::Taxi(vec![Node::new(
+      Intern::from_ref("T"),
+      NodeKind::Taxiway,
+      NodeBehavior::GoTo,
+      (),
+    )]);
+
+    HandleAircraftEvent::run(&mut aircraft, &taxi, &mut bundle);
+    assert!(matches!(aircraft.state, AircraftState::Parked { .. }));
+    assert!(aircraft.needs_clearance(&world.airspace));
+
+    // 8 isn't an octal digit.
+    bundle.events.clear();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::IssueClearance(4528).into(),
+      &mut bundle,
+    );
+    assert!(!aircraft.ifr_cleared);
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::WithCallsign { .. },
+          ..
+        }),
+        ..
+      })]
+    ));
+
+    bundle.events.clear();
+    HandleAircraftEvent::run(
+      &mut aircraft,
+      &Task::IssueClearance(4521).into(),
+      &mut bundle,
+    );
+    assert!(aircraft.ifr_cleared);
+    assert_eq!(aircraft.squawk, Some(4521));
+    assert!(matches!(
+      bundle.events.as_slice(),
+      [Event::Aircraft(AircraftEvent {
+        kind: EventKind::Callout(CommandWithFreq {
+          reply: CommandReply::ClearanceReadback { .. },
+          ..
+        }),
+        ..
+      })]
+    ));
+
+    HandleAircraftEvent::run(&mut aircraft, &taxi, &mut bundle);
+    assert!(matches!(aircraft.state, AircraftState::Taxiing { .. }));
   }
 }
@@ -1,6 +1,8 @@
 pub mod effects;
 pub mod events;
 
+use std::time::Duration;
+
 use events::EventKind;
 use glam::Vec2;
 use internment::Intern;
@@ -8,15 +10,19 @@ use serde::{Deserialize, Serialize};
 use turborand::{rng::Rng, TurboRand};
 
 use crate::{
+  airline::Airlines,
   angle_between_points,
-  pathfinder::{new_vor, Node, NodeVORData},
-  ENROUTE_TIME_MULTIPLIER,
+  command::HoldDirection,
+  engine::Bundle,
+  headwind_component,
+  pathfinder::{new_vor, Node, NodeBehavior, NodeKind, NodeVORData},
+  ENROUTE_TIME_MULTIPLIER, KNOT_TO_FEET_PER_SECOND,
 };
 
 use super::{
   airport::{Gate, Runway},
-  airspace::Airspace,
-  world::Connection,
+  airspace::{Airspace, Wind},
+  world::{closest_airport, Connection},
 };
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
@@ -24,6 +30,10 @@ pub struct AircraftTargets {
   pub heading: f32,
   pub speed: f32,
   pub altitude: f32,
+  /// A Mach number to fly instead of `speed`, which it is converted to for
+  /// our altitude.
+  #[serde(default)]
+  pub mach: Option<f32>,
 }
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -45,6 +55,10 @@ pub enum LandingState {
   /// Once established on the glideslope, descend.
   Glideslope,
 
+  /// Flying a low pattern to line up with a runway other than the one we
+  /// flew the approach to.
+  Circling,
+
   /// We have landed.
   Touchdown,
 
@@ -68,6 +82,24 @@ pub enum TaxiingState {
 
   /// Player or waypoint ovveride. Won't move unless a continue is given.
   Holding,
+
+  /// Cleared to depart, but waiting for the aircraft ahead in the departure
+  /// queue to go first.
+  Queued,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// Why a taxiing aircraft has stopped.
+pub enum TaxiStopReason {
+  /// Another aircraft is in the way.
+  CollisionAhead,
+  /// Reached a hold short line.
+  HoldShort,
+  /// Waiting for the aircraft ahead in the departure queue.
+  RunwayOccupied,
+  /// Told to hold by the player.
+  PlayerHold,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -102,6 +134,106 @@ impl Default for AircraftState {
   }
 }
 
+/// Who steers us along our route once we are inside the airspace.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApproachMode {
+  /// We fly our published route by ourselves.
+  #[default]
+  PilotNav,
+  /// We hold our heading and wait for the controller's vectors, keeping our
+  /// route for when we are told to resume it. Our [`NavMode`] is `Heading`
+  /// while we wait.
+  Vectors,
+}
+
+/// The leg of a holding pattern that we are on.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldLeg {
+  /// Flying to the fix to enter the hold.
+  #[default]
+  Entry,
+  /// Turning away from the fix and flying the outbound leg.
+  Outbound,
+  /// Turning back and flying to the fix.
+  Inbound,
+}
+
+/// A racetrack holding pattern over a fix.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hold {
+  pub fix: Intern<String>,
+  pub pos: Vec2,
+  /// The course we fly inbound to the fix, in degrees.
+  pub inbound_course: f32,
+  pub direction: HoldDirection,
+  /// How long we fly the outbound leg for, in seconds.
+  pub leg_seconds: f32,
+  pub leg: HoldLeg,
+  /// How long we have flown straight on the outbound leg, in seconds.
+  pub elapsed: f32,
+}
+
+/// What we are steering by, so that an aircraft that has a route but was
+/// given a heading isn't mistaken for one that is flying its route.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum NavMode {
+  /// We fly the heading that we were assigned.
+  Heading,
+  /// We fly our waypoints.
+  #[default]
+  LNav,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The part of a flight that an aircraft is in, as seen by ATC.
+pub enum FlightSegment {
+  Parked,
+  Taxi,
+  /// Holding short of or lined up on the departure runway.
+  Takeoff,
+  Departure,
+  Enroute,
+  Approach,
+  Landing,
+}
+
+/// What to include when describing an aircraft as text, beyond its callsign
+/// and its heading, speed and altitude.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToTextOptions {
+  pub segment: bool,
+  pub flight_plan: bool,
+  pub frequency: bool,
+  pub kind: bool,
+}
+
+impl ToTextOptions {
+  /// Only the callsign, heading, speed and altitude.
+  pub fn terse() -> Self {
+    Self::default()
+  }
+
+  /// Everything that we know about the aircraft.
+  pub fn verbose() -> Self {
+    Self {
+      segment: true,
+      flight_plan: true,
+      frequency: true,
+      kind: true,
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FlightPlan {
   // To and From
@@ -111,6 +243,15 @@ pub struct FlightPlan {
   // IFR Clearance
   pub speed: f32,
   pub altitude: f32,
+
+  /// The filed route as written, such as "KSFO SID KSJC J80 KLAX". Only
+  /// the fixes in it that we know of are flown.
+  #[serde(default)]
+  pub route: String,
+  /// How far along the planned route each of its waypoints is, in the order
+  /// they are flown, from where the route was planned.
+  #[serde(default)]
+  pub distances: Vec<f32>,
 }
 
 impl Default for FlightPlan {
@@ -121,6 +262,9 @@ impl Default for FlightPlan {
 
       speed: 220.0,
       altitude: 3000.0,
+
+      route: String::new(),
+      distances: Vec::new(),
     }
   }
 }
@@ -133,6 +277,22 @@ impl FlightPlan {
       ..Self::default()
     }
   }
+
+  /// Plans the route of `waypoints`, which are flown from the back, starting
+  /// from `start`.
+  pub fn plan_route(&mut self, start: Vec2, waypoints: &[Node<NodeVORData>]) {
+    let mut from = start;
+    let mut distance = 0.0;
+    self.distances = waypoints
+      .iter()
+      .rev()
+      .map(|wp| {
+        distance += from.distance(wp.value.to);
+        from = wp.value.to;
+        distance
+      })
+      .collect();
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -157,6 +317,8 @@ pub struct AircraftStats {
   pub min_speed: f32,
   /// Maximum speed in knots
   pub max_speed: f32,
+  /// Strongest crosswind we can land in, in knots
+  pub max_crosswind: f32,
 
   // Performance
   /// V2 speed in knots (when rotate)
@@ -179,11 +341,12 @@ pub struct AircraftStats {
   pub seats: usize,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AircraftKind {
   // Airbus
   /// https://contentzone.eurocontrol.int/aircraftperformance/details.aspx?ICAO=A21N
+  #[default]
   A21N,
   /// https://contentzone.eurocontrol.int/aircraftperformance/details.aspx?ICAO=A333
   A333,
@@ -204,18 +367,32 @@ pub enum AircraftKind {
 }
 
 impl AircraftKind {
+  /// A random kind, with narrowbodies more common than widebodies.
+  pub fn random(rng: &mut Rng) -> Self {
+    let total: usize = KIND_WEIGHTS.iter().map(|(_, weight)| weight).sum();
+    let mut pick = rng.usize(0..total);
+    for (kind, weight) in KIND_WEIGHTS.iter() {
+      if pick < *weight {
+        return kind.clone();
+      }
+      pick -= weight;
+    }
+
+    AircraftKind::A21N
+  }
+
   pub fn stats(&self) -> AircraftStats {
     match self {
       AircraftKind::A21N => AircraftStats {
         thrust: 140.96,
-        // TODO: placeholder
-        drag: 0.0,
+        drag: 56.0,
         turn_speed: 1.0,
         roc: 1500.0,
         rod: 2500.0,
         max_altitude: 39000.0,
         min_speed: 140.0,
         max_speed: 450.0,
+        max_crosswind: 38.0,
         v2: 145.0,
         takeoff_length: 7054.0,
         landing_length: 6070.0,
@@ -225,12 +402,257 @@ impl AircraftKind {
         fuel_capacity: 58232.5,
         seats: 200,
       },
-      AircraftKind::A333 => todo!(),
-      AircraftKind::B737 => todo!(),
-      AircraftKind::B747 => todo!(),
-      AircraftKind::B77L => todo!(),
-      AircraftKind::CRJ7 => todo!(),
-      AircraftKind::E170 => todo!(),
+      AircraftKind::A333 => AircraftStats {
+        thrust: 316.0,
+        drag: 130.0,
+        turn_speed: 1.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 145.0,
+        max_speed: 480.0,
+        max_crosswind: 40.0,
+        v2: 155.0,
+        takeoff_length: 8200.0,
+        landing_length: 5900.0,
+        max_takeoff_weight: 533519.0,
+        max_landing_weight: 412264.0,
+        dry_weight: 275577.0,
+        fuel_capacity: 168800.0,
+        seats: 300,
+      },
+      AircraftKind::B737 => AircraftStats {
+        thrust: 121.4,
+        drag: 45.0,
+        turn_speed: 1.0,
+        roc: 1800.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 135.0,
+        max_speed: 460.0,
+        max_crosswind: 36.0,
+        v2: 145.0,
+        takeoff_length: 7600.0,
+        landing_length: 5400.0,
+        max_takeoff_weight: 174200.0,
+        max_landing_weight: 146300.0,
+        dry_weight: 91300.0,
+        fuel_capacity: 45900.0,
+        seats: 189,
+      },
+      AircraftKind::B747 => AircraftStats {
+        thrust: 282.0,
+        drag: 210.0,
+        turn_speed: 1.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 45000.0,
+        min_speed: 160.0,
+        max_speed: 490.0,
+        max_crosswind: 45.0,
+        v2: 170.0,
+        takeoff_length: 10000.0,
+        landing_length: 7000.0,
+        max_takeoff_weight: 875000.0,
+        max_landing_weight: 652000.0,
+        dry_weight: 394100.0,
+        fuel_capacity: 383800.0,
+        seats: 416,
+      },
+      AircraftKind::B77L => AircraftStats {
+        thrust: 489.0,
+        drag: 180.0,
+        turn_speed: 1.0,
+        roc: 1500.0,
+        rod: 2500.0,
+        max_altitude: 43100.0,
+        min_speed: 150.0,
+        max_speed: 490.0,
+        max_crosswind: 45.0,
+        v2: 165.0,
+        takeoff_length: 9800.0,
+        landing_length: 5600.0,
+        max_takeoff_weight: 766000.0,
+        max_landing_weight: 492000.0,
+        dry_weight: 320000.0,
+        fuel_capacity: 320900.0,
+        seats: 317,
+      },
+      AircraftKind::CRJ7 => AircraftStats {
+        thrust: 61.3,
+        drag: 20.0,
+        turn_speed: 1.5,
+        roc: 2500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 130.0,
+        max_speed: 450.0,
+        max_crosswind: 27.0,
+        v2: 140.0,
+        takeoff_length: 5500.0,
+        landing_length: 5000.0,
+        max_takeoff_weight: 75000.0,
+        max_landing_weight: 67000.0,
+        dry_weight: 44245.0,
+        fuel_capacity: 19450.0,
+        seats: 78,
+      },
+      AircraftKind::E170 => AircraftStats {
+        thrust: 63.2,
+        drag: 21.0,
+        turn_speed: 1.5,
+        roc: 2500.0,
+        rod: 2500.0,
+        max_altitude: 41000.0,
+        min_speed: 125.0,
+        max_speed: 450.0,
+        max_crosswind: 38.0,
+        v2: 135.0,
+        takeoff_length: 5400.0,
+        landing_length: 4100.0,
+        max_takeoff_weight: 82012.0,
+        max_landing_weight: 72312.0,
+        dry_weight: 46738.0,
+        fuel_capacity: 20580.0,
+        seats: 78,
+      },
+    }
+  }
+}
+
+/// How much wake turbulence an aircraft leaves behind it, which grows with
+/// its weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WakeCategory {
+  Light,
+  Medium,
+  Heavy,
+  Super,
+}
+
+impl WakeCategory {
+  /// The ICAO category of an aircraft with a max takeoff weight of
+  /// `max_takeoff_weight` pounds.
+  pub fn from_max_takeoff_weight(max_takeoff_weight: f32) -> Self {
+    match max_takeoff_weight {
+      w if w <= 15500.0 => Self::Light,
+      w if w < 300000.0 => Self::Medium,
+      w if w < 1000000.0 => Self::Heavy,
+      _ => Self::Super,
+    }
+  }
+}
+
+/// How far apart aircraft are spaced on approach when there is no wake
+/// turbulence minimum between them, in nautical miles.
+pub const APPROACH_SEPARATION: f32 = 5.0;
+
+/// How far `follower` needs to be behind `leader` on approach, in nautical
+/// miles, using the ICAO wake turbulence minima.
+pub fn wake_separation(leader: WakeCategory, follower: WakeCategory) -> f32 {
+  match (leader, follower) {
+    (WakeCategory::Super, WakeCategory::Heavy)
+    | (WakeCategory::Heavy, WakeCategory::Light) => 6.0,
+    (WakeCategory::Super, WakeCategory::Medium) => 7.0,
+    (WakeCategory::Super, WakeCategory::Light) => 8.0,
+    _ => APPROACH_SEPARATION,
+  }
+}
+
+/// How much slower than their clean minimum speed aircraft can fly with their
+/// flaps out for landing.
+pub const FLAPS_SPEED_FACTOR: f32 = 0.9;
+
+/// Thrust specific fuel consumption of our engines, in pounds of fuel per
+/// hour for each pound of thrust.
+pub const TSFC: f32 = 0.55;
+/// Pounds of thrust in a kilonewton.
+pub const KILONEWTON_TO_POUNDS: f32 = 224.809;
+/// How much of our rated thrust we use at idle, such as while taxiing.
+pub const IDLE_THRUST: f32 = 0.05;
+/// How much of our rated thrust we use to climb out.
+pub const CLIMB_THRUST: f32 = 0.6;
+/// How much of our rated thrust we use to cruise and to come down.
+pub const CRUISE_THRUST: f32 = 0.3;
+
+/// How common each kind of aircraft is, out of the sum of the weights.
+const KIND_WEIGHTS: [(AircraftKind, usize); 7] = [
+  (AircraftKind::A21N, 4),
+  (AircraftKind::B737, 4),
+  (AircraftKind::CRJ7, 2),
+  (AircraftKind::E170, 2),
+  (AircraftKind::A333, 1),
+  (AircraftKind::B77L, 1),
+  (AircraftKind::B747, 1),
+];
+
+/// How long a transponder flashes ident for.
+pub const IDENT_DURATION: Duration = Duration::from_secs(18);
+
+/// How long an outbound flight takes to board before it is ready to depart.
+pub const BOARDING_DURATION: Duration = Duration::from_secs(120);
+
+/// How fast we taxi, in knots.
+pub const TAXI_SPEED: f32 = 20.0;
+/// How far from a runway we stop to hold short of it, in feet.
+pub const HOLD_SHORT_DISTANCE: f32 = 250.0;
+/// How fast we can turn off the runway onto a high-speed exit, in knots.
+pub const HIGH_SPEED_EXIT_SPEED: f32 = 40.0;
+/// The sharpest turn that we can make at [`HIGH_SPEED_EXIT_SPEED`], in
+/// degrees.
+pub const HIGH_SPEED_EXIT_ANGLE: f32 = 45.0;
+/// How hard we brake after touching down, in knots per second.
+pub const ROLLOUT_DECELERATION: f32 = 3.3;
+/// How far along an exit we taxi to be clear of the runway, in feet.
+pub const RUNWAY_EXIT_CLEARANCE: f32 = 300.0;
+
+/// The fastest we can make a turn of `turn` degrees on the ground, in knots.
+pub fn turn_speed(turn: f32) -> f32 {
+  if turn.abs() <= HIGH_SPEED_EXIT_ANGLE {
+    HIGH_SPEED_EXIT_SPEED
+  } else {
+    TAXI_SPEED
+  }
+}
+
+/// The fastest we can be rolling and still brake to `final_speed` within
+/// `distance` feet, in knots.
+pub fn braking_speed(distance: f32, final_speed: f32) -> f32 {
+  let distance = distance / KNOT_TO_FEET_PER_SECOND;
+  (final_speed.powf(2.0) + 2.0 * ROLLOUT_DECELERATION * distance).sqrt()
+}
+
+/// Whether `code` is a valid transponder code: four octal digits, written as
+/// a decimal number (such as 4321).
+pub fn is_valid_squawk(code: u16) -> bool {
+  code <= 7777
+    && [code / 1000, code / 100, code / 10, code]
+      .iter()
+      .all(|d| d % 10 <= 7)
+}
+
+/// The longest that we will wait before starting a change that was left to
+/// our discretion.
+pub const DISCRETION_DELAY: Duration = Duration::from_secs(30);
+
+/// A target that was left to our discretion, and when we will start changing
+/// to it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DeferredTarget {
+  pub value: f32,
+  pub at: Duration,
+}
+
+impl DeferredTarget {
+  /// Picks a time within [`DISCRETION_DELAY`] of now to start the change.
+  pub fn new(value: f32, bundle: &mut Bundle) -> Self {
+    let delay = Duration::from_secs_f32(
+      bundle.rng.f32() * DISCRETION_DELAY.as_secs_f32(),
+    );
+
+    Self {
+      value,
+      at: bundle.now + delay,
     }
   }
 }
@@ -250,6 +672,66 @@ pub struct Aircraft {
   pub flight_plan: FlightPlan,
 
   pub frequency: f32,
+  /// The last readback given to ATC, repeated on "say again".
+  #[serde(default)]
+  pub last_readback: Option<String>,
+  /// Position in the departure queue of the runway we're waiting for,
+  /// starting at 0 for the next aircraft to depart.
+  #[serde(default)]
+  pub queue_position: Option<usize>,
+  /// Why we are stopped while taxiing, cleared once we move again.
+  #[serde(default)]
+  pub stop_reason: Option<TaxiStopReason>,
+  /// The runway we were told to expect, used when cleared to land without
+  /// one being named.
+  #[serde(default)]
+  pub expected_runway: Option<Intern<String>>,
+  /// The runway we will circle to once low enough on the approach.
+  #[serde(default)]
+  pub circle_to_land: Option<Intern<String>>,
+  /// The transponder code we were assigned.
+  #[serde(default)]
+  pub squawk: Option<u16>,
+  /// When our transponder stops flashing ident, if it is.
+  #[serde(default)]
+  pub ident_until: Option<Duration>,
+  /// Altitude and speed clearances that we will start on when convenient.
+  #[serde(default)]
+  pub deferred_altitude: Option<DeferredTarget>,
+  #[serde(default)]
+  pub deferred_speed: Option<DeferredTarget>,
+  /// Whether our updates are paused, for debugging.
+  #[serde(default)]
+  pub frozen: bool,
+  /// When we finish boarding at the gate and call ready for departure.
+  #[serde(default)]
+  pub boarding_until: Option<Duration>,
+  /// Whether we have been given our IFR clearance.
+  #[serde(default)]
+  pub ifr_cleared: bool,
+  /// The route we were flying before being vectored off of it, to rejoin
+  /// when told to resume.
+  #[serde(default)]
+  pub vectored_from: Vec<Node<NodeVORData>>,
+  /// When we remind ATC that we are still waiting for takeoff clearance, if
+  /// we are lined up on the runway.
+  #[serde(default)]
+  pub lined_up_until: Option<Duration>,
+  /// The type of aircraft we are, for its performance.
+  #[serde(default)]
+  pub kind: AircraftKind,
+  /// Fuel on board, in pounds.
+  #[serde(default)]
+  pub fuel: f32,
+  /// Whether we fly our route or wait for vectors inside the airspace.
+  #[serde(default)]
+  pub approach_mode: ApproachMode,
+  /// Whether we are on a vector or following our waypoints.
+  #[serde(default)]
+  pub nav_mode: NavMode,
+  /// The holding pattern we are flying instead of our waypoints, if any.
+  #[serde(default)]
+  pub hold: Option<Hold>,
 }
 
 // Helper methods
@@ -268,6 +750,53 @@ impl Aircraft {
     }
   }
 
+  /// Whether our transponder is flashing ident.
+  pub fn is_identing(&self) -> bool {
+    self.ident_until.is_some()
+  }
+
+  /// Whether we are still boarding at the gate.
+  pub fn is_boarding(&self) -> bool {
+    self.boarding_until.is_some()
+  }
+
+  /// Whether we are parked at an airport that requires an IFR clearance
+  /// before taxiing, and haven't been given ours yet.
+  pub fn needs_clearance(&self, airspace: &Airspace) -> bool {
+    matches!(self.state, AircraftState::Parked { .. })
+      && !self.ifr_cleared
+      && closest_airport(airspace, self.pos)
+        .is_some_and(|airport| airport.requires_clearance)
+  }
+
+  /// Whether we are established on the localizer of the runway we are
+  /// landing on, including once we are descending on the glideslope.
+  pub fn is_on_localizer(&self) -> bool {
+    matches!(
+      self.state,
+      AircraftState::Landing {
+        state: LandingState::Localizer | LandingState::Glideslope,
+        ..
+      }
+    )
+  }
+
+  /// Whether we are descending on the glideslope.
+  pub fn is_on_glideslope(&self) -> bool {
+    matches!(
+      self.state,
+      AircraftState::Landing {
+        state: LandingState::Glideslope,
+        ..
+      }
+    )
+  }
+
+  /// Whether we are fully established on the approach.
+  pub fn on_localizer_and_glideslope(&self) -> bool {
+    self.is_on_localizer() && self.is_on_glideslope()
+  }
+
   pub fn sync_targets_to_vals(&mut self) {
     self.target.heading = self.heading;
     self.target.speed = self.speed;
@@ -279,25 +808,16 @@ impl Aircraft {
     self
   }
 
-  pub fn random_callsign(rng: &mut Rng) -> String {
-    let mut string = String::new();
-    let airlines = ["AAL", "SKW", "JBU"];
-
-    let airline = rng.sample(&airlines).unwrap();
-
-    string.push_str(airline);
-
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-    string.push_str(&rng.sample_iter(0..=9).unwrap().to_string());
-
-    string
-  }
-
-  pub fn random_parked(gate: Gate, rng: &mut Rng, airspace: &Airspace) -> Self {
+  pub fn random_parked(
+    gate: Gate,
+    rng: &mut Rng,
+    airspace: &Airspace,
+    airlines: &Airlines,
+  ) -> Self {
+    let id = Intern::from(airlines.random_callsign(rng));
+    let kind = airlines.random_kind(&id, rng);
     Self {
-      id: Intern::from(Self::random_callsign(rng)),
+      id,
       is_colliding: false,
 
       pos: gate.pos,
@@ -316,6 +836,25 @@ impl Aircraft {
       ),
 
       frequency: airspace.frequencies.ground,
+      last_readback: None,
+      queue_position: None,
+      stop_reason: None,
+      expected_runway: None,
+      circle_to_land: None,
+      squawk: None,
+      ident_until: None,
+      deferred_altitude: None,
+      deferred_speed: None,
+      frozen: false,
+      boarding_until: None,
+      ifr_cleared: false,
+      vectored_from: Vec::new(),
+      lined_up_until: None,
+      fuel: kind.stats().fuel_capacity,
+      kind,
+      approach_mode: ApproachMode::default(),
+      nav_mode: NavMode::default(),
+      hold: None,
     }
     .with_synced_targets()
   }
@@ -324,9 +863,12 @@ impl Aircraft {
     frequency: f32,
     flight_plan: FlightPlan,
     rng: &mut Rng,
+    airlines: &Airlines,
   ) -> Self {
+    let id = Intern::from(airlines.random_callsign(rng));
+    let kind = airlines.random_kind(&id, rng);
     Self {
-      id: Intern::from(Aircraft::random_callsign(rng)),
+      id,
       is_colliding: false,
 
       pos: Vec2::ZERO,
@@ -342,6 +884,25 @@ impl Aircraft {
       flight_plan,
 
       frequency,
+      last_readback: None,
+      queue_position: None,
+      stop_reason: None,
+      expected_runway: None,
+      circle_to_land: None,
+      squawk: None,
+      ident_until: None,
+      deferred_altitude: None,
+      deferred_speed: None,
+      frozen: false,
+      boarding_until: None,
+      ifr_cleared: false,
+      vectored_from: Vec::new(),
+      lined_up_until: None,
+      fuel: kind.stats().fuel_capacity,
+      kind,
+      approach_mode: ApproachMode::default(),
+      nav_mode: NavMode::default(),
+      hold: None,
     }
     .with_synced_targets()
   }
@@ -351,33 +912,131 @@ impl Aircraft {
     departure: &Connection,
     arrival: &Airspace,
     rng: &mut Rng,
+    airlines: &Airlines,
   ) -> Self {
     let mut aircraft = Self::random_flying(
       frequency,
       FlightPlan::new(departure.id, arrival.id),
       rng,
+      airlines,
     );
 
     aircraft.pos = departure.pos;
     aircraft.heading = angle_between_points(departure.pos, arrival.pos);
     aircraft.speed = 300.0;
-    aircraft.altitude = 7000.0;
+    aircraft.altitude = arrival.altitudes.arrival;
     aircraft.sync_targets_to_vals();
 
+    let waypoints = vec![new_vor(departure.id, departure.transition)
+      .with_name(Intern::from_ref("TRSN"))
+      .with_behavior(vec![
+        EventKind::EnRoute(false),
+        EventKind::SpeedAtOrBelow(250.0),
+        EventKind::CalloutInAirspace,
+      ])];
+    aircraft.flight_plan.plan_route(aircraft.pos, &waypoints);
     aircraft.state = AircraftState::Flying {
-      waypoints: vec![new_vor(departure.id, departure.transition)
-        .with_name(Intern::from_ref("TRSN"))
-        .with_behavior(vec![
-          EventKind::EnRoute(false),
-          EventKind::SpeedAtOrBelow(250.0),
-          EventKind::CalloutInAirspace,
-        ])],
+      waypoints,
       enroute: true,
     };
 
     aircraft
   }
 
+  /// The runway we are holding short of or lined up on, waiting to depart.
+  pub fn departure_runway(&self) -> Option<Intern<String>> {
+    if let AircraftState::Taxiing {
+      current, waypoints, ..
+    } = &self.state
+    {
+      match waypoints.last() {
+        Some(next) if next.kind == NodeKind::Runway && waypoints.len() == 1 => {
+          Some(next.name)
+        }
+        None
+          if current.kind == NodeKind::Runway
+            && current.behavior != NodeBehavior::GoTo =>
+        {
+          Some(current.name)
+        }
+        _ => None,
+      }
+    } else {
+      None
+    }
+  }
+
+  /// Whether we have reached the hold short line of the runway that we are
+  /// departing from, or are already past it.
+  pub fn at_hold_short(&self) -> bool {
+    let AircraftState::Taxiing { waypoints, .. } = &self.state else {
+      return false;
+    };
+
+    self.departure_runway().is_some()
+      && waypoints.last().is_none_or(|runway| {
+        self.pos.distance_squared(runway.value) <= HOLD_SHORT_DISTANCE.powf(2.0)
+      })
+  }
+
+  /// Whether we have been cleared to line up or take off.
+  pub fn cleared_for_departure(&self) -> bool {
+    if let AircraftState::Taxiing {
+      current, waypoints, ..
+    } = &self.state
+    {
+      let behavior = waypoints.last().unwrap_or(current).behavior;
+      matches!(behavior, NodeBehavior::LineUp | NodeBehavior::Takeoff)
+    } else {
+      false
+    }
+  }
+
+  /// Where we are in our flight, relative to `airspace`.
+  pub fn segment(&self, airspace: &Airspace) -> FlightSegment {
+    match &self.state {
+      AircraftState::Parked { .. } => FlightSegment::Parked,
+      AircraftState::Taxiing { .. } => {
+        if self.departure_runway().is_some() {
+          FlightSegment::Takeoff
+        } else {
+          FlightSegment::Taxi
+        }
+      }
+      AircraftState::Landing { .. } => FlightSegment::Landing,
+      AircraftState::Flying { enroute, .. } => {
+        if *enroute
+          || !airspace.contains(self.pos, self.altitude)
+          || self.altitude > airspace.altitudes.transition
+        {
+          FlightSegment::Enroute
+        } else if self.flight_plan.departing == airspace.id
+          || airspace
+            .airports
+            .iter()
+            .any(|a| a.id == self.flight_plan.departing)
+        {
+          FlightSegment::Departure
+        } else {
+          FlightSegment::Approach
+        }
+      }
+    }
+  }
+
+  /// The frequency of the controller responsible for us, based on where we
+  /// are in our flight.
+  pub fn controller_frequency(&self, airspace: &Airspace) -> f32 {
+    let frequencies = &airspace.frequencies;
+    match self.segment(airspace) {
+      FlightSegment::Parked | FlightSegment::Taxi => frequencies.ground,
+      FlightSegment::Takeoff | FlightSegment::Landing => frequencies.tower,
+      FlightSegment::Departure => frequencies.departure,
+      FlightSegment::Approach => frequencies.approach,
+      FlightSegment::Enroute => frequencies.center,
+    }
+  }
+
   pub fn flip_flight_plan(&mut self) {
     let d = self.flight_plan.departing;
     let a = self.flight_plan.arriving;
@@ -389,26 +1048,170 @@ impl Aircraft {
 
 // Performance stats
 impl Aircraft {
+  /// Our speed over the ground along our heading, in knots. Only airborne
+  /// aircraft are affected by the wind.
+  pub fn ground_speed(&self, wind: Wind) -> f32 {
+    match self.state {
+      AircraftState::Flying { .. } | AircraftState::Landing { .. } => {
+        let headwind =
+          headwind_component(self.heading, wind.heading, wind.speed);
+        (self.speed - headwind).max(0.0)
+      }
+      AircraftState::Taxiing { .. } | AircraftState::Parked { .. } => {
+        self.speed
+      }
+    }
+  }
+
+  /// How far along our planned route we are, from 0 at its start to 1 at its
+  /// end. Without a planned route, this is 0, and once we have flown all of
+  /// it or are landing, this is 1.
+  pub fn flight_progress(&self) -> f32 {
+    let distances = &self.flight_plan.distances;
+    let Some(total) = distances.last().copied().filter(|d| *d > 0.0) else {
+      return 0.0;
+    };
+
+    let waypoints = match &self.state {
+      AircraftState::Flying { waypoints, .. } => waypoints,
+      AircraftState::Landing { .. } => return 1.0,
+      _ => return 0.0,
+    };
+    let Some(next) = waypoints.last() else {
+      return 1.0;
+    };
+    if waypoints.len() > distances.len() {
+      return 0.0;
+    }
+
+    let at_next = distances[distances.len() - waypoints.len()];
+    let flown = at_next - self.pos.distance(next.value.to);
+    (flown / total).clamp(0.0, 1.0)
+  }
+
+  /// Plans our route again from where we are, such as after we were rerouted
+  /// or vectored off of it.
+  pub fn replan_route(&mut self) {
+    if let AircraftState::Flying { waypoints, .. } = &self.state {
+      self.flight_plan.plan_route(self.pos, waypoints);
+    }
+  }
+
+  /// A one-line description of us, such as
+  /// "AAL1234 270° 250kt 4000ft approach KSFO-KLAX 118.5 B737".
+  pub fn to_text(&self, airspace: &Airspace, options: ToTextOptions) -> String {
+    let mut text = format!(
+      "{} {:03}° {}kt {}ft",
+      self.id,
+      self.heading.round() as i32,
+      self.speed.round(),
+      self.altitude.round()
+    );
+
+    if options.segment {
+      let segment = format!("{:?}", self.segment(airspace)).to_lowercase();
+      text.push_str(&format!(" {segment}"));
+    }
+    if options.flight_plan {
+      text.push_str(&format!(
+        " {}-{}",
+        self.flight_plan.departing, self.flight_plan.arriving
+      ));
+    }
+    if options.frequency {
+      text.push_str(&format!(" {}", self.frequency));
+    }
+    if options.kind {
+      text.push_str(&format!(" {:?}", self.kind));
+    }
+
+    text
+  }
+
+  /// Our weight in pounds.
+  pub fn weight(&self) -> f32 {
+    let stats = self.kind.stats();
+    (stats.dry_weight + self.fuel).min(stats.max_takeoff_weight)
+  }
+
+  /// How fast we burn fuel during `segment`, in pounds per second. Our
+  /// engines are off at the gate.
+  pub fn fuel_flow(&self, segment: FlightSegment) -> f32 {
+    let thrust = self.kind.stats().thrust * KILONEWTON_TO_POUNDS;
+    let setting = match segment {
+      FlightSegment::Parked => 0.0,
+      FlightSegment::Taxi | FlightSegment::Takeoff => IDLE_THRUST,
+      FlightSegment::Departure => CLIMB_THRUST,
+      FlightSegment::Enroute
+      | FlightSegment::Approach
+      | FlightSegment::Landing => CRUISE_THRUST,
+    };
+
+    thrust * setting * TSFC / 3600.0
+  }
+
+  /// Our wake turbulence category.
+  pub fn wake_category(&self) -> WakeCategory {
+    WakeCategory::from_max_takeoff_weight(self.kind.stats().max_takeoff_weight)
+  }
+
+  /// The slowest we can be told to fly, if we are in the air. Once we are
+  /// landing, our flaps let us fly slower than when clean.
+  pub fn min_speed(&self) -> Option<f32> {
+    let min_speed = self.kind.stats().min_speed;
+    match self.state {
+      AircraftState::Flying { .. } => Some(min_speed),
+      AircraftState::Landing { .. } => Some(min_speed * FLAPS_SPEED_FACTOR),
+      _ => None,
+    }
+  }
+
+  /// How fast we can climb, in feet per minute. Our rate of climb is for a
+  /// max weight takeoff, and the lighter we are the faster we climb.
+  pub fn climb_rate(&self) -> f32 {
+    let stats = self.kind.stats();
+    stats.roc * stats.max_takeoff_weight / self.weight()
+  }
+
+  /// The speed at which we start to climb after takeoff, in knots.
+  pub fn v2(&self) -> f32 {
+    self.kind.stats().v2
+  }
+
+  /// How fast we can descend, in feet per minute.
+  pub fn descent_rate(&self) -> f32 {
+    self.kind.stats().rod
+  }
+
   pub fn dt_climb_speed(&self, dt: f32) -> f32 {
     // When taking off or taxiing (no climb until V2)
-    if self.speed < 140.0 {
+    if self.speed < self.v2() {
       0.0
     } else {
       // Flying
-      (2000.0_f32 / 60.0_f32).round() * dt
+      (self.climb_rate() / 60.0_f32).round() * dt
+    }
+  }
+
+  pub fn dt_descent_speed(&self, dt: f32) -> f32 {
+    // Slower than we can fly with our flaps out, we are on the ground.
+    if self.speed < self.kind.stats().min_speed * FLAPS_SPEED_FACTOR {
+      0.0
+    } else {
+      (self.descent_rate() / 60.0_f32).round() * dt
     }
   }
 
   pub fn dt_turn_speed(&self, dt: f32) -> f32 {
-    2.0 * dt
+    self.kind.stats().turn_speed * dt
   }
 
   pub fn dt_speed_speed(&self, dt: f32) -> f32 {
     // Taxi speed
     if self.altitude == 0.0 {
       // If landing
-      if self.speed > 20.0 {
-        3.3 * dt
+      if self.speed > TAXI_SPEED {
+        ROLLOUT_DECELERATION * dt
         // If taxiing
       } else {
         5.0 * dt
@@ -434,3 +1237,373 @@ impl Aircraft {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use turborand::SeededCore;
+
+  use crate::{airline::Airline, entities::airport::Airport};
+
+  use super::*;
+
+  #[test]
+  fn spawns_follow_the_airline_fleet() {
+    let mut rng = Rng::with_seed(0);
+    let airspace = Airspace::default();
+    let gate = Gate {
+      id: Intern::from_ref("A1"),
+      pos: Vec2::ZERO,
+      heading: 0.0,
+      terminal: None,
+    };
+    let fleet = vec![AircraftKind::B77L, AircraftKind::B747];
+    let airlines = Airlines(vec![Airline {
+      fleet: fleet.clone(),
+      ..Airline::new("BAW", "Speedbird")
+    }]);
+
+    for _ in 0..20 {
+      let parked =
+        Aircraft::random_parked(gate.clone(), &mut rng, &airspace, &airlines);
+      assert!(fleet.contains(&parked.kind), "{:?}", parked.kind);
+      assert_eq!(parked.fuel, parked.kind.stats().fuel_capacity);
+
+      let flying = Aircraft::random_flying(
+        118.5,
+        FlightPlan::default(),
+        &mut rng,
+        &airlines,
+      );
+      assert!(fleet.contains(&flying.kind), "{:?}", flying.kind);
+    }
+
+    // Airlines without a fleet fly any kind.
+    let airlines = Airlines(vec![Airline::new("AAL", "American Airlines")]);
+    let kinds: HashSet<String> = (0..50)
+      .map(|_| format!("{:?}", airlines.random_kind("AAL1234", &mut rng)))
+      .collect();
+    assert!(kinds.len() > 2);
+  }
+
+  #[test]
+  fn ground_speed_accounts_for_wind() {
+    let aircraft = Aircraft {
+      speed: 200.0,
+      heading: 270.0,
+      ..Default::default()
+    };
+
+    let calm = Wind::default();
+    let headwind = Wind {
+      heading: 270.0,
+      speed: 20.0,
+    };
+    let tailwind = Wind {
+      heading: 90.0,
+      speed: 20.0,
+    };
+
+    assert_eq!(aircraft.ground_speed(calm), 200.0);
+    assert_eq!(aircraft.ground_speed(headwind), 180.0);
+    assert!((aircraft.ground_speed(tailwind) - 220.0).abs() < 0.001);
+
+    // Taxiing aircraft aren't blown around.
+    let taxiing = Aircraft {
+      state: AircraftState::Taxiing {
+        current: Node::new(
+          Intern::from_ref("A"),
+          NodeKind::Taxiway,
+          NodeBehavior::GoTo,
+          Vec2::ZERO,
+        ),
+        waypoints: Vec::new(),
+        state: TaxiingState::Armed,
+      },
+      ..aircraft
+    };
+    assert_eq!(taxiing.ground_speed(headwind), 200.0);
+  }
+
+  #[test]
+  fn every_kind_has_stats() {
+    for kind in [
+      AircraftKind::A21N,
+      AircraftKind::A333,
+      AircraftKind::B737,
+      AircraftKind::B747,
+      AircraftKind::B77L,
+      AircraftKind::CRJ7,
+      AircraftKind::E170,
+    ] {
+      let stats = kind.stats();
+      for value in [
+        stats.thrust,
+        stats.drag,
+        stats.turn_speed,
+        stats.roc,
+        stats.rod,
+        stats.max_altitude,
+        stats.min_speed,
+        stats.max_speed,
+        stats.max_crosswind,
+        stats.v2,
+        stats.takeoff_length,
+        stats.landing_length,
+        stats.max_takeoff_weight,
+        stats.max_landing_weight,
+        stats.dry_weight,
+        stats.fuel_capacity,
+      ] {
+        assert!(value.is_finite() && value > 0.0, "{kind:?}: {stats:?}");
+      }
+      assert!(stats.seats > 0, "{kind:?}");
+      assert!(stats.min_speed < stats.max_speed, "{kind:?}");
+      assert!(stats.dry_weight < stats.max_landing_weight, "{kind:?}");
+      assert!(
+        stats.max_landing_weight <= stats.max_takeoff_weight,
+        "{kind:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn performance_depends_on_kind() {
+    let jumbo = Aircraft {
+      kind: AircraftKind::B747,
+      ..Default::default()
+    };
+    let regional = Aircraft {
+      kind: AircraftKind::CRJ7,
+      ..Default::default()
+    };
+
+    assert!(jumbo.dt_turn_speed(1.0) < regional.dt_turn_speed(1.0));
+    assert!(
+      AircraftKind::B747.stats().max_altitude
+        > AircraftKind::CRJ7.stats().max_altitude
+    );
+    assert!(jumbo.v2() > regional.v2());
+
+    // Aircraft saved without a kind fly as the most common one.
+    let mut saved = serde_json::to_value(Aircraft::default()).unwrap();
+    saved.as_object_mut().unwrap().remove("kind");
+    let saved: Aircraft = serde_json::from_value(saved).unwrap();
+    assert_eq!(saved.kind, AircraftKind::A21N);
+  }
+
+  #[test]
+  fn wake_category_follows_max_takeoff_weight() {
+    let category = |kind| {
+      Aircraft {
+        kind,
+        ..Default::default()
+      }
+      .wake_category()
+    };
+    assert_eq!(category(AircraftKind::CRJ7), WakeCategory::Medium);
+    assert_eq!(category(AircraftKind::A21N), WakeCategory::Medium);
+    assert_eq!(category(AircraftKind::B747), WakeCategory::Heavy);
+    assert_eq!(Aircraft::default().wake_category(), WakeCategory::Medium);
+
+    assert_eq!(
+      WakeCategory::from_max_takeoff_weight(8750.0),
+      WakeCategory::Light
+    );
+    assert_eq!(
+      WakeCategory::from_max_takeoff_weight(1268000.0),
+      WakeCategory::Super
+    );
+  }
+
+  #[test]
+  fn heavier_aircraft_climb_slower() {
+    let stats = AircraftKind::A21N.stats();
+    let aircraft = |fuel: f32| Aircraft {
+      speed: 250.0,
+      kind: AircraftKind::A21N,
+      fuel,
+      ..Default::default()
+    };
+
+    let heavy = aircraft(stats.max_takeoff_weight - stats.dry_weight);
+    let light = aircraft(1000.0);
+
+    assert_eq!(heavy.weight(), stats.max_takeoff_weight);
+    assert_eq!(heavy.climb_rate(), stats.roc);
+    assert!(light.climb_rate() > heavy.climb_rate() * 1.5);
+    assert!(light.dt_climb_speed(1.0) > heavy.dt_climb_speed(1.0));
+  }
+
+  #[test]
+  fn flight_progress_follows_the_route() {
+    let route = [
+      new_vor(Intern::from_ref("C"), Vec2::new(3000.0, 0.0)),
+      new_vor(Intern::from_ref("B"), Vec2::new(2000.0, 0.0)),
+      new_vor(Intern::from_ref("A"), Vec2::new(1000.0, 0.0)),
+    ];
+    let mut aircraft = Aircraft {
+      state: AircraftState::Flying {
+        waypoints: route.to_vec(),
+        enroute: false,
+      },
+      ..Default::default()
+    };
+    assert_eq!(aircraft.flight_progress(), 0.0);
+
+    aircraft.flight_plan.plan_route(Vec2::ZERO, &route);
+    assert_eq!(aircraft.flight_plan.distances, vec![1000.0, 2000.0, 3000.0]);
+    assert_eq!(aircraft.flight_progress(), 0.0);
+
+    // Halfway along the middle leg, with A behind us.
+    aircraft.pos = Vec2::new(1500.0, 0.0);
+    aircraft.state = AircraftState::Flying {
+      waypoints: route[..2].to_vec(),
+      enroute: false,
+    };
+    let progress = aircraft.flight_progress();
+    assert!((progress - 0.5).abs() < 0.001);
+
+    // Arriving at C.
+    aircraft.pos = Vec2::new(3000.0, 0.0);
+    aircraft.state = AircraftState::Flying {
+      waypoints: route[..1].to_vec(),
+      enroute: false,
+    };
+    assert_eq!(aircraft.flight_progress(), 1.0);
+
+    // With all of it flown.
+    aircraft.state = AircraftState::Flying {
+      waypoints: Vec::new(),
+      enroute: false,
+    };
+    assert_eq!(aircraft.flight_progress(), 1.0);
+  }
+
+  #[test]
+  fn to_text_verbosity() {
+    let airspace = Airspace {
+      id: Intern::from_ref("KSFO"),
+      ..Default::default()
+    };
+    let aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      heading: 90.0,
+      speed: 250.0,
+      altitude: 4000.0,
+      frequency: 118.5,
+      flight_plan: FlightPlan::new(
+        Intern::from_ref("KSFO"),
+        Intern::from_ref("KLAX"),
+      ),
+      kind: AircraftKind::B737,
+      ..Default::default()
+    };
+
+    let terse = aircraft.to_text(&airspace, ToTextOptions::terse());
+    assert_eq!(terse, "AAL1234 090° 250kt 4000ft");
+
+    let verbose = aircraft.to_text(&airspace, ToTextOptions::verbose());
+    assert!(verbose.starts_with(&terse));
+    assert!(verbose.contains("KSFO-KLAX"));
+    assert!(verbose.contains("118.5"));
+    assert!(verbose.ends_with("B737"));
+  }
+
+  #[test]
+  fn transition_altitude_bounds_the_departure_segment() {
+    let mut airspace = Airspace {
+      id: Intern::from_ref("KSFO"),
+      radius: 200000.0,
+      ..Default::default()
+    };
+    let aircraft = Aircraft {
+      altitude: 12000.0,
+      state: AircraftState::Flying {
+        enroute: false,
+        waypoints: Vec::new(),
+      },
+      flight_plan: FlightPlan::new(airspace.id, Intern::from_ref("KLAX")),
+      ..Default::default()
+    };
+
+    assert_eq!(aircraft.segment(&airspace), FlightSegment::Departure);
+    assert_eq!(
+      aircraft.controller_frequency(&airspace),
+      airspace.frequencies.departure
+    );
+
+    airspace.altitudes.transition = 10000.0;
+    assert_eq!(aircraft.segment(&airspace), FlightSegment::Enroute);
+    assert_eq!(
+      aircraft.controller_frequency(&airspace),
+      airspace.frequencies.center
+    );
+  }
+
+  #[test]
+  fn departures_from_our_airports_are_departures() {
+    let airspace = Airspace {
+      id: Intern::from_ref("NCT"),
+      radius: 200000.0,
+      airports: vec![Airport::new(Intern::from_ref("KOAK"), Vec2::ZERO)],
+      ..Default::default()
+    };
+    let aircraft = |departing| Aircraft {
+      altitude: 5000.0,
+      state: AircraftState::Flying {
+        enroute: false,
+        waypoints: Vec::new(),
+      },
+      flight_plan: FlightPlan::new(
+        Intern::from_ref(departing),
+        Intern::from_ref("KLAX"),
+      ),
+      ..Default::default()
+    };
+
+    for departing in ["NCT", "KOAK"] {
+      assert_eq!(
+        aircraft(departing).segment(&airspace),
+        FlightSegment::Departure,
+        "{departing}"
+      );
+    }
+    assert_eq!(aircraft("KSJC").segment(&airspace), FlightSegment::Approach);
+  }
+
+  #[test]
+  fn approach_predicates_follow_landing_state() {
+    let states = [
+      (LandingState::BeforeTurn, "before-turn", false, false),
+      (LandingState::Turning, "turning", false, false),
+      (LandingState::Correcting, "correcting", false, false),
+      (LandingState::Localizer, "localizer", true, false),
+      (LandingState::Glideslope, "glideslope", true, true),
+      (LandingState::Circling, "circling", false, false),
+      (LandingState::Touchdown, "touchdown", false, false),
+      (LandingState::GoAround, "go-around", false, false),
+    ];
+
+    for (state, name, localizer, glideslope) in states {
+      let aircraft = Aircraft {
+        state: AircraftState::Landing {
+          runway: Runway::default(),
+          state,
+        },
+        ..Default::default()
+      };
+
+      assert_eq!(aircraft.is_on_localizer(), localizer, "{name}");
+      assert_eq!(aircraft.is_on_glideslope(), glideslope, "{name}");
+      assert_eq!(aircraft.on_localizer_and_glideslope(), glideslope, "{name}");
+      assert_eq!(
+        serde_json::to_string(&state).unwrap(),
+        format!("\"{name}\"")
+      );
+    }
+
+    assert!(!Aircraft::default().is_on_localizer());
+  }
+}
@@ -1,13 +1,54 @@
+use std::{cmp::Ordering, fmt::Write, time::Duration};
+
 use glam::Vec2;
 use internment::Intern;
+use petgraph::visit::{EdgeRef, IntoNodeReferences};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-  inverse_degrees, move_point,
-  pathfinder::{Object, Pathfinder},
-  Line,
+  angle_between_points, delta_angle,
+  entities::{aircraft::TAXI_SPEED, airspace::Wind},
+  headwind_component, inverse_degrees, move_point,
+  pathfinder::{display_node_vec2, Object, Pathfinder},
+  true_to_magnetic, Line, NAUTICALMILES_TO_FEET,
 };
 
+/// The area ahead of a taxiing aircraft in which it will stop for others.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaxiCollisionCone {
+  /// How far ahead we look when stopped, in feet.
+  pub distance: f32,
+  /// How much further ahead we look for each knot of taxi speed, in feet.
+  pub distance_per_knot: f32,
+  /// How far either side of our heading we look, in degrees.
+  pub half_angle: f32,
+}
+
+impl Default for TaxiCollisionCone {
+  fn default() -> Self {
+    Self {
+      distance: 300.0,
+      distance_per_knot: 3.0,
+      half_angle: 45.0,
+    }
+  }
+}
+
+impl TaxiCollisionCone {
+  /// Whether `point` is inside the cone of an aircraft at `pos`, facing
+  /// `heading`.
+  ///
+  /// The cone is sized for [`TAXI_SPEED`] rather than the current speed, so
+  /// that it doesn't shrink as we brake and let us roll again straight away.
+  pub fn contains(&self, pos: Vec2, heading: f32, point: Vec2) -> bool {
+    let distance = self.distance + self.distance_per_knot * TAXI_SPEED;
+
+    pos.distance_squared(point) <= distance.powf(2.0)
+      && delta_angle(heading, angle_between_points(pos, point)).abs()
+        <= self.half_angle
+  }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Airport {
   pub id: Intern<String>,
@@ -15,8 +56,22 @@ pub struct Airport {
   pub runways: Vec<Runway>,
   pub taxiways: Vec<Taxiway>,
   pub terminals: Vec<Terminal>,
+  /// How conservatively taxiing aircraft stop for each other.
+  #[serde(default)]
+  pub taxi_collision_cone: TaxiCollisionCone,
+  /// Whether departures need an IFR clearance before they can taxi.
+  #[serde(default)]
+  pub requires_clearance: bool,
+  /// The least time between successive departures from the same runway, in
+  /// seconds.
+  #[serde(default)]
+  pub min_departure_interval: f32,
+  /// The SIDs and STARs that aircraft can be cleared to fly.
+  #[serde(default)]
+  pub procedures: Vec<Procedure>,
 
-  #[serde(skip)]
+  /// Only serialized once calculated, so that saves can restore it.
+  #[serde(default, skip_serializing_if = "Pathfinder::is_empty")]
   pub pathfinder: Pathfinder,
 }
 
@@ -28,11 +83,35 @@ impl Airport {
       runways: Vec::new(),
       taxiways: Vec::new(),
       terminals: Vec::new(),
+      taxi_collision_cone: TaxiCollisionCone::default(),
+      requires_clearance: false,
+      min_departure_interval: 0.0,
+      procedures: Vec::new(),
 
       pathfinder: Pathfinder::new(),
     }
   }
 
+  pub fn min_departure_interval(&self) -> Duration {
+    Duration::from_secs_f32(self.min_departure_interval.max(0.0))
+  }
+
+  /// The runway with the strongest headwind in `wind`. Runways with the same
+  /// headwind, to the tenth of a knot, go to the longer one.
+  pub fn best_runway(&self, wind: Wind) -> Option<&Runway> {
+    let headwind = |runway: &Runway| {
+      (headwind_component(runway.heading, wind.heading, wind.speed) * 10.0)
+        .round()
+    };
+
+    self.runways.iter().max_by(|a, b| {
+      headwind(a)
+        .partial_cmp(&headwind(b))
+        .unwrap_or(Ordering::Equal)
+        .then(a.length.total_cmp(&b.length))
+    })
+  }
+
   pub fn add_taxiway(&mut self, taxiway: Taxiway) {
     let taxiway = taxiway.extend_ends_by(100.0);
     self.taxiways.push(taxiway);
@@ -43,6 +122,19 @@ impl Airport {
     self.runways.push(runway);
   }
 
+  pub fn add_terminal(&mut self, mut terminal: Terminal) {
+    // Namespace bare gate numbers by their terminal so that gate "1" of
+    // terminal "A" and gate "1" of terminal "B" become "A1" and "B1".
+    for gate in terminal.gates.iter_mut() {
+      if gate.terminal != Some(terminal.id) {
+        gate.id = Intern::from(format!("{}{}", terminal.id, gate.id));
+        gate.terminal = Some(terminal.id);
+      }
+    }
+
+    self.terminals.push(terminal);
+  }
+
   pub fn calculate_waypoints(&mut self) {
     let mut nodes: Vec<Object> = Vec::new();
     nodes.extend(self.runways.iter().map(|r| r.clone().into()));
@@ -51,6 +143,84 @@ impl Airport {
 
     self.pathfinder.calculate(nodes);
   }
+
+  /// Renders the taxi network as a GraphViz DOT graph, labeling each edge with
+  /// the point where its two segments intersect.
+  pub fn export_graph_dot(&self) -> String {
+    let graph = &self.pathfinder.graph;
+    let mut dot = format!("graph \"{}\" {{\n", self.id);
+
+    for (index, node) in graph.node_references() {
+      writeln!(
+        dot,
+        "  {} [label=\"{}\"];",
+        index.index(),
+        display_node_vec2(node)
+      )
+      .unwrap();
+    }
+
+    for edge in graph.edge_references() {
+      let point = edge.weight();
+      writeln!(
+        dot,
+        "  {} -- {} [label=\"({:.0}, {:.0})\"];",
+        edge.source().index(),
+        edge.target().index(),
+        point.x,
+        point.y
+      )
+      .unwrap();
+    }
+
+    dot.push_str("}\n");
+    dot
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// The published missed approach procedure for a runway.
+pub struct MissedApproach {
+  /// Altitude to climb to, in feet.
+  pub altitude: f32,
+  /// Minimum speed to fly, in knots.
+  pub speed: f32,
+}
+
+impl Default for MissedApproach {
+  fn default() -> Self {
+    Self {
+      altitude: 3000.0,
+      speed: 210.0,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// The published minimums for the approach to a runway.
+pub struct Minimums {
+  /// The altitude at which the runway must be in sight to continue, in feet.
+  pub decision_height: f32,
+  /// How far from the threshold the approach lights can first be seen, in
+  /// feet.
+  pub sight_distance: f32,
+}
+
+impl Default for Minimums {
+  fn default() -> Self {
+    Self {
+      decision_height: 200.0,
+      sight_distance: NAUTICALMILES_TO_FEET * 2.0,
+    }
+  }
+}
+
+/// A named departure or arrival, such as a SID or STAR, which is flown
+/// through its fixes in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Procedure {
+  pub id: Intern<String>,
+  pub fixes: Vec<Intern<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
@@ -59,6 +229,11 @@ pub struct Runway {
   pub pos: Vec2,
   pub heading: f32,
   pub length: f32,
+
+  #[serde(default)]
+  pub missed_approach: MissedApproach,
+  #[serde(default)]
+  pub minimums: Minimums,
 }
 
 impl Runway {
@@ -69,6 +244,11 @@ impl Runway {
   pub fn end(&self) -> Vec2 {
     move_point(self.pos, self.heading, self.length * 0.5)
   }
+
+  /// The heading of the runway as published, which is magnetic.
+  pub fn magnetic_heading(&self, variation: f32) -> f32 {
+    true_to_magnetic(self.heading, variation)
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -100,6 +280,8 @@ pub struct Terminal {
   pub d: Vec2,
 
   pub gates: Vec<Gate>,
+  #[serde(default)]
+  pub helipads: Vec<Helipad>,
   pub apron: Line,
 }
 
@@ -108,4 +290,230 @@ pub struct Gate {
   pub id: Intern<String>,
   pub pos: Vec2,
   pub heading: f32,
+  /// The terminal that `id` has been namespaced by, once the gate is added to
+  /// an airport.
+  #[serde(default)]
+  pub terminal: Option<Intern<String>>,
+}
+
+/// A pad off of an apron that rotorcraft can taxi to and lift off from,
+/// without needing a runway.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Helipad {
+  pub id: Intern<String>,
+  pub pos: Vec2,
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::pathfinder::{Node, NodeBehavior, NodeKind};
+
+  use super::*;
+
+  fn terminal(id: &str, x: f32) -> Terminal {
+    let apron = Line::new(Vec2::new(x, -100.0), Vec2::new(x, 100.0));
+    Terminal {
+      id: Intern::from_ref(id),
+      a: apron.0,
+      b: apron.1,
+      c: apron.1 + Vec2::X * 100.0,
+      d: apron.0 + Vec2::X * 100.0,
+      gates: vec![Gate {
+        id: Intern::from_ref("1"),
+        pos: Vec2::new(x + 50.0, 50.0),
+        heading: 90.0,
+        terminal: None,
+      }],
+      helipads: Vec::new(),
+      apron,
+    }
+  }
+
+  #[test]
+  fn best_runway_is_into_the_wind() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    for (id, heading, length) in [("27", 270.0, 9000.0), ("09", 90.0, 7000.0)] {
+      airport.add_runway(Runway {
+        id: Intern::from_ref(id),
+        pos: Vec2::ZERO,
+        heading,
+        length,
+        missed_approach: MissedApproach::default(),
+        minimums: Minimums::default(),
+      });
+    }
+
+    for heading in (0..360).step_by(10) {
+      let wind = Wind {
+        heading: heading as f32,
+        speed: 15.0,
+      };
+      let expected = match heading {
+        // A direct crosswind, from 0 or 180, ties and so goes to the longer
+        // runway.
+        0 | 180..=350 => "27",
+        _ => "09",
+      };
+
+      let best = airport.best_runway(wind).unwrap();
+      assert_eq!(best.id.as_str(), expected, "wind from {heading}");
+    }
+
+    let calm = Wind::default();
+    assert_eq!(airport.best_runway(calm).unwrap().id.as_str(), "27");
+    assert!(Airport::new(Intern::from_ref("KJFK"), Vec2::ZERO)
+      .best_runway(calm)
+      .is_none());
+  }
+
+  #[test]
+  fn colliding_gate_numbers_are_namespaced() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("T"),
+      Vec2::new(0.0, 0.0),
+      Vec2::new(1000.0, 0.0),
+    ));
+    airport.add_terminal(terminal("A", 200.0));
+    airport.add_terminal(terminal("B", 800.0));
+    airport.calculate_waypoints();
+
+    let gates: Vec<&str> = airport
+      .terminals
+      .iter()
+      .flat_map(|t| t.gates.iter().map(|g| g.id.as_str()))
+      .collect();
+    assert_eq!(gates, vec!["A1", "B1"]);
+
+    let path = airport
+      .pathfinder
+      .path_to(
+        Node::new(
+          Intern::from_ref("T"),
+          NodeKind::Taxiway,
+          NodeBehavior::GoTo,
+          (),
+        ),
+        Node::new(
+          Intern::from_ref("B1"),
+          NodeKind::Gate,
+          NodeBehavior::Park,
+          (),
+        ),
+        Vec2::new(0.0, 0.0),
+        90.0,
+      )
+      .unwrap();
+
+    let names: Vec<(NodeKind, &str)> = path
+      .path
+      .iter()
+      .map(|n| (n.kind, n.name.as_str()))
+      .collect();
+    assert_eq!(names, vec![(NodeKind::Apron, "B"), (NodeKind::Gate, "B1")]);
+  }
+
+  #[test]
+  fn gates_are_namespaced_once() {
+    let mut numbered = terminal("1", 200.0);
+    let mut gate = numbered.gates[0].clone();
+    gate.id = Intern::from_ref("10");
+    numbered.gates.push(gate);
+
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_terminal(numbered);
+    // Adding it again, such as to another airport, leaves the ids alone.
+    airport.add_terminal(airport.terminals[0].clone());
+
+    for terminal in airport.terminals.iter() {
+      let gates: Vec<&str> =
+        terminal.gates.iter().map(|g| g.id.as_str()).collect();
+      assert_eq!(gates, vec!["11", "110"]);
+    }
+  }
+
+  #[test]
+  fn rotorcraft_taxi_to_helipads() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("T"),
+      Vec2::new(0.0, 0.0),
+      Vec2::new(1000.0, 0.0),
+    ));
+    // A runway crossing the taxiway past the apron, which we shouldn't use.
+    airport.add_runway(Runway {
+      id: Intern::from_ref("36"),
+      pos: Vec2::new(600.0, 0.0),
+      heading: 0.0,
+      length: 2000.0,
+      ..Default::default()
+    });
+    let mut terminal = terminal("A", 200.0);
+    terminal.helipads.push(Helipad {
+      id: Intern::from_ref("H1"),
+      pos: Vec2::new(250.0, -50.0),
+    });
+    airport.add_terminal(terminal);
+    airport.calculate_waypoints();
+
+    let path = airport
+      .pathfinder
+      .path_to(
+        Node::new(
+          Intern::from_ref("T"),
+          NodeKind::Taxiway,
+          NodeBehavior::GoTo,
+          (),
+        ),
+        Node::new(
+          Intern::from_ref("H1"),
+          NodeKind::Helipad,
+          NodeBehavior::Park,
+          (),
+        ),
+        Vec2::new(0.0, 0.0),
+        90.0,
+      )
+      .unwrap();
+
+    let names: Vec<(NodeKind, &str)> = path
+      .path
+      .iter()
+      .map(|n| (n.kind, n.name.as_str()))
+      .collect();
+    assert_eq!(
+      names,
+      vec![(NodeKind::Apron, "A"), (NodeKind::Helipad, "H1")]
+    );
+    assert_eq!(path.path.last().unwrap().behavior, NodeBehavior::Park);
+  }
+
+  #[test]
+  fn export_graph_dot() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.add_taxiway(Taxiway::new(
+      Intern::from_ref("T"),
+      Vec2::new(0.0, 0.0),
+      Vec2::new(1000.0, 0.0),
+    ));
+    airport.add_terminal(terminal("A", 200.0));
+    airport.add_terminal(terminal("B", 800.0));
+    airport.calculate_waypoints();
+
+    let dot = airport.export_graph_dot();
+    assert!(dot.starts_with("graph \"KSFO\" {"));
+    for name in [
+      "Taxiway: T",
+      "Apron: A",
+      "Apron: B",
+      "Gate: !A1",
+      "Gate: !B1",
+    ] {
+      assert!(dot.contains(&format!("[label=\"{name}\"]")), "{name}");
+    }
+
+    // T meets both aprons, and each apron leads to its gate.
+    assert_eq!(dot.matches(" -- ").count(), 4);
+    assert!(dot.contains("[label=\"(200, 0)\"]"));
+  }
 }
@@ -1,8 +1,15 @@
+use std::time::Duration;
+
 use glam::Vec2;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
 use turborand::{rng::Rng, TurboRand};
 
+use crate::{
+  circle_line_intersection, crosswind_component, headwind_component, Line,
+  NAUTICALMILES_TO_FEET,
+};
+
 use super::airport::Airport;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -40,6 +47,47 @@ impl Frequencies {
   }
 }
 
+/// The default tailwind limit for landing, in knots.
+pub const DEFAULT_MAX_TAILWIND: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Wind {
+  /// The direction the wind is blowing from, in degrees.
+  pub heading: f32,
+  /// In knots.
+  pub speed: f32,
+}
+
+/// The altitudes that flights are routed at, which differ between regions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Altitudes {
+  /// Flights above this are en route, in feet.
+  pub transition: f32,
+  /// Inbound flights arrive in the airspace at this altitude, in feet.
+  pub arrival: f32,
+  /// Flights leaving the airspace climb to this altitude, in feet.
+  pub departure: f32,
+}
+
+impl Default for Altitudes {
+  fn default() -> Self {
+    Self {
+      transition: 18000.0,
+      arrival: 7000.0,
+      departure: 13000.0,
+    }
+  }
+}
+
+/// How long an aircraft waits lined up on the runway for its takeoff
+/// clearance before reminding ATC.
+pub const DEFAULT_LINE_UP_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How much flying time of fuel aircraft keep in reserve before declaring
+/// minimum fuel.
+pub const DEFAULT_FUEL_RESERVE: Duration = Duration::from_secs(30 * 60);
+
 // TODO: Support non-circular (regional) airspaces
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Airspace {
@@ -48,6 +96,39 @@ pub struct Airspace {
   pub radius: f32,
   pub airports: Vec<Airport>,
   pub frequencies: Frequencies,
+  /// Airborne aircraft descending below this altitude are warned, if set.
+  #[serde(default)]
+  pub min_safe_altitude: Option<f32>,
+  #[serde(default)]
+  pub wind: Wind,
+  /// Landing is refused on runways with a stronger tailwind than this, in
+  /// knots. Defaults to [`DEFAULT_MAX_TAILWIND`].
+  #[serde(default)]
+  pub max_tailwind: Option<f32>,
+  /// The lowest altitude we control, in feet. Defaults to the surface.
+  #[serde(default)]
+  pub floor: Option<f32>,
+  /// The highest altitude we control, in feet, if limited.
+  #[serde(default)]
+  pub ceiling: Option<f32>,
+  /// How far pilots can see, in nautical miles. Unlimited if not set.
+  #[serde(default)]
+  pub visibility: Option<f32>,
+  /// How long an aircraft waits lined up on the runway for its takeoff
+  /// clearance before reminding ATC, in seconds. Defaults to
+  /// [`DEFAULT_LINE_UP_TIMEOUT`].
+  #[serde(default)]
+  pub line_up_timeout: Option<f32>,
+  /// Whether aircraft taxi off the runway after reminding ATC, instead of
+  /// waiting there.
+  #[serde(default)]
+  pub vacate_after_line_up_timeout: bool,
+  /// How much flying time of fuel aircraft keep in reserve before declaring
+  /// minimum fuel, in minutes. Defaults to [`DEFAULT_FUEL_RESERVE`].
+  #[serde(default)]
+  pub fuel_reserve: Option<f32>,
+  #[serde(default)]
+  pub altitudes: Altitudes,
 }
 
 impl Airspace {
@@ -56,6 +137,82 @@ impl Airspace {
     distance <= self.radius.powf(2.0)
   }
 
+  /// Where a track from `from` straight to our center first crosses our
+  /// boundary, or `from` if it is already inside.
+  pub fn boundary_entry(&self, from: Vec2) -> Vec2 {
+    circle_line_intersection(self.pos, self.radius, Line::new(from, self.pos))
+      .first()
+      .copied()
+      .unwrap_or(from)
+  }
+
+  /// Our airports whose center is within `radius` of `point`, nearest first.
+  pub fn airports_within(&self, point: Vec2, radius: f32) -> Vec<&Airport> {
+    let radius_squared = radius.powf(2.0);
+    let mut airports: Vec<(f32, &Airport)> = self
+      .airports
+      .iter()
+      .map(|airport| (airport.center.distance_squared(point), airport))
+      .filter(|(distance, _)| *distance <= radius_squared)
+      .collect();
+    airports.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    airports.into_iter().map(|(_, airport)| airport).collect()
+  }
+
+  pub fn contains_altitude(&self, altitude: f32) -> bool {
+    !self.floor.is_some_and(|floor| altitude < floor)
+      && !self.ceiling.is_some_and(|ceiling| altitude > ceiling)
+  }
+
+  pub fn contains(&self, point: Vec2, altitude: f32) -> bool {
+    self.contains_point(point) && self.contains_altitude(altitude)
+  }
+
+  /// Limits `altitude` to between our floor and ceiling.
+  pub fn clamp_altitude(&self, altitude: f32) -> f32 {
+    let altitude = self.ceiling.map_or(altitude, |c| altitude.min(c));
+    self.floor.map_or(altitude, |f| altitude.max(f))
+  }
+
+  /// Whether something `distance` feet away can be seen.
+  pub fn is_visible(&self, distance: f32) -> bool {
+    self
+      .visibility
+      .is_none_or(|v| distance <= v * NAUTICALMILES_TO_FEET)
+  }
+
+  pub fn line_up_timeout(&self) -> Duration {
+    self
+      .line_up_timeout
+      .map_or(DEFAULT_LINE_UP_TIMEOUT, Duration::from_secs_f32)
+  }
+
+  pub fn fuel_reserve(&self) -> Duration {
+    self
+      .fuel_reserve
+      .map_or(DEFAULT_FUEL_RESERVE, |m| Duration::from_secs_f32(m * 60.0))
+  }
+
+  pub fn max_tailwind(&self) -> f32 {
+    self.max_tailwind.unwrap_or(DEFAULT_MAX_TAILWIND)
+  }
+
+  /// The headwind component for a runway facing `heading`, in knots.
+  pub fn headwind(&self, heading: f32) -> f32 {
+    headwind_component(heading, self.wind.heading, self.wind.speed)
+  }
+
+  /// The tailwind component for a runway facing `heading`, in knots.
+  pub fn tailwind(&self, heading: f32) -> f32 {
+    -self.headwind(heading)
+  }
+
+  /// The crosswind component for a runway facing `heading`, in knots.
+  pub fn crosswind(&self, heading: f32) -> f32 {
+    crosswind_component(heading, self.wind.heading, self.wind.speed)
+  }
+
   pub fn find_random_airport(&self, rng: &mut Rng) -> Option<&Airport> {
     rng.sample_iter(self.airports.iter())
   }
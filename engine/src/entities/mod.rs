@@ -2,4 +2,5 @@ pub mod aircraft;
 pub mod airport;
 pub mod airspace;
 pub mod flight;
+pub mod strip;
 pub mod world;
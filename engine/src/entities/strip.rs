@@ -0,0 +1,102 @@
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+
+use super::{
+  aircraft::{Aircraft, AircraftKind, FlightSegment},
+  airspace::Airspace,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A flight progress strip: a controller's summary of an aircraft.
+pub struct FlightStrip {
+  pub callsign: Intern<String>,
+  /// The aircraft type.
+  pub kind: AircraftKind,
+  pub departing: Intern<String>,
+  pub arriving: Intern<String>,
+
+  /// The altitude we were last assigned.
+  pub assigned_altitude: f32,
+  /// The altitude filed in our flight plan.
+  pub cruise_altitude: f32,
+  pub squawk: Option<u16>,
+  /// The filed route, as written.
+  pub route: String,
+
+  pub segment: FlightSegment,
+  pub frequency: f32,
+  /// How far along our planned route we are, from 0 to 1.
+  pub progress: f32,
+}
+
+impl FlightStrip {
+  pub fn new(aircraft: &Aircraft, airspace: &Airspace) -> Self {
+    Self {
+      callsign: aircraft.id,
+      kind: aircraft.kind.clone(),
+      departing: aircraft.flight_plan.departing,
+      arriving: aircraft.flight_plan.arriving,
+
+      assigned_altitude: aircraft.target.altitude,
+      cruise_altitude: aircraft.flight_plan.altitude,
+      squawk: aircraft.squawk,
+      route: aircraft.flight_plan.route.clone(),
+
+      segment: aircraft.segment(airspace),
+      frequency: aircraft.frequency,
+      progress: aircraft.flight_progress(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use glam::Vec2;
+
+  use crate::entities::{
+    aircraft::{AircraftState, FlightPlan},
+    airport::Gate,
+  };
+
+  use super::*;
+
+  #[test]
+  fn departing_strip() {
+    let airspace = Airspace {
+      id: Intern::from_ref("KSFO"),
+      ..Default::default()
+    };
+    let gate = Gate {
+      id: Intern::from_ref("A1"),
+      pos: Vec2::ZERO,
+      heading: 0.0,
+      terminal: None,
+    };
+
+    let mut aircraft = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      state: AircraftState::Parked {
+        at: gate.into(),
+        active: true,
+      },
+      flight_plan: FlightPlan {
+        altitude: 9000.0,
+        ..FlightPlan::new(Intern::from_ref("KSFO"), Intern::from_ref("KLAX"))
+      },
+      squawk: Some(4321),
+      kind: AircraftKind::B737,
+      ..Default::default()
+    };
+    aircraft.target.altitude = 4000.0;
+
+    let strip = FlightStrip::new(&aircraft, &airspace);
+    assert_eq!(strip.callsign.as_str(), "AAL1234");
+    assert_eq!(strip.kind, AircraftKind::B737);
+    assert_eq!(strip.departing.as_str(), "KSFO");
+    assert_eq!(strip.arriving.as_str(), "KLAX");
+    assert_eq!(strip.assigned_altitude, 4000.0);
+    assert_eq!(strip.cruise_altitude, 9000.0);
+    assert_eq!(strip.squawk, Some(4321));
+    assert_eq!(strip.segment, FlightSegment::Parked);
+  }
+}
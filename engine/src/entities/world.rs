@@ -1,33 +1,43 @@
 use std::{
-  collections::VecDeque,
-  time::{Duration, SystemTime},
+  collections::{HashMap, HashSet, VecDeque},
+  fmt,
+  ops::{Deref, DerefMut},
+  time::Duration,
 };
 
 use glam::Vec2;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
 
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+  airline::Airlines,
+  pathfinder::{new_vor, Node, NodeVORData, Pathfinder},
+};
+
 use super::{
-  aircraft::Aircraft, airport::Airport, airspace::Airspace, flight::Flights,
+  aircraft::Aircraft,
+  airport::{Airport, Procedure},
+  airspace::Airspace,
+  flight::Flights,
 };
 
 pub fn closest_airport(airspace: &Airspace, point: Vec2) -> Option<&Airport> {
-  let mut closest: Option<&Airport> = None;
-  let mut distance = f32::MAX;
-  for airport in airspace.airports.iter() {
-    if airport.center.distance_squared(point) < distance {
-      distance = airport.center.distance_squared(point);
-      closest = Some(airport);
-    }
-  }
-
-  closest
+  airspace
+    .airports_within(point, f32::INFINITY)
+    .first()
+    .copied()
 }
 
+/// Calculates the waypoints of airports that weren't loaded with a cached
+/// pathfinder graph.
 pub fn calculate_airport_waypoints(airspaces: &mut [Airspace]) {
   for airspace in airspaces.iter_mut() {
     for airport in airspace.airports.iter_mut() {
-      airport.calculate_waypoints();
+      if !airport.pathfinder.is_calculated() {
+        airport.calculate_waypoints();
+      }
     }
   }
 }
@@ -48,10 +58,218 @@ pub struct Connection {
   pub transition: Vec2,
 }
 
+/// A problem with a world that was generated or loaded, which the game can
+/// still run with but likely wasn't meant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldWarning {
+  /// An aircraft's flight plan names an airspace or airport that doesn't
+  /// exist.
+  UnknownAirport {
+    aircraft: Intern<String>,
+    airport: Intern<String>,
+  },
+  /// Two airspaces overlap. Connections have no area of their own, so they
+  /// only overlap ours, or another connection at the same center.
+  OverlappingAirspaces(Intern<String>, Intern<String>),
+  /// Our airspace has no area to control.
+  EmptyAirspace(Intern<String>),
+  /// A SID or STAR names a fix that we don't know of.
+  UnknownProcedureFix {
+    procedure: Intern<String>,
+    fix: Intern<String>,
+  },
+}
+
+impl fmt::Display for WorldWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      WorldWarning::UnknownAirport { aircraft, airport } => {
+        write!(
+          f,
+          "{aircraft} has a flight plan for unknown airport {airport}"
+        )
+      }
+      WorldWarning::OverlappingAirspaces(a, b) => {
+        write!(f, "airspaces {a} and {b} overlap")
+      }
+      WorldWarning::EmptyAirspace(id) => {
+        write!(f, "airspace {id} has no radius")
+      }
+      WorldWarning::UnknownProcedureFix { procedure, fix } => {
+        write!(f, "procedure {procedure} names unknown fix {fix}")
+      }
+    }
+  }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct World {
   pub airspace: Airspace,
   pub connections: Vec<Connection>,
+  /// How many degrees magnetic north is east of true north. Headings given
+  /// by ATC are magnetic, while the geometry of the world is true.
+  #[serde(default)]
+  pub magnetic_variation: f32,
+  /// The airlines that callsigns are generated for and spoken with.
+  #[serde(default)]
+  pub airlines: Airlines,
+}
+
+impl World {
+  /// A copy of the world without the pathfinder graphs of its airports, for
+  /// clients that have no use for them. They're only needed to restore a
+  /// save without recalculating them.
+  pub fn without_pathfinders(&self) -> World {
+    let mut world = self.clone();
+    for airport in world.airspace.airports.iter_mut() {
+      airport.pathfinder = Pathfinder::new();
+    }
+
+    world
+  }
+
+  /// Checks that `aircraft` only fly to and from places in the world, that
+  /// its airspaces are laid out sensibly, and that procedures only name
+  /// fixes we know of.
+  pub fn validate(&self, aircraft: &[Aircraft]) -> Vec<WorldWarning> {
+    let mut warnings = Vec::new();
+
+    let airspaces: Vec<(Intern<String>, Vec2, f32)> = core::iter::once((
+      self.airspace.id,
+      self.airspace.pos,
+      self.airspace.radius,
+    ))
+    .chain(self.connections.iter().map(|c| (c.id, c.pos, 0.0)))
+    .collect();
+    let known: HashSet<Intern<String>> = airspaces
+      .iter()
+      .map(|(id, _, _)| *id)
+      .chain(self.airspace.airports.iter().map(|a| a.id))
+      .collect();
+
+    for aircraft in aircraft.iter() {
+      let plan = &aircraft.flight_plan;
+      for airport in [plan.departing, plan.arriving] {
+        // Aircraft without a flight have nowhere to go.
+        if !airport.is_empty() && !known.contains(&airport) {
+          warnings.push(WorldWarning::UnknownAirport {
+            aircraft: aircraft.id,
+            airport,
+          });
+        }
+      }
+    }
+
+    for (i, (a, a_pos, a_radius)) in airspaces.iter().enumerate() {
+      for (b, b_pos, b_radius) in airspaces.iter().skip(i + 1) {
+        if a_pos.distance(*b_pos) < (a_radius + b_radius).max(1.0) {
+          warnings.push(WorldWarning::OverlappingAirspaces(*a, *b));
+        }
+      }
+    }
+
+    if self.airspace.radius <= 0.0 {
+      warnings.push(WorldWarning::EmptyAirspace(self.airspace.id));
+    }
+
+    for procedure in self.airspace.airports.iter().flat_map(|a| &a.procedures) {
+      for fix in procedure.fixes.iter() {
+        if self.find_waypoint(*fix).is_none() {
+          warnings.push(WorldWarning::UnknownProcedureFix {
+            procedure: procedure.id,
+            fix: *fix,
+          });
+        }
+      }
+    }
+
+    warnings
+  }
+
+  /// Airports whose center is within `radius` of `point`, nearest first.
+  pub fn airports_within(&self, point: Vec2, radius: f32) -> Vec<&Airport> {
+    self.airspace.airports_within(point, radius)
+  }
+
+  /// A connected airspace for a departure to fly to. This is random, but
+  /// each aircraft already flying to one more than the least busy halves its
+  /// chances, so that departures don't pile up at a single airspace.
+  pub fn balanced_destination(
+    &self,
+    aircraft: &[Aircraft],
+    rng: &mut Rng,
+  ) -> Option<&Connection> {
+    let inbounds: Vec<usize> = self
+      .connections
+      .iter()
+      .map(|c| {
+        aircraft
+          .iter()
+          .filter(|a| a.flight_plan.arriving == c.id)
+          .count()
+      })
+      .collect();
+    let least = inbounds.iter().min().copied()?;
+    let weights: Vec<f32> = inbounds
+      .iter()
+      .map(|count| 0.5f32.powi((count - least) as i32))
+      .collect();
+
+    let mut pick = rng.f32() * weights.iter().sum::<f32>();
+    for (connection, weight) in self.connections.iter().zip(weights) {
+      if pick < weight {
+        return Some(connection);
+      }
+      pick -= weight;
+    }
+
+    self.connections.last()
+  }
+
+  /// The position of a named waypoint: a connected airspace or one of our
+  /// airports.
+  pub fn find_waypoint(&self, name: Intern<String>) -> Option<Vec2> {
+    self
+      .connections
+      .iter()
+      .find(|c| c.id == name)
+      .map(|c| c.pos)
+      .or_else(|| {
+        self
+          .airspace
+          .airports
+          .iter()
+          .find(|a| a.id == name)
+          .map(|a| a.center)
+      })
+  }
+
+  /// The SID or STAR named `id`, at any of our airports.
+  pub fn find_procedure(&self, id: Intern<String>) -> Option<&Procedure> {
+    self
+      .airspace
+      .airports
+      .iter()
+      .flat_map(|a| a.procedures.iter())
+      .find(|p| p.id == id)
+  }
+
+  /// The named waypoint closest to `point`, out of the same ones as
+  /// [`World::find_waypoint`].
+  pub fn nearest_waypoint(&self, point: Vec2) -> Option<Node<NodeVORData>> {
+    let airport = closest_airport(&self.airspace, point);
+    self
+      .connections
+      .iter()
+      .map(|c| (c.id, c.pos))
+      .chain(airport.map(|a| (a.id, a.center)))
+      .min_by(|a, b| {
+        a.1
+          .distance_squared(point)
+          .total_cmp(&b.1.distance_squared(point))
+      })
+      .map(|(name, pos)| new_vor(name, pos))
+  }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
@@ -61,21 +279,290 @@ pub struct Points {
 
   pub takeoffs: usize,
   pub takeoff_rate: Marker,
+
+  /// Only kept in the scored game mode.
+  #[serde(default)]
+  pub score: Option<Score>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub const COMPLETED_FLIGHT_SCORE: i32 = 100;
+pub const SEPARATION_LOSS_PENALTY: i32 = 50;
+pub const GO_AROUND_PENALTY: i32 = 20;
+/// Taken away for every minute that aircraft spend stopped while taxiing.
+pub const GROUND_DELAY_PENALTY: i32 = 5;
+
+/// The score of the scored game mode, which rewards completed flights and
+/// penalizes losses of separation, go-arounds and aircraft left waiting on
+/// the ground.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+  pub total: i32,
+
+  pub completed_flights: usize,
+  pub separation_losses: usize,
+  pub go_arounds: usize,
+  /// Time that aircraft have spent stopped while taxiing, in seconds.
+  pub ground_delay: f32,
+}
+
+impl Score {
+  pub fn calc_total(&mut self) -> i32 {
+    let delay_minutes = (self.ground_delay / 60.0).floor() as i32;
+
+    self.total = self.completed_flights as i32 * COMPLETED_FLIGHT_SCORE
+      - self.separation_losses as i32 * SEPARATION_LOSS_PENALTY
+      - self.go_arounds as i32 * GO_AROUND_PENALTY
+      - delay_minutes * GROUND_DELAY_PENALTY;
+
+    self.total
+  }
+}
+
+/// The slowest and fastest that the game can run, relative to real time.
+pub const MIN_TIME_SCALE: f32 = 0.25;
+pub const MAX_TIME_SCALE: f32 = 8.0;
+
+fn default_time_scale() -> f32 {
+  1.0
+}
+
+/// Something kept for each runway, by airport and then by runway, since
+/// runways at different airports can share an id.
+pub type PerRunway<T> = HashMap<Intern<String>, HashMap<Intern<String>, T>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Game {
-  pub aircraft: Vec<Aircraft>,
+  /// Only added to and removed from through [`Game::push_aircraft`] and
+  /// [`Game::remove_aircraft`], which keep `aircraft_index` up to date.
+  aircraft: Vec<Aircraft>,
   pub funds: usize,
   pub flights: Flights,
   pub points: Points,
   pub paused: bool,
+  /// Leaves routine callouts out of the message log.
+  #[serde(default)]
+  pub silent: bool,
+  /// How many seconds of game time pass for each second of real time.
+  #[serde(default = "default_time_scale")]
+  pub time_scale: f32,
+
+  /// Aircraft waiting to depart, per airport and then runway, in the order
+  /// that they arrived at the hold short line.
+  #[serde(default)]
+  pub departure_queues: PerRunway<Vec<Intern<String>>>,
+  /// When the last aircraft departed from each runway, for spacing out the
+  /// ones after it.
+  #[serde(default)]
+  pub last_departures: PerRunway<Duration>,
+
+  /// Position of each aircraft in [`Game::aircraft`], keyed by id. This is
+  /// also the registry of callsigns in use, which are freed once their
+  /// aircraft is removed.
+  #[serde(skip)]
+  aircraft_index: HashMap<Intern<String>, usize>,
+}
+
+impl Default for Game {
+  fn default() -> Self {
+    Self {
+      aircraft: Vec::new(),
+      funds: 0,
+      flights: Flights::default(),
+      points: Points::default(),
+      paused: false,
+      silent: false,
+      time_scale: default_time_scale(),
+      departure_queues: HashMap::new(),
+      last_departures: HashMap::new(),
+      aircraft_index: HashMap::new(),
+    }
+  }
+}
+
+impl Game {
+  /// The time scale, kept within [`MIN_TIME_SCALE`] and [`MAX_TIME_SCALE`].
+  pub fn time_scale(&self) -> f32 {
+    self.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE)
+  }
+
+  pub fn aircraft(&self) -> &[Aircraft] {
+    &self.aircraft
+  }
+
+  /// Mutable access to every aircraft. Changing an id is allowed, and
+  /// re-indexes them once the access is dropped.
+  pub fn aircraft_mut(&mut self) -> AircraftListMut<'_> {
+    AircraftListMut {
+      aircraft: &mut self.aircraft,
+      index: &mut self.aircraft_index,
+    }
+  }
+
+  pub fn aircraft_by_id(&self, id: Intern<String>) -> Option<&Aircraft> {
+    self
+      .aircraft_index
+      .get(&id)
+      .and_then(|i| self.aircraft.get(*i))
+      .filter(|a| a.id == id)
+  }
+
+  /// Mutable access to the aircraft with `id`. Changing its id is allowed,
+  /// and re-indexes them once the access is dropped.
+  pub fn aircraft_by_id_mut(
+    &mut self,
+    id: Intern<String>,
+  ) -> Option<AircraftMut<'_>> {
+    let i = *self.aircraft_index.get(&id)?;
+    (self.aircraft.get(i)?.id == id).then_some(AircraftMut {
+      aircraft: &mut self.aircraft,
+      index: &mut self.aircraft_index,
+      i,
+    })
+  }
+
+  /// Adds an aircraft, unless its callsign is already in use. Returns
+  /// whether it was added.
+  pub fn push_aircraft(&mut self, aircraft: Aircraft) -> bool {
+    if self.callsign_in_use(aircraft.id) {
+      tracing::warn!("Refusing duplicate aircraft {}", aircraft.id);
+      return false;
+    }
+
+    self.aircraft_index.insert(aircraft.id, self.aircraft.len());
+    self.aircraft.push(aircraft);
+    true
+  }
+
+  pub fn callsign_in_use(&self, id: Intern<String>) -> bool {
+    self.aircraft_index.contains_key(&id)
+  }
+
+  /// `id` if it isn't in use, or else a random callsign of one of
+  /// `airlines` that isn't.
+  pub fn free_callsign(
+    &self,
+    id: Intern<String>,
+    airlines: &Airlines,
+    rng: &mut Rng,
+  ) -> Intern<String> {
+    let mut id = id;
+    while self.callsign_in_use(id) {
+      id = Intern::from(airlines.random_callsign(rng));
+    }
+
+    id
+  }
+
+  pub fn remove_aircraft(&mut self, id: Intern<String>) -> Option<Aircraft> {
+    let index = self.aircraft_index.remove(&id)?;
+    let aircraft = self.aircraft.swap_remove(index);
+
+    // The last aircraft was moved into the removed slot.
+    if let Some(moved) = self.aircraft.get(index) {
+      self.aircraft_index.insert(moved.id, index);
+    }
+
+    Some(aircraft)
+  }
+
+  /// Rebuilds the id index from [`Game::aircraft`]. This must be called after
+  /// the list is modified directly, such as after deserializing a game. Any
+  /// aircraft with a callsign that is already in use are dropped.
+  pub fn reindex_aircraft(&mut self) {
+    reindex_aircraft(&mut self.aircraft, &mut self.aircraft_index);
+  }
+}
+
+fn reindex_aircraft(
+  aircraft: &mut Vec<Aircraft>,
+  index: &mut HashMap<Intern<String>, usize>,
+) {
+  let mut seen = HashSet::new();
+  aircraft.retain(|a| {
+    let unique = seen.insert(a.id);
+    if !unique {
+      tracing::warn!("Dropping duplicate aircraft {}", a.id);
+    }
+
+    unique
+  });
+
+  *index = aircraft
+    .iter()
+    .enumerate()
+    .map(|(i, a)| (a.id, i))
+    .collect();
+}
+
+/// Every aircraft of a [`Game`], borrowed mutably through
+/// [`Game::aircraft_mut`].
+pub struct AircraftListMut<'a> {
+  aircraft: &'a mut Vec<Aircraft>,
+  index: &'a mut HashMap<Intern<String>, usize>,
+}
+
+impl Deref for AircraftListMut<'_> {
+  type Target = [Aircraft];
+
+  fn deref(&self) -> &Self::Target {
+    self.aircraft
+  }
+}
+
+impl DerefMut for AircraftListMut<'_> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.aircraft
+  }
+}
+
+impl Drop for AircraftListMut<'_> {
+  fn drop(&mut self) {
+    let stale = self
+      .aircraft
+      .iter()
+      .enumerate()
+      .any(|(i, a)| self.index.get(&a.id) != Some(&i));
+    if stale {
+      reindex_aircraft(self.aircraft, self.index);
+    }
+  }
+}
+
+/// One aircraft of a [`Game`], borrowed mutably through
+/// [`Game::aircraft_by_id_mut`].
+pub struct AircraftMut<'a> {
+  aircraft: &'a mut Vec<Aircraft>,
+  index: &'a mut HashMap<Intern<String>, usize>,
+  i: usize,
+}
+
+impl Deref for AircraftMut<'_> {
+  type Target = Aircraft;
+
+  fn deref(&self) -> &Self::Target {
+    &self.aircraft[self.i]
+  }
+}
+
+impl DerefMut for AircraftMut<'_> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.aircraft[self.i]
+  }
+}
+
+impl Drop for AircraftMut<'_> {
+  fn drop(&mut self) {
+    let id = self.aircraft[self.i].id;
+    if self.index.get(&id) != Some(&self.i) {
+      reindex_aircraft(self.aircraft, self.index);
+    }
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Marker {
   window: Duration,
-  marks: VecDeque<SystemTime>,
+  marks: VecDeque<Duration>,
   rate: Duration,
 }
 
@@ -94,13 +581,13 @@ impl Marker {
     }
   }
 
-  pub fn mark(&mut self) {
-    self.marks.push_back(SystemTime::now());
+  pub fn mark(&mut self, now: Duration) {
+    self.marks.push_back(now);
   }
 
-  pub fn trim(&mut self) {
+  pub fn trim(&mut self, now: Duration) {
     while let Some(front) = self.marks.front() {
-      if front.elapsed().unwrap() > self.window {
+      if now.saturating_sub(*front) > self.window {
         self.marks.pop_front();
       } else {
         break;
@@ -108,8 +595,8 @@ impl Marker {
     }
   }
 
-  pub fn calc_rate(&mut self) -> Duration {
-    self.trim();
+  pub fn calc_rate(&mut self, now: Duration) -> Duration {
+    self.trim(now);
 
     let count = self.marks.len();
     if count > 0 {
@@ -124,3 +611,351 @@ impl Marker {
     self.marks.len()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use turborand::SeededCore;
+
+  use crate::entities::airport::Taxiway;
+
+  use super::*;
+
+  #[test]
+  fn aircraft_by_id() {
+    let mut game = Game::default();
+    for id in ["AAL1234", "SKW5678"] {
+      game.push_aircraft(Aircraft {
+        id: Intern::from_ref(id),
+        ..Default::default()
+      });
+    }
+
+    let aircraft = game.aircraft_by_id(Intern::from_ref("SKW5678"));
+    assert_eq!(aircraft.map(|a| a.id.as_str()), Some("SKW5678"));
+    assert!(game.aircraft_by_id(Intern::from_ref("JBU0000")).is_none());
+
+    game
+      .aircraft_by_id_mut(Intern::from_ref("AAL1234"))
+      .unwrap()
+      .altitude = 3000.0;
+    assert_eq!(game.aircraft()[0].altitude, 3000.0);
+    assert!(game
+      .aircraft_by_id_mut(Intern::from_ref("JBU0000"))
+      .is_none());
+  }
+
+  #[test]
+  fn aircraft_index_stays_consistent() {
+    let mut game = Game::default();
+    for id in ["AAL1234", "SKW5678", "JBU9012", "DAL3456"] {
+      game.push_aircraft(Aircraft {
+        id: Intern::from_ref(id),
+        ..Default::default()
+      });
+    }
+
+    let removed = game.remove_aircraft(Intern::from_ref("SKW5678"));
+    assert_eq!(removed.map(|a| a.id), Some(Intern::from_ref("SKW5678")));
+    assert!(game.remove_aircraft(Intern::from_ref("SKW5678")).is_none());
+
+    // Removing the last aircraft doesn't move anything.
+    game.remove_aircraft(Intern::from_ref("DAL3456"));
+    game.push_aircraft(Aircraft {
+      id: Intern::from_ref("UAL7890"),
+      ..Default::default()
+    });
+
+    assert_eq!(game.aircraft().len(), 3);
+    assert_eq!(game.aircraft_index.len(), 3);
+    for (i, aircraft) in game.aircraft().iter().enumerate() {
+      assert_eq!(game.aircraft_index.get(&aircraft.id), Some(&i));
+      assert_eq!(
+        game.aircraft_by_id(aircraft.id).map(|a| a.id),
+        Some(aircraft.id)
+      );
+    }
+    assert!(game.aircraft_by_id(Intern::from_ref("DAL3456")).is_none());
+  }
+
+  #[test]
+  fn changing_ids_reindexes_aircraft() {
+    let mut game = Game::default();
+    for id in ["AAL1234", "SKW5678", "JBU9012"] {
+      game.push_aircraft(Aircraft {
+        id: Intern::from_ref(id),
+        ..Default::default()
+      });
+    }
+
+    game
+      .aircraft_by_id_mut(Intern::from_ref("AAL1234"))
+      .unwrap()
+      .id = Intern::from_ref("UAL7890");
+    game.aircraft_mut()[1].id = Intern::from_ref("DAL3456");
+
+    for (old, new) in [("AAL1234", "UAL7890"), ("SKW5678", "DAL3456")] {
+      assert!(game.aircraft_by_id(Intern::from_ref(old)).is_none());
+      assert!(!game.callsign_in_use(Intern::from_ref(old)));
+      assert_eq!(
+        game
+          .aircraft_by_id(Intern::from_ref(new))
+          .map(|a| a.id.as_str()),
+        Some(new)
+      );
+    }
+    assert!(game.aircraft_by_id(Intern::from_ref("JBU9012")).is_some());
+  }
+
+  #[test]
+  fn destinations_are_balanced() {
+    let world = World {
+      connections: ["KLAX", "KSJC", "KOAK"]
+        .into_iter()
+        .map(|id| Connection {
+          id: Intern::from_ref(id),
+          ..Default::default()
+        })
+        .collect(),
+      ..Default::default()
+    };
+    let mut rng = Rng::with_seed(0);
+
+    let mut aircraft: Vec<Aircraft> = Vec::new();
+    for _ in 0..300 {
+      let destination = world.balanced_destination(&aircraft, &mut rng);
+      let mut departure = Aircraft::default();
+      departure.flight_plan.arriving = destination.unwrap().id;
+      aircraft.push(departure);
+    }
+
+    let counts: Vec<usize> = world
+      .connections
+      .iter()
+      .map(|c| {
+        aircraft
+          .iter()
+          .filter(|a| a.flight_plan.arriving == c.id)
+          .count()
+      })
+      .collect();
+    // Picking at random would usually leave a spread of around 15.
+    let spread = counts.iter().max().unwrap() - counts.iter().min().unwrap();
+    assert!(spread <= 5, "uneven destinations: {counts:?}");
+  }
+
+  #[test]
+  fn validate_finds_unknown_airports() {
+    let world = World {
+      airspace: Airspace {
+        id: Intern::from_ref("KSFO"),
+        radius: 1000.0,
+        ..Default::default()
+      },
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(50000.0, 0.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+
+    let mut known = Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      ..Default::default()
+    };
+    known.flight_plan.departing = Intern::from_ref("KSFO");
+    known.flight_plan.arriving = Intern::from_ref("KLAX");
+    assert!(world.validate(&[known.clone()]).is_empty());
+
+    let mut unknown = known.clone();
+    unknown.id = Intern::from_ref("SKW5678");
+    unknown.flight_plan.arriving = Intern::from_ref("KJFK");
+    assert_eq!(
+      world.validate(&[known, unknown]),
+      vec![WorldWarning::UnknownAirport {
+        aircraft: Intern::from_ref("SKW5678"),
+        airport: Intern::from_ref("KJFK"),
+      }]
+    );
+  }
+
+  #[test]
+  fn validate_finds_overlapping_airspaces() {
+    let connection = |id, x| Connection {
+      id: Intern::from_ref(id),
+      pos: Vec2::new(x, 0.0),
+      ..Default::default()
+    };
+    let world = World {
+      airspace: Airspace {
+        id: Intern::from_ref("KSFO"),
+        radius: 1000.0,
+        ..Default::default()
+      },
+      connections: vec![
+        connection("KLAX", 50000.0),
+        connection("KSJC", 50000.0),
+        connection("KOAK", 900.0),
+        connection("KSMF", 1100.0),
+      ],
+      ..Default::default()
+    };
+
+    assert_eq!(
+      world.validate(&[]),
+      vec![
+        WorldWarning::OverlappingAirspaces(
+          Intern::from_ref("KSFO"),
+          Intern::from_ref("KOAK"),
+        ),
+        WorldWarning::OverlappingAirspaces(
+          Intern::from_ref("KLAX"),
+          Intern::from_ref("KSJC"),
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn validate_finds_unknown_procedure_fixes() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.procedures.push(Procedure {
+      id: Intern::from_ref("PORTE3"),
+      fixes: ["KLAX", "AVE"].map(Intern::from_ref).to_vec(),
+    });
+    let world = World {
+      airspace: Airspace {
+        id: Intern::from_ref("NCT"),
+        radius: 1000.0,
+        airports: vec![airport],
+        ..Default::default()
+      },
+      connections: vec![Connection {
+        id: Intern::from_ref("KLAX"),
+        pos: Vec2::new(50000.0, 0.0),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+
+    assert_eq!(
+      world.validate(&[]),
+      vec![WorldWarning::UnknownProcedureFix {
+        procedure: Intern::from_ref("PORTE3"),
+        fix: Intern::from_ref("AVE"),
+      }]
+    );
+  }
+
+  #[test]
+  fn callsigns_are_freed_on_removal() {
+    let mut game = Game::default();
+    let mut rng = Rng::with_seed(0);
+    let aal = Intern::from_ref("AAL1234");
+    let aircraft = Aircraft {
+      id: aal,
+      ..Default::default()
+    };
+
+    assert!(game.push_aircraft(aircraft.clone()));
+    assert!(!game.push_aircraft(aircraft.clone()));
+    assert_eq!(game.aircraft().len(), 1);
+
+    let renamed = game.free_callsign(aal, &Airlines::default(), &mut rng);
+    assert_ne!(renamed, aal);
+    assert!(!game.callsign_in_use(renamed));
+
+    game.remove_aircraft(aal);
+    assert!(!game.callsign_in_use(aal));
+    assert_eq!(game.free_callsign(aal, &Airlines::default(), &mut rng), aal);
+    assert!(game.push_aircraft(aircraft));
+  }
+
+  #[test]
+  fn airports_within() {
+    let mut world = World::default();
+    for (id, x) in [("KLAX", 9000.0), ("KSFO", 0.0), ("KSJC", 4000.0)] {
+      world
+        .airspace
+        .airports
+        .push(Airport::new(Intern::from_ref(id), Vec2::new(x, 0.0)));
+    }
+
+    let ids = |airports: Vec<&Airport>| -> Vec<String> {
+      airports.iter().map(|a| a.id.to_string()).collect()
+    };
+
+    assert_eq!(
+      ids(world.airports_within(Vec2::new(5000.0, 0.0), 5000.0)),
+      vec!["KSJC", "KLAX", "KSFO"]
+    );
+    assert_eq!(
+      ids(world.airports_within(Vec2::new(1000.0, 0.0), 3000.0)),
+      vec!["KSFO", "KSJC"]
+    );
+    assert!(world
+      .airports_within(Vec2::new(0.0, 20000.0), 1000.0)
+      .is_empty());
+
+    // The closest airport is the first of them, however far away.
+    let closest = closest_airport(&world.airspace, Vec2::new(8000.0, 20000.0));
+    assert_eq!(closest.map(|a| a.id.as_str()), Some("KLAX"));
+  }
+
+  #[test]
+  fn clients_get_the_world_without_pathfinders() {
+    let mut airport = Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO);
+    airport.taxiways = vec![
+      Taxiway::new(Intern::from_ref("A"), Vec2::ZERO, Vec2::new(1000.0, 0.0)),
+      Taxiway::new(
+        Intern::from_ref("B"),
+        Vec2::new(500.0, -500.0),
+        Vec2::new(500.0, 500.0),
+      ),
+    ];
+    airport.calculate_waypoints();
+
+    let mut world = World::default();
+    world.airspace.airports.push(airport);
+
+    let saved = serde_json::to_value(&world).unwrap();
+    assert!(saved["airspace"]["airports"][0].get("pathfinder").is_some());
+
+    let sent = serde_json::to_value(world.without_pathfinders()).unwrap();
+    assert!(sent["airspace"]["airports"][0].get("pathfinder").is_none());
+    assert!(world.airspace.airports[0].pathfinder.is_calculated());
+  }
+
+  #[test]
+  fn nearest_waypoint() {
+    let mut world = World::default();
+    assert!(world.nearest_waypoint(Vec2::ZERO).is_none());
+
+    for (id, pos) in [
+      ("KLAX", Vec2::new(-30000.0, 0.0)),
+      ("KJFK", Vec2::new(0.0, 25000.0)),
+      ("KPHL", Vec2::new(20000.0, 20000.0)),
+    ] {
+      world.connections.push(Connection {
+        id: Intern::from_ref(id),
+        pos,
+        transition: pos,
+        ..Default::default()
+      });
+    }
+    world.airspace.airports.push(Airport::new(
+      Intern::from_ref("KSFO"),
+      Vec2::new(0.0, -5000.0),
+    ));
+
+    let nearest = |x, y| {
+      world
+        .nearest_waypoint(Vec2::new(x, y))
+        .map(|n| n.name.to_string())
+    };
+    assert_eq!(nearest(0.0, 0.0).as_deref(), Some("KSFO"));
+    assert_eq!(nearest(0.0, 15000.0).as_deref(), Some("KJFK"));
+    assert_eq!(nearest(15000.0, 15000.0).as_deref(), Some("KPHL"));
+    assert_eq!(nearest(-20000.0, 5000.0).as_deref(), Some("KLAX"));
+  }
+}
@@ -9,6 +9,8 @@ use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use turborand::{rng::Rng, TurboRand};
 
+pub mod airline;
+pub mod clock;
 pub mod engine;
 pub mod pathfinder;
 
@@ -35,8 +37,10 @@ pub fn duration_now() -> Duration {
   SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
 }
 
+/// Wraps an angle into `0.0..360.0`, no matter how many turns it is off by.
 pub fn normalize_angle(angle: f32) -> f32 {
-  (360.0 + angle) % 360.0
+  // A tiny negative angle wraps to exactly 360 after rounding.
+  angle.rem_euclid(360.0) % 360.0
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
@@ -110,8 +114,19 @@ pub fn inverse_degrees(degrees: f32) -> f32 {
   add_degrees(degrees, 180.0)
 }
 
+/// Converts a true heading to a magnetic one, where `variation` is how many
+/// degrees magnetic north is east of true north.
+pub fn true_to_magnetic(heading: f32, variation: f32) -> f32 {
+  normalize_angle(heading - variation)
+}
+
+/// Converts a magnetic heading to a true one. See [`true_to_magnetic`].
+pub fn magnetic_to_true(heading: f32, variation: f32) -> f32 {
+  normalize_angle(heading + variation)
+}
+
 pub fn delta_angle(current: f32, target: f32) -> f32 {
-  ((target - current + 540.0) % 360.0) - 180.0
+  normalize_angle(target - current + 180.0) - 180.0
 }
 
 pub fn angle_between_points(a: Vec2, b: Vec2) -> f32 {
@@ -205,32 +220,96 @@ pub fn get_random_point_on_circle(
   }
 }
 
-pub fn heading_to_direction(heading: f32) -> &'static str {
-  // Normalize the heading to be between 0 and 360
-  let normalized_heading = heading.rem_euclid(360.0);
+/// Points within a `size` wide square around the origin that are all at least
+/// `min_distance` apart, using Bridson's Poisson-disk sampling. Sampling stops
+/// once `attempts` candidates around every point have failed to fit, so the
+/// square is close to full.
+pub fn poisson_disk_sample(
+  rng: &mut Rng,
+  size: f32,
+  min_distance: f32,
+  attempts: usize,
+) -> Vec<Vec2> {
+  // Each cell is small enough to hold at most one point.
+  let cell_size = min_distance / 2.0_f32.sqrt();
+  let cells = ((size / cell_size).ceil() as usize).max(1);
+  let half = size * 0.5;
+  let cell_of = |point: Vec2| -> (usize, usize) {
+    let x = ((point.x + half) / cell_size) as usize;
+    let y = ((point.y + half) / cell_size) as usize;
+    (x.min(cells - 1), y.min(cells - 1))
+  };
+
+  let mut grid: Vec<Option<usize>> = vec![None; cells * cells];
+  let mut points: Vec<Vec2> = Vec::new();
+  let mut active: Vec<usize> = Vec::new();
+
+  let first = Vec2::new((rng.f32() - 0.5) * size, (rng.f32() - 0.5) * size);
+  let (x, y) = cell_of(first);
+  grid[y * cells + x] = Some(0);
+  points.push(first);
+  active.push(0);
+
+  while !active.is_empty() {
+    let index = rng.usize(0..active.len());
+    let base = points[active[index]];
+
+    let fits = |point: Vec2| -> bool {
+      if point.x.abs() >= half || point.y.abs() >= half {
+        return false;
+      }
+
+      let (x, y) = cell_of(point);
+      for ny in y.saturating_sub(2)..=(y + 2).min(cells - 1) {
+        for nx in x.saturating_sub(2)..=(x + 2).min(cells - 1) {
+          if let Some(other) = grid[ny * cells + nx] {
+            if points[other].distance(point) < min_distance {
+              return false;
+            }
+          }
+        }
+      }
+
+      true
+    };
+
+    let candidate = (0..attempts)
+      .map(|_| {
+        let angle = rng.f32() * 2.0 * PI;
+        let distance = min_distance * (1.0 + rng.f32());
+        base + Vec2::from_angle(angle) * distance
+      })
+      .find(|point| fits(*point));
+
+    if let Some(candidate) = candidate {
+      let (x, y) = cell_of(candidate);
+      grid[y * cells + x] = Some(points.len());
+      active.push(points.len());
+      points.push(candidate);
+    } else {
+      active.swap_remove(index);
+    }
+  }
+
+  points
+}
 
-  // Define the directions and their corresponding angle ranges
+pub fn heading_to_direction(heading: f32) -> &'static str {
+  // Each direction covers 45 degrees, centered on its heading, so that the
+  // half-open sectors cover the whole circle.
   let directions = [
-    ("North", 0.0, 22.5),
-    ("Northeast", 22.5, 67.5),
-    ("East", 67.5, 112.5),
-    ("Southeast", 112.5, 157.5),
-    ("South", 157.5, 202.5),
-    ("Southwest", 202.5, 247.5),
-    ("West", 247.5, 292.5),
-    ("Northwest", 292.5, 337.5),
-    ("North", 337.5, 360.0),
+    "North",
+    "Northeast",
+    "East",
+    "Southeast",
+    "South",
+    "Southwest",
+    "West",
+    "Northwest",
   ];
+  let sector = (normalize_angle(heading + 22.5) / 45.0) as usize;
 
-  // Find the matching direction
-  for (direction, start, end) in directions.iter() {
-    if normalized_heading >= *start && normalized_heading < *end {
-      return direction;
-    }
-  }
-
-  // This should never happen, but we'll return "Unknown" just in case
-  "Unknown"
+  directions[sector % directions.len()]
 }
 
 /// Abbreviates an altitude to feet or flight level (depending on the altitude).
@@ -242,6 +321,114 @@ pub fn abbreviate_altitude(altitude: f32) -> String {
   }
 }
 
+/// How each digit is said on the radio.
+pub const NATO_DIGITS: [&str; 10] = [
+  "zero", "one", "two", "three", "four", "five", "six", "seven", "eight",
+  "niner",
+];
+
+/// Numbers up to twenty as they are said in words, such as "ten thousand".
+const NUMBER_WORDS: [&str; 21] = [
+  "zero",
+  "one",
+  "two",
+  "three",
+  "four",
+  "five",
+  "six",
+  "seven",
+  "eight",
+  "nine",
+  "ten",
+  "eleven",
+  "twelve",
+  "thirteen",
+  "fourteen",
+  "fifteen",
+  "sixteen",
+  "seventeen",
+  "eighteen",
+  "nineteen",
+  "twenty",
+];
+
+/// Says `number` one digit at a time, padded with zeros to `width` digits.
+fn spoken_digits(number: u32, width: usize) -> String {
+  format!("{number:0width$}")
+    .chars()
+    .filter_map(|c| c.to_digit(10))
+    .map(|d| NATO_DIGITS[d as usize])
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// A speed as it is said on the radio, such as "two five zero" for 250
+/// knots.
+pub fn spoken_speed(knots: f32) -> String {
+  spoken_digits(knots.round().max(0.0) as u32, 1)
+}
+
+/// A heading as it is said on the radio, always with three digits, such as
+/// "zero four zero". North is "three six zero".
+pub fn spoken_heading(heading: f32) -> String {
+  let heading = normalize_angle(heading.round()) as u32;
+  spoken_digits(if heading == 0 { 360 } else { heading }, 3)
+}
+
+/// A number said either as digits, such as "one zero" or "10", or as a single
+/// word up to twenty, such as "ten".
+fn parse_spoken_number(words: &[&str]) -> Option<u32> {
+  let digit = |word: &str| match word {
+    "nine" => Some(9),
+    word => NATO_DIGITS
+      .iter()
+      .position(|d| *d == word)
+      .map(|d| d as u32),
+  };
+
+  match words {
+    [] => None,
+    [word] => word.parse().ok().or_else(|| {
+      NUMBER_WORDS
+        .iter()
+        .position(|w| w == word)
+        .map(|n| n as u32)
+        .or_else(|| digit(word))
+    }),
+    words => words
+      .iter()
+      .try_fold(0, |number, word| Some(number * 10 + digit(word)?)),
+  }
+}
+
+/// Reads an altitude in feet from how it is said, such as "flight level
+/// three seven zero", "ten thousand" or "four thousand five hundred feet".
+/// This also reads what [`abbreviate_altitude`] writes.
+pub fn parse_spoken_altitude(text: &str) -> Option<f32> {
+  let text = text.to_lowercase();
+  let words: Vec<&str> = text
+    .split_whitespace()
+    .filter(|w| !matches!(*w, "feet" | "ft"))
+    .collect();
+
+  if let ["flight", "level", level @ ..] | ["fl", level @ ..] = words.as_slice()
+  {
+    return parse_spoken_number(level).map(|level| level as f32 * 100.0);
+  }
+
+  let (thousands, rest) = match words.iter().position(|w| *w == "thousand") {
+    Some(i) => (parse_spoken_number(&words[..i])?, &words[i + 1..]),
+    None => (0, words.as_slice()),
+  };
+  let hundreds = match rest {
+    [] if thousands > 0 => 0,
+    [hundreds @ .., "hundred"] => parse_spoken_number(hundreds)?,
+    _ => return None,
+  };
+
+  Some((thousands * 1000 + hundreds * 100) as f32)
+}
+
 // TODO: Use [`Line`] instead
 fn closest_point_on_line(
   point: Vec2,
@@ -264,6 +451,87 @@ fn closest_point_on_line(
   line_start + line_dir_normalized * projection
 }
 
+/// Distance from a point to the segment between `a` and `b`. Unlike
+/// [`closest_point_on_line`], the closest point is clamped to the segment.
+pub fn point_to_segment_distance(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+  let segment = b - a;
+  let length_squared = segment.length_squared();
+  if length_squared == 0.0 {
+    return point.distance(a);
+  }
+
+  let t = ((point - a).dot(segment) / length_squared).clamp(0.0, 1.0);
+  point.distance(a + segment * t)
+}
+
+/// The component of a wind blowing from `wind_heading` that is a headwind
+/// for a runway facing `runway_heading`. Negative values are a tailwind.
+pub fn headwind_component(
+  runway_heading: f32,
+  wind_heading: f32,
+  wind_speed: f32,
+) -> f32 {
+  wind_speed * delta_angle(runway_heading, wind_heading).to_radians().cos()
+}
+
+/// The speed of sound at sea level in the standard atmosphere, in knots.
+pub const SEA_LEVEL_SPEED_OF_SOUND: f32 = 661.47;
+
+/// The true airspeed in knots of `mach` at `altitude` feet, in the standard
+/// atmosphere. The temperature, and so the speed of sound, falls by about 2°C
+/// per thousand feet until the tropopause, above which it stays the same.
+pub fn mach_to_knots(mach: f32, altitude: f32) -> f32 {
+  const SEA_LEVEL_TEMPERATURE: f32 = 288.15;
+  const TROPOPAUSE_TEMPERATURE: f32 = 216.65;
+  const LAPSE_RATE: f32 = 0.0019812;
+
+  let temperature =
+    (SEA_LEVEL_TEMPERATURE - LAPSE_RATE * altitude).max(TROPOPAUSE_TEMPERATURE);
+  mach * SEA_LEVEL_SPEED_OF_SOUND * (temperature / SEA_LEVEL_TEMPERATURE).sqrt()
+}
+
+/// The component of a wind blowing from `wind_heading` that is across a
+/// runway facing `runway_heading`, from either side.
+pub fn crosswind_component(
+  runway_heading: f32,
+  wind_heading: f32,
+  wind_speed: f32,
+) -> f32 {
+  wind_speed
+    * delta_angle(runway_heading, wind_heading)
+      .to_radians()
+      .sin()
+      .abs()
+}
+
+/// How far ahead along a course [`intercept_heading`] aims, in feet.
+pub const INTERCEPT_LOOKAHEAD: f32 = NAUTICALMILES_TO_FEET;
+
+/// The heading to fly from `pos` to capture the course running from
+/// `line.0` to `line.1`, intercepting it at no more than `max_angle` degrees.
+pub fn intercept_heading(pos: Vec2, line: Line, max_angle: f32) -> f32 {
+  let course = angle_between_points(line.0, line.1);
+  let closest = closest_point_on_line(pos, line.0, line.1);
+  let aim = move_point(closest, course, INTERCEPT_LOOKAHEAD);
+
+  let correction = delta_angle(course, angle_between_points(pos, aim))
+    .clamp(-max_angle, max_angle);
+  normalize_angle(course + correction)
+}
+
+/// The radius of a turn flown at `speed` knots and `rate` degrees per second,
+/// in feet.
+pub fn turn_radius(speed: f32, rate: f32) -> f32 {
+  speed * KNOT_TO_FEET_PER_SECOND / rate.to_radians()
+}
+
+/// How far from a course to start turning onto it, in feet, so that a turn of
+/// `radius` feet through `angle` degrees rolls out on the course instead of
+/// overshooting it.
+pub fn turn_anticipation(radius: f32, angle: f32) -> f32 {
+  radius * (1.0 - angle.to_radians().cos())
+}
+
 pub fn circle_circle_intersection(
   lhs_position: Vec2,
   rhs_position: Vec2,
@@ -276,10 +544,166 @@ pub fn circle_circle_intersection(
   d <= lhs_radius + rhs_radius
 }
 
+/// The points where the segment `line` crosses a circle, in order from the
+/// start of the segment. A segment that is tangent to the circle touches it
+/// at a single point.
+pub fn circle_line_intersection(
+  center: Vec2,
+  radius: f32,
+  line: Line,
+) -> Vec<Vec2> {
+  let direction = line.1 - line.0;
+  let offset = line.0 - center;
+
+  let a = direction.dot(direction);
+  if a == 0.0 {
+    return Vec::new();
+  }
+  let b = 2.0 * offset.dot(direction);
+  let c = offset.dot(offset) - radius.powf(2.0);
+
+  let discriminant = b * b - 4.0 * a * c;
+  let roots = if discriminant < 0.0 {
+    Vec::new()
+  } else if discriminant == 0.0 {
+    vec![-b / (2.0 * a)]
+  } else {
+    let root = discriminant.sqrt();
+    vec![(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+  };
+
+  roots
+    .into_iter()
+    .filter(|t| (0.0..=1.0).contains(t))
+    .map(|t| line.0 + direction * t)
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  mod heading_to_direction {
+    use super::*;
+
+    #[test]
+    fn test_sector_boundaries() {
+      for (heading, direction) in [
+        (0.0, "North"),
+        (22.4, "North"),
+        (22.5, "Northeast"),
+        (22.55, "Northeast"),
+        (67.4, "Northeast"),
+        (67.5, "East"),
+        (112.5, "Southeast"),
+        (157.5, "South"),
+        (202.5, "Southwest"),
+        (247.5, "West"),
+        (292.5, "Northwest"),
+        (337.4, "Northwest"),
+        (337.5, "North"),
+        (359.99, "North"),
+        (360.0, "North"),
+      ] {
+        assert_eq!(heading_to_direction(heading), direction, "{heading}");
+      }
+    }
+
+    #[test]
+    fn test_wraps_out_of_range_headings() {
+      assert_eq!(heading_to_direction(-0.00001), "North");
+      assert_eq!(heading_to_direction(-90.0), "West");
+      assert_eq!(heading_to_direction(810.0), "East");
+    }
+  }
+
+  mod spoken {
+    use super::*;
+
+    #[test]
+    fn test_spoken_heading() {
+      assert_eq!(spoken_heading(40.0), "zero four zero");
+      assert_eq!(spoken_heading(5.0), "zero zero five");
+      assert_eq!(spoken_heading(0.0), "three six zero");
+      assert_eq!(spoken_heading(359.6), "three six zero");
+      assert_eq!(spoken_heading(270.0), "two seven zero");
+    }
+
+    #[test]
+    fn test_spoken_speed() {
+      assert_eq!(spoken_speed(250.0), "two five zero");
+      assert_eq!(spoken_speed(90.0), "niner zero");
+    }
+
+    #[test]
+    fn test_parse_spoken_altitude() {
+      assert_eq!(
+        parse_spoken_altitude("flight level three seven zero"),
+        Some(37000.0)
+      );
+      assert_eq!(parse_spoken_altitude("FL 190"), Some(19000.0));
+      assert_eq!(
+        parse_spoken_altitude("flight level niner zero"),
+        Some(9000.0)
+      );
+      assert_eq!(parse_spoken_altitude("ten thousand"), Some(10000.0));
+      assert_eq!(parse_spoken_altitude("one zero thousand"), Some(10000.0));
+      assert_eq!(
+        parse_spoken_altitude("four thousand five hundred feet"),
+        Some(4500.0)
+      );
+      assert_eq!(parse_spoken_altitude("eight hundred"), Some(800.0));
+      assert_eq!(parse_spoken_altitude("niner thousand"), Some(9000.0));
+
+      assert_eq!(parse_spoken_altitude(""), None);
+      assert_eq!(parse_spoken_altitude("thousand"), None);
+      assert_eq!(parse_spoken_altitude("flight level"), None);
+      assert_eq!(parse_spoken_altitude("ten thousand and change"), None);
+    }
+
+    #[test]
+    fn test_abbreviate_altitude_round_trips() {
+      for altitude in [3000.0, 10000.0, 12000.0, 13000.0, 24000.0, 37000.0] {
+        assert_eq!(
+          parse_spoken_altitude(&abbreviate_altitude(altitude)),
+          Some(altitude),
+          "{}",
+          abbreviate_altitude(altitude)
+        );
+      }
+    }
+  }
+
+  mod delta_angle {
+    use super::*;
+
+    #[test]
+    fn test_matches_normalized_angles() {
+      assert_eq!(delta_angle(350.0, 10.0), 20.0);
+      assert_eq!(delta_angle(10.0, 350.0), -20.0);
+      assert_eq!(delta_angle(90.0, 90.0 - 720.0), 0.0);
+      assert_eq!(delta_angle(-450.0, 0.0), 90.0);
+    }
+  }
+
+  mod magnetic_variation {
+    use super::*;
+
+    #[test]
+    fn test_easterly_variation() {
+      let magnetic = true_to_magnetic(100.0, 13.0);
+      assert_eq!(magnetic, 87.0);
+      assert_eq!(magnetic_to_true(magnetic, 13.0), 100.0);
+    }
+
+    #[test]
+    fn test_westerly_variation_wraps() {
+      let magnetic = true_to_magnetic(355.0, -10.0);
+      assert_eq!(magnetic, 5.0);
+      assert_eq!(magnetic_to_true(magnetic, -10.0), 355.0);
+    }
+  }
+
   mod angle_between_points {
     use super::*;
 
@@ -434,6 +858,114 @@ mod tests {
     }
   }
 
+  mod point_to_segment_distance {
+    use super::*;
+
+    #[test]
+    fn test_point_to_segment_distance_perpendicular() {
+      let a = Vec2::new(0.0, 0.0);
+      let b = Vec2::new(10.0, 0.0);
+
+      assert_eq!(point_to_segment_distance(Vec2::new(5.0, 3.0), a, b), 3.0);
+      assert_eq!(point_to_segment_distance(Vec2::new(5.0, -4.0), a, b), 4.0);
+      assert_eq!(point_to_segment_distance(Vec2::new(2.0, 0.0), a, b), 0.0);
+    }
+
+    #[test]
+    fn test_point_to_segment_distance_outside_segment() {
+      let a = Vec2::new(0.0, 0.0);
+      let b = Vec2::new(10.0, 0.0);
+
+      // The perpendicular foot is past the end, so measure to the end.
+      assert_eq!(point_to_segment_distance(Vec2::new(13.0, 4.0), a, b), 5.0);
+      // The perpendicular foot is before the start, so measure to the start.
+      assert_eq!(point_to_segment_distance(Vec2::new(-3.0, -4.0), a, b), 5.0);
+    }
+
+    #[test]
+    fn test_point_to_segment_distance_degenerate() {
+      let a = Vec2::new(1.0, 1.0);
+
+      assert_eq!(point_to_segment_distance(Vec2::new(4.0, 5.0), a, a), 5.0);
+    }
+  }
+
+  mod headwind_component {
+    use super::*;
+
+    #[test]
+    fn test_headwind_component() {
+      assert_eq!(headwind_component(270.0, 270.0, 15.0), 15.0);
+      assert_eq!(headwind_component(90.0, 270.0, 15.0), -15.0);
+      assert!(headwind_component(360.0, 90.0, 15.0).abs() < 0.001);
+      assert!((headwind_component(360.0, 60.0, 20.0) - 10.0).abs() < 0.001);
+    }
+  }
+
+  mod crosswind_component {
+    use super::*;
+
+    #[test]
+    fn test_crosswind_component() {
+      assert!((crosswind_component(270.0, 360.0, 15.0) - 15.0).abs() < 0.001);
+      assert!((crosswind_component(270.0, 180.0, 15.0) - 15.0).abs() < 0.001);
+      assert!(crosswind_component(270.0, 270.0, 15.0).abs() < 0.001);
+      assert!((crosswind_component(360.0, 30.0, 20.0) - 10.0).abs() < 0.001);
+    }
+  }
+
+  mod mach_to_knots {
+    use super::*;
+
+    #[test]
+    fn test_mach_to_knots() {
+      assert_eq!(mach_to_knots(1.0, 0.0), SEA_LEVEL_SPEED_OF_SOUND);
+      // Mach 0.78 at FL370, above the tropopause, is about 447 knots.
+      assert!((mach_to_knots(0.78, 37000.0) - 447.4).abs() < 1.0);
+      assert_eq!(mach_to_knots(0.78, 37000.0), mach_to_knots(0.78, 41000.0));
+    }
+  }
+
+  mod intercept_heading {
+    use super::*;
+
+    fn course() -> Line {
+      Line::new(Vec2::new(0.0, -50000.0), Vec2::new(0.0, 0.0))
+    }
+
+    #[test]
+    fn test_intercept_heading_on_course() {
+      let heading = intercept_heading(Vec2::new(0.0, -20000.0), course(), 30.0);
+      assert!(delta_angle(0.0, heading).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_intercept_heading_small_offset() {
+      // Left of the course, so turn right towards it.
+      let heading =
+        intercept_heading(Vec2::new(-500.0, -20000.0), course(), 30.0);
+      let correction = delta_angle(0.0, heading);
+      assert!(correction > 0.0 && correction < 30.0, "{correction}");
+
+      // Right of the course, so turn left towards it.
+      let heading =
+        intercept_heading(Vec2::new(500.0, -20000.0), course(), 30.0);
+      let correction = delta_angle(0.0, heading);
+      assert!(correction < 0.0 && correction > -30.0, "{correction}");
+    }
+
+    #[test]
+    fn test_intercept_heading_large_offset() {
+      let heading =
+        intercept_heading(Vec2::new(-20000.0, -20000.0), course(), 30.0);
+      assert!((delta_angle(0.0, heading) - 30.0).abs() < 0.01);
+
+      let heading =
+        intercept_heading(Vec2::new(20000.0, -20000.0), course(), 30.0);
+      assert!((delta_angle(0.0, heading) + 30.0).abs() < 0.01);
+    }
+  }
+
   mod find_line_intersection {
     use super::*;
 
@@ -455,4 +987,43 @@ mod tests {
       assert_eq!(intersection, Some(Vec2::new(0.0, 0.0)));
     }
   }
+
+  mod circle_line_intersection {
+    use super::*;
+
+    #[test]
+    fn test_miss() {
+      let line = Line(Vec2::new(-20.0, 15.0), Vec2::new(20.0, 15.0));
+      assert!(circle_line_intersection(Vec2::ZERO, 10.0, line).is_empty());
+
+      // Stops short of the circle.
+      let line = Line(Vec2::new(-20.0, 0.0), Vec2::new(-15.0, 0.0));
+      assert!(circle_line_intersection(Vec2::ZERO, 10.0, line).is_empty());
+    }
+
+    #[test]
+    fn test_tangent() {
+      let line = Line(Vec2::new(-20.0, 10.0), Vec2::new(20.0, 10.0));
+      assert_eq!(
+        circle_line_intersection(Vec2::ZERO, 10.0, line),
+        vec![Vec2::new(0.0, 10.0)]
+      );
+    }
+
+    #[test]
+    fn test_through() {
+      let line = Line(Vec2::new(-20.0, 0.0), Vec2::new(20.0, 0.0));
+      assert_eq!(
+        circle_line_intersection(Vec2::ZERO, 10.0, line),
+        vec![Vec2::new(-10.0, 0.0), Vec2::new(10.0, 0.0)]
+      );
+
+      // Ending inside the circle, it only crosses on the way in.
+      let line = Line(Vec2::new(-20.0, 0.0), Vec2::ZERO);
+      assert_eq!(
+        circle_line_intersection(Vec2::ZERO, 10.0, line),
+        vec![Vec2::new(-10.0, 0.0)]
+      );
+    }
+  }
 }
@@ -1,7 +1,9 @@
 use glam::Vec2;
 use internment::Intern;
 use petgraph::{
-  algo::simple_paths, visit::IntoNodeReferences, Graph, Undirected,
+  algo::simple_paths,
+  visit::{EdgeRef, IntoNodeReferences},
+  Graph, Undirected,
 };
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +11,7 @@ use crate::{
   angle_between_points, closest_point_on_line, delta_angle,
   entities::{
     aircraft::events::EventKind,
-    airport::{Gate, Runway, Taxiway, Terminal},
+    airport::{Gate, Helipad, Runway, Taxiway, Terminal},
   },
   find_line_intersection, Line,
 };
@@ -21,6 +23,8 @@ pub enum NodeKind {
   Runway,
   Gate,
   Apron,
+  /// A helipad, which rotorcraft can go to straight from the apron.
+  Helipad,
 
   VOR,
 }
@@ -40,7 +44,7 @@ pub enum NodeBehavior {
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct NodeVORData {
   pub to: Vec2,
-  #[serde(skip)]
+  #[serde(default)]
   pub then: Vec<EventKind>,
 }
 
@@ -125,6 +129,17 @@ impl From<Gate> for Node<Line> {
   }
 }
 
+impl From<Helipad> for Node<Line> {
+  fn from(value: Helipad) -> Self {
+    Self {
+      name: value.id,
+      kind: NodeKind::Helipad,
+      behavior: NodeBehavior::Park,
+      value: Line::new(value.pos, value.pos),
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Object {
   Taxiway(Taxiway),
@@ -238,7 +253,70 @@ pub struct PathfinderPath {
   pub final_pos: Vec2,
 }
 
-#[derive(Debug, Clone, Default)]
+/// How many extra waypoints a taxi route may take to avoid crossing a runway.
+pub const RUNWAY_CROSSING_COST: usize = 4;
+
+/// Bumped whenever the way the graph is calculated changes, so that stale
+/// cached graphs are discarded and recalculated.
+pub const PATHFINDER_GRAPH_VERSION: u32 = 2;
+
+/// A compact edge list of a [`WaypointGraph`], used to cache it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PathfinderGraphData {
+  version: u32,
+  nodes: Vec<Node<Line>>,
+  edges: Vec<(usize, usize, Vec2)>,
+}
+
+impl From<Pathfinder> for PathfinderGraphData {
+  fn from(value: Pathfinder) -> Self {
+    let graph = value.graph;
+    Self {
+      version: PATHFINDER_GRAPH_VERSION,
+      nodes: graph.node_weights().cloned().collect(),
+      edges: graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), *e.weight()))
+        .collect(),
+    }
+  }
+}
+
+impl From<PathfinderGraphData> for Pathfinder {
+  fn from(value: PathfinderGraphData) -> Self {
+    let mut pathfinder = Pathfinder::new();
+    if value.version != PATHFINDER_GRAPH_VERSION {
+      tracing::warn!(
+        "Discarding pathfinder graph with version {} (expected {})",
+        value.version,
+        PATHFINDER_GRAPH_VERSION
+      );
+      return pathfinder;
+    }
+
+    let nodes: Vec<_> = value
+      .nodes
+      .into_iter()
+      .map(|n| pathfinder.graph.add_node(n))
+      .collect();
+    for (a, b, intersection) in value.edges {
+      match (nodes.get(a), nodes.get(b)) {
+        (Some(a), Some(b)) => {
+          pathfinder.graph.add_edge(*a, *b, intersection);
+        }
+        _ => {
+          tracing::warn!("Discarding pathfinder graph with invalid edges");
+          return Pathfinder::new();
+        }
+      }
+    }
+
+    pathfinder
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(into = "PathfinderGraphData", from = "PathfinderGraphData")]
 pub struct Pathfinder {
   pub graph: WaypointGraph,
 }
@@ -250,6 +328,46 @@ impl Pathfinder {
     }
   }
 
+  /// Whether the graph has been calculated or restored.
+  pub fn is_calculated(&self) -> bool {
+    self.graph.node_count() > 0
+  }
+
+  /// Whether the graph is yet to be calculated or restored.
+  pub fn is_empty(&self) -> bool {
+    !self.is_calculated()
+  }
+
+  /// Whether the graph has a node with the same name and kind as `node`.
+  pub fn contains<T>(&self, node: &Node<T>) -> bool {
+    self.graph.node_weights().any(|n| node.name_and_kind_eq(n))
+  }
+
+  /// The nodes connected to `node`, with the points where they meet it.
+  pub fn connections<T>(&self, node: &Node<T>) -> Vec<(&Node<Line>, Vec2)> {
+    let Some((index, _)) = self
+      .graph
+      .node_references()
+      .find(|(_, n)| node.name_and_kind_eq(*n))
+    else {
+      return Vec::new();
+    };
+
+    self
+      .graph
+      .edges(index)
+      .map(|edge| {
+        let other = if edge.source() == index {
+          edge.target()
+        } else {
+          edge.source()
+        };
+
+        (&self.graph[other], *edge.weight())
+      })
+      .collect()
+  }
+
   pub fn calculate(&mut self, mut segments: Vec<Object>) {
     let mut graph = WaypointGraph::new_undirected();
     if segments.is_empty() || segments.len() < 2 {
@@ -287,12 +405,52 @@ impl Pathfinder {
 
           graph.add_edge(current_node, gate_node, intersection);
         }
+
+        for helipad in terminal.helipads.iter() {
+          let helipad_node = graph.add_node(helipad.clone().into());
+          let intersection = closest_point_on_line(
+            helipad.pos,
+            terminal.apron.0,
+            terminal.apron.1,
+          );
+
+          graph.add_edge(current_node, helipad_node, intersection);
+        }
       }
     }
 
     self.graph = graph;
   }
 
+  /// How many runways, other than the one we are going to, the legs of
+  /// `path` cross when taxiing it from `pos`.
+  pub fn runway_crossings<T>(
+    &self,
+    path: &[Node<Vec2>],
+    pos: Vec2,
+    to: &Node<T>,
+  ) -> usize {
+    let runways: Vec<Line> = self
+      .graph
+      .node_weights()
+      .filter(|n| n.kind == NodeKind::Runway && !to.name_and_kind_eq(*n))
+      .map(|n| n.value)
+      .collect();
+
+    let mut from = pos;
+    path
+      .iter()
+      .map(|wp| {
+        let leg = Line::new(from, wp.value);
+        from = wp.value;
+        runways
+          .iter()
+          .filter(|r| find_line_intersection(leg, **r).is_some())
+          .count()
+      })
+      .sum()
+  }
+
   pub fn path_to(
     &self,
     from: WaypointString,
@@ -391,15 +549,16 @@ impl Pathfinder {
             // If our waypoint is not a gate and we are not heading towards it,
             // don't use this path.
             //
-            // Inverse: If this is a gate, ignore the heading check.
-            if first.kind != NodeKind::Gate
+            // Inverse: If this is a gate or helipad, ignore the heading check.
+            if !matches!(first.kind, NodeKind::Gate | NodeKind::Helipad)
               && delta_angle(heading, angle).abs() >= 175.0
             {
               return false;
             }
 
             // If the waypoint is a runway and we haven't instructed to go to
-            // it, don't use this path.
+            // it, don't use this path. Helipads aren't runways, so rotorcraft
+            // can reach them without one.
             if wp.kind == NodeKind::Runway && !to.name_and_kind_eq(wp) {
               return false;
             }
@@ -422,7 +581,10 @@ impl Pathfinder {
       //     .partial_cmp(&total_distance(b, pos))
       //     .unwrap()
       // });
-      paths.sort_by_key(|p| p.path.len());
+      paths.sort_by_cached_key(|p| {
+        p.path.len()
+          + self.runway_crossings(&p.path, pos, &to) * RUNWAY_CROSSING_COST
+      });
 
       // for path in paths.iter() {
       //   println!(
@@ -648,6 +810,7 @@ mod tests {
         pos: Vec2::new(5.0, 0.0),
         heading: 360.0,
         length: 500.0,
+        ..Default::default()
       };
 
       segments.push(Object::Taxiway(taxiway_a));
@@ -700,6 +863,7 @@ mod tests {
         pos: Vec2::new(5.0, 0.0),
         heading: 360.0,
         length: 500.0,
+        ..Default::default()
       };
 
       segments.push(Object::Taxiway(taxiway_a));
@@ -735,5 +899,123 @@ mod tests {
         // assert_eq!(path.path[0].value, Vec2::new(5.0, 0.0));
       }
     }
+
+    #[test]
+    fn serialized_graph_round_trips() {
+      let mut pathfinder = Pathfinder::new();
+      pathfinder.calculate(vec![
+        Object::Taxiway(Taxiway::new(
+          Intern::from_ref("A"),
+          Vec2::new(0.0, 0.0),
+          Vec2::new(10.0, 0.0),
+        )),
+        Object::Taxiway(Taxiway::new(
+          Intern::from_ref("B"),
+          Vec2::new(5.0, -5.0),
+          Vec2::new(5.0, 5.0),
+        )),
+        Object::Taxiway(Taxiway::new(
+          Intern::from_ref("C"),
+          Vec2::new(0.0, 4.0),
+          Vec2::new(10.0, 4.0),
+        )),
+      ]);
+
+      let json = serde_json::to_string(&pathfinder).unwrap();
+      let restored: Pathfinder = serde_json::from_str(&json).unwrap();
+      assert_eq!(restored.graph.node_count(), pathfinder.graph.node_count());
+      assert_eq!(restored.graph.edge_count(), pathfinder.graph.edge_count());
+
+      let path_to = |pathfinder: &Pathfinder| {
+        pathfinder
+          .path_to(
+            Node::new(
+              Intern::from_ref("A"),
+              NodeKind::Taxiway,
+              NodeBehavior::GoTo,
+              (),
+            ),
+            Node::new(
+              Intern::from_ref("C"),
+              NodeKind::Taxiway,
+              NodeBehavior::GoTo,
+              (),
+            ),
+            Vec2::new(2.0, 0.0),
+            90.0,
+          )
+          .unwrap()
+      };
+      let expected = path_to(&pathfinder);
+      let actual = path_to(&restored);
+      assert_eq!(actual.path, expected.path);
+      assert_eq!(actual.final_pos, expected.final_pos);
+      assert_eq!(actual.final_heading, expected.final_heading);
+
+      // A graph from a different version is recalculated instead.
+      let stale = json.replace(
+        &format!("\"version\":{PATHFINDER_GRAPH_VERSION}"),
+        "\"version\":0",
+      );
+      let stale: Pathfinder = serde_json::from_str(&stale).unwrap();
+      assert!(!stale.is_calculated());
+    }
+
+    #[test]
+    fn routes_avoid_crossing_runways() {
+      let taxiway = |id: &str, a: Vec2, b: Vec2| {
+        Object::Taxiway(Taxiway::new(Intern::from_ref(id), a, b))
+      };
+      let node = |id: &str| {
+        Node::new(
+          Intern::from_ref(id),
+          NodeKind::Taxiway,
+          NodeBehavior::GoTo,
+          (),
+        )
+      };
+
+      // A runs straight across the runway to C, and B and D go around its
+      // north end.
+      let mut segments = vec![
+        taxiway("A", Vec2::new(0.0, 0.0), Vec2::new(2000.0, 0.0)),
+        taxiway("B", Vec2::new(200.0, -100.0), Vec2::new(200.0, 1000.0)),
+        taxiway("C", Vec2::new(1500.0, -100.0), Vec2::new(1500.0, 1200.0)),
+        taxiway("D", Vec2::new(0.0, 900.0), Vec2::new(2000.0, 900.0)),
+        Object::Runway(Runway {
+          id: Intern::from_ref("36"),
+          pos: Vec2::new(1000.0, 0.0),
+          heading: 360.0,
+          length: 1000.0,
+          ..Default::default()
+        }),
+      ];
+      let mut pathfinder = Pathfinder::new();
+      pathfinder.calculate(segments.clone());
+
+      let path = pathfinder
+        .path_to(node("A"), node("C"), Vec2::new(0.0, 0.0), 90.0)
+        .unwrap();
+      let names: Vec<&str> =
+        path.path.iter().map(|n| n.name.as_str()).collect();
+      assert_eq!(names, vec!["B", "D", "C"]);
+      assert_eq!(
+        pathfinder.runway_crossings(&path.path, Vec2::ZERO, &node("C")),
+        0
+      );
+
+      // Without a way around, we still cross.
+      segments
+        .retain(|s| !matches!(s, Object::Taxiway(t) if t.id.as_str() == "D"));
+      pathfinder.calculate(segments);
+      let path = pathfinder
+        .path_to(node("A"), node("C"), Vec2::new(0.0, 0.0), 90.0)
+        .unwrap();
+      assert_eq!(path.path.len(), 1);
+      assert_eq!(
+        pathfinder.runway_crossings(&path.path, Vec2::ZERO, &node("C")),
+        1
+      );
+    }
   }
 }
@@ -2,7 +2,9 @@ use glam::Vec2;
 
 use engine::{
   add_degrees,
-  entities::airport::{Airport, Gate, Runway, Taxiway, Terminal},
+  entities::airport::{
+    Airport, Gate, Helipad, Procedure, Runway, Taxiway, Terminal,
+  },
   find_projected_intersection, inverse_degrees, move_point, Line, CLOCKWISE,
   COUNTERCLOCKWISE,
 };
@@ -16,6 +18,7 @@ pub fn setup(airport: &mut Airport) {
     pos: airport.center + Vec2::new(1000.0, 0.0),
     heading: 135.0,
     length: 7000.0,
+    ..Default::default()
   };
 
   let runway_22 = Runway {
@@ -23,6 +26,7 @@ pub fn setup(airport: &mut Airport) {
     pos: airport.center + Vec2::new(-1000.0, 0.0),
     heading: 225.0,
     length: 7000.0,
+    ..Default::default()
   };
 
   let taxiway_a = Taxiway {
@@ -149,6 +153,7 @@ pub fn setup(airport: &mut Airport) {
     c: terminal_a_c,
     d: terminal_a_d,
     gates: Vec::new(),
+    helipads: Vec::new(),
     apron: Line::new(
       terminal_a_a.lerp(terminal_a_b, 0.5),
       terminal_a_c.lerp(terminal_a_d, 0.5),
@@ -159,7 +164,7 @@ pub fn setup(airport: &mut Airport) {
   let total_gates = 6;
   for i in 1..=total_gates {
     let gate = Gate {
-      id: Intern::from(format!("A{}", i)),
+      id: Intern::from(i.to_string()),
       pos: move_point(
         terminal_a
           .apron
@@ -169,12 +174,13 @@ pub fn setup(airport: &mut Airport) {
         terminal_a.a.distance(terminal_a.b) * 0.35,
       ),
       heading: inverse_degrees(runway_22.heading),
+      terminal: None,
     };
     terminal_a.gates.push(gate);
   }
   for i in 1..=total_gates {
     let gate = Gate {
-      id: Intern::from(format!("A{}", i + total_gates)),
+      id: Intern::from((i + total_gates).to_string()),
       pos: move_point(
         terminal_a
           .apron
@@ -184,6 +190,7 @@ pub fn setup(airport: &mut Airport) {
         terminal_a.a.distance(terminal_a.b) * 0.35,
       ),
       heading: runway_22.heading,
+      terminal: None,
     };
     terminal_a.gates.push(gate);
   }
@@ -208,6 +215,7 @@ pub fn setup(airport: &mut Airport) {
     c: terminal_b_c,
     d: terminal_b_d,
     gates: Vec::new(),
+    helipads: Vec::new(),
     apron: Line::new(
       terminal_b_a.lerp(terminal_b_b, 0.5),
       terminal_b_c.lerp(terminal_b_d, 0.5),
@@ -218,7 +226,7 @@ pub fn setup(airport: &mut Airport) {
   let total_gates = 6;
   for i in 1..=total_gates {
     let gate = Gate {
-      id: Intern::from(format!("B{}", i)),
+      id: Intern::from(i.to_string()),
       pos: move_point(
         terminal_b
           .apron
@@ -228,12 +236,13 @@ pub fn setup(airport: &mut Airport) {
         terminal_b.a.distance(terminal_b.b) * 0.35,
       ),
       heading: inverse_degrees(runway_13.heading),
+      terminal: None,
     };
     terminal_b.gates.push(gate);
   }
   for i in 1..=total_gates {
     let gate = Gate {
-      id: Intern::from(format!("B{}", i + total_gates)),
+      id: Intern::from((i + total_gates).to_string()),
       pos: move_point(
         terminal_b
           .apron
@@ -243,10 +252,21 @@ pub fn setup(airport: &mut Airport) {
         terminal_b.a.distance(terminal_b.b) * 0.35,
       ),
       heading: runway_13.heading,
+      terminal: None,
     };
     terminal_b.gates.push(gate);
   }
 
+  // Past the last gates, at the far end of the apron.
+  terminal_b.helipads.push(Helipad {
+    id: Intern::from_ref("H1"),
+    pos: move_point(
+      terminal_b.apron.1,
+      runway_13.heading,
+      terminal_b.a.distance(terminal_b.b) * 0.35,
+    ),
+  });
+
   airport.add_runway(runway_13);
   airport.add_runway(runway_22);
 
@@ -265,6 +285,13 @@ pub fn setup(airport: &mut Airport) {
   airport.add_taxiway(taxiway_f3);
   airport.add_taxiway(taxiway_f4);
 
-  airport.terminals.push(terminal_a);
-  airport.terminals.push(terminal_b);
+  airport.add_terminal(terminal_a);
+  airport.add_terminal(terminal_b);
+
+  // The departure south to Los Angeles. We only know airspaces and airports
+  // as fixes, so it flies to the airspace instead of its published fixes.
+  airport.procedures.push(Procedure {
+    id: Intern::from_ref("PORTE3"),
+    fixes: vec![Intern::from_ref("KLAX")],
+  });
 }
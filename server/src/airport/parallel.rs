@@ -27,6 +27,7 @@ pub fn setup(airport: &mut Airport) {
     pos: airport.center + Vec2::Y * RUNWAY_SPACING / 2.0,
     heading: 270.0,
     length: 7000.0,
+    ..Default::default()
   };
 
   let taxiway_b = Taxiway {
@@ -88,6 +89,7 @@ pub fn setup(airport: &mut Airport) {
     pos: airport.center + Vec2::Y * -(RUNWAY_SPACING / 2.0),
     heading: 270.0,
     length: 7000.0,
+    ..Default::default()
   };
 
   let taxiway_c = Taxiway {
@@ -152,6 +154,7 @@ pub fn setup(airport: &mut Airport) {
     d: move_point(taxiway_a2.b, DOWN, 750.0),
     apron: Line::new(taxiway_a2.b, taxiway_a3.b),
     gates: Vec::new(),
+    helipads: Vec::new(),
   };
 
   let mut terminal_b = Terminal {
@@ -162,6 +165,7 @@ pub fn setup(airport: &mut Airport) {
     d: move_point(taxiway_d2.b, UP, 750.0),
     apron: Line::new(taxiway_d2.b, taxiway_d3.b),
     gates: Vec::new(),
+    helipads: Vec::new(),
   };
 
   const GATES_PER_TERMINAL: usize = 4;
@@ -169,7 +173,7 @@ pub fn setup(airport: &mut Airport) {
   // TODO: Shift the gates back over to where they're supposed to be
   for i in 1..=GATES_PER_TERMINAL {
     terminal_a.gates.push(Gate {
-      id: Intern::from(format!("{}{i}", terminal_a.id)),
+      id: Intern::from(i.to_string()),
       heading: DOWN,
       pos: move_point(
         terminal_a
@@ -178,10 +182,11 @@ pub fn setup(airport: &mut Airport) {
         UP,
         150.0,
       ),
+      terminal: None,
     });
 
     terminal_b.gates.push(Gate {
-      id: Intern::from(format!("{}{i}", terminal_b.id)),
+      id: Intern::from(i.to_string()),
       heading: UP,
       pos: move_point(
         terminal_b
@@ -190,6 +195,7 @@ pub fn setup(airport: &mut Airport) {
         DOWN,
         150.0,
       ),
+      terminal: None,
     });
   }
 
@@ -217,6 +223,6 @@ pub fn setup(airport: &mut Airport) {
 
   // MARK: Terminals.
 
-  airport.terminals.push(terminal_a);
-  airport.terminals.push(terminal_b);
+  airport.add_terminal(terminal_a);
+  airport.add_terminal(terminal_b);
 }
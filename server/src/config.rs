@@ -1,13 +1,82 @@
-use std::{net::SocketAddr, path::Path};
+use std::{net::SocketAddr, ops::RangeInclusive, path::Path};
 
-use engine::entities::airspace::Frequencies;
+use engine::{
+  airline::Airline,
+  entities::airspace::{Altitudes, Frequencies, Wind},
+};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The frequencies of the VHF airband, in MHz.
+pub const AIRBAND: RangeInclusive<f32> = 118.0..=136.975;
+
+/// Whether `name` is a four letter ICAO airport code, which airspaces are
+/// named by.
+pub fn is_icao_airport_code(name: &str) -> bool {
+  name.len() == 4 && name.chars().all(|c| c.is_ascii_uppercase())
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ConfigError {
+  #[error(
+    "{name} frequency {frequency} is outside of the airband (118.0 to 136.975)"
+  )]
+  FrequencyOutOfRange { name: &'static str, frequency: f32 },
+  #[error("no airspaces are listed in the world")]
+  NoAirspaces,
+  #[error("\"{0}\" is not an ICAO airport code")]
+  InvalidAirspace(String),
+  #[error("{0} is listed more than once")]
+  DuplicateAirspace(String),
+  #[error("{name} cannot be negative, but is {value}")]
+  Negative { name: &'static str, value: f32 },
+  #[error("the floor ({floor}) is not below the ceiling ({ceiling})")]
+  FloorAboveCeiling { floor: f32, ceiling: f32 },
+  #[error("\"{0}\" is not a three letter ICAO airline code")]
+  InvalidAirline(String),
+  #[error("airline {0} is listed more than once")]
+  DuplicateAirline(String),
+  #[error("the airlines are listed, but there are none")]
+  NoAirlines,
+  #[error("the server address {0} needs a port other than 0")]
+  NoPort(SocketAddr),
+  #[error("the tick rate must be at least 1 tick per second")]
+  NoTickRate,
+}
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
   pub frequencies: Option<Frequencies>,
+  /// The minimum safe altitude of the player's airspace, in feet.
+  pub min_safe_altitude: Option<f32>,
+  pub wind: Option<Wind>,
+  /// The tailwind limit for landing, in knots.
+  pub max_tailwind: Option<f32>,
+  /// The vertical limits of the player's airspace, in feet.
+  pub floor: Option<f32>,
+  pub ceiling: Option<f32>,
+  /// How far pilots can see, in nautical miles.
+  pub visibility: Option<f32>,
+  /// How long aircraft wait lined up on the runway before reminding ATC, in
+  /// seconds.
+  pub line_up_timeout: Option<f32>,
+  /// Whether aircraft taxi off the runway after reminding ATC.
+  pub vacate_after_line_up_timeout: Option<bool>,
+  /// The least time between successive departures from the same runway of
+  /// the player's airport, in seconds.
+  pub min_departure_interval: Option<f32>,
+  /// How much flying time of fuel aircraft keep in reserve before declaring
+  /// minimum fuel, in minutes.
+  pub fuel_reserve: Option<f32>,
+  /// How many degrees magnetic north is east of true north, negative when
+  /// it is to the west.
+  pub magnetic_variation: Option<f32>,
+  /// Whether to keep a score, for the scored game mode.
+  pub scored: Option<bool>,
   pub world: Option<WorldConfig>,
   pub server: Option<ServerConfig>,
+  /// The airlines to generate callsigns for, in place of the defaults.
+  pub airlines: Option<Vec<Airline>>,
 }
 
 impl Config {
@@ -25,14 +94,270 @@ impl Config {
       Err(err) => Err(format!("Failed to read config file: {}", err)),
     }
   }
+
+  /// Checks the values that parse but make no sense, returning every problem
+  /// found.
+  pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+    let mut errors = Vec::new();
+
+    if let Some(frequencies) = &self.frequencies {
+      for (name, frequency) in [
+        ("approach", frequencies.approach),
+        ("departure", frequencies.departure),
+        ("tower", frequencies.tower),
+        ("ground", frequencies.ground),
+        ("center", frequencies.center),
+      ] {
+        if !AIRBAND.contains(&frequency) {
+          errors.push(ConfigError::FrequencyOutOfRange { name, frequency });
+        }
+      }
+    }
+
+    for (name, value) in [
+      ("min_safe_altitude", self.min_safe_altitude),
+      ("max_tailwind", self.max_tailwind),
+      ("wind speed", self.wind.map(|w| w.speed)),
+      ("floor", self.floor),
+      ("ceiling", self.ceiling),
+      ("visibility", self.visibility),
+      ("line_up_timeout", self.line_up_timeout),
+      ("min_departure_interval", self.min_departure_interval),
+      ("fuel_reserve", self.fuel_reserve),
+    ] {
+      if let Some(value) = value.filter(|v| *v < 0.0) {
+        errors.push(ConfigError::Negative { name, value });
+      }
+    }
+
+    if let (Some(floor), Some(ceiling)) = (self.floor, self.ceiling) {
+      if floor >= ceiling {
+        errors.push(ConfigError::FloorAboveCeiling { floor, ceiling });
+      }
+    }
+
+    if let Some(world) = &self.world {
+      let names = world.airspace_names();
+      if names.is_empty() {
+        errors.push(ConfigError::NoAirspaces);
+      }
+
+      for (i, name) in names.iter().enumerate() {
+        if !is_icao_airport_code(name) {
+          errors.push(ConfigError::InvalidAirspace(name.clone()));
+        } else if names[..i].contains(name) {
+          errors.push(ConfigError::DuplicateAirspace(name.clone()));
+        }
+      }
+
+      if world.tick_rate == Some(0) {
+        errors.push(ConfigError::NoTickRate);
+      }
+    }
+
+    if let Some(airlines) = &self.airlines {
+      if airlines.is_empty() {
+        errors.push(ConfigError::NoAirlines);
+      }
+
+      for (i, airline) in airlines.iter().enumerate() {
+        let icao = &airline.icao;
+        if icao.len() != 3 || !icao.chars().all(|c| c.is_ascii_uppercase()) {
+          errors.push(ConfigError::InvalidAirline(icao.clone()));
+        } else if airlines[..i].iter().any(|a| a.icao == *icao) {
+          errors.push(ConfigError::DuplicateAirline(icao.clone()));
+        }
+      }
+    }
+
+    if let Some(address) = self.server.and_then(|s| s.address) {
+      if address.port() == 0 {
+        errors.push(ConfigError::NoPort(address));
+      }
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+/// The airspaces generated around the player's airspace when none are
+/// configured.
+pub const DEFAULT_AIRSPACE_NAMES: [&str; 16] = [
+  "KLAX", "KPHL", "KJFK", "KMGM", "KCLT", "KDFW", "KATL", "KMCO", "EGLL",
+  "EGLC", "EGNV", "EGNT", "EGGP", "EGCC", "EGKK", "EGHI",
+];
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct WorldConfig {
   pub seed: Option<u64>,
+  /// The ICAO codes of the airspaces to generate in the world.
+  pub airspaces: Option<Vec<String>>,
+  /// The altitudes that flights are routed at, in place of the defaults.
+  pub altitudes: Option<Altitudes>,
+  /// How many times a second the game ticks.
+  pub tick_rate: Option<usize>,
+}
+
+impl WorldConfig {
+  pub fn airspace_names(&self) -> Vec<String> {
+    self.airspaces.clone().unwrap_or_else(|| {
+      DEFAULT_AIRSPACE_NAMES
+        .iter()
+        .map(|n| n.to_string())
+        .collect()
+    })
+  }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub struct ServerConfig {
   pub address: Option<SocketAddr>,
 }
+
+#[cfg(test)]
+mod test {
+  use engine::{airline::Airlines, entities::aircraft::AircraftKind};
+  use turborand::{rng::Rng, SeededCore};
+
+  use super::*;
+
+  #[test]
+  fn missing_airspaces_are_reported() {
+    let config = Config {
+      world: Some(WorldConfig {
+        airspaces: Some(vec![]),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    assert_eq!(config.validate(), Err(vec![ConfigError::NoAirspaces]));
+  }
+
+  #[test]
+  fn invalid_airspaces_are_reported() {
+    let config = Config {
+      world: Some(WorldConfig {
+        airspaces: Some(
+          ["KSFO", "ksfo", "K1A5", "EGLLL", "KSFO"]
+            .map(String::from)
+            .to_vec(),
+        ),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      config.validate(),
+      Err(vec![
+        ConfigError::InvalidAirspace("ksfo".to_string()),
+        ConfigError::InvalidAirspace("K1A5".to_string()),
+        ConfigError::InvalidAirspace("EGLLL".to_string()),
+        ConfigError::DuplicateAirspace("KSFO".to_string()),
+      ])
+    );
+  }
+
+  #[test]
+  fn zero_tick_rate_is_reported() {
+    let config = Config {
+      world: Some(WorldConfig {
+        tick_rate: Some(0),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    assert_eq!(config.validate(), Err(vec![ConfigError::NoTickRate]));
+  }
+
+  #[test]
+  fn out_of_range_frequency_is_reported() {
+    let config = Config {
+      frequencies: Some(Frequencies {
+        tower: 300.0,
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      config.validate(),
+      Err(vec![ConfigError::FrequencyOutOfRange {
+        name: "tower",
+        frequency: 300.0
+      }])
+    );
+    assert_eq!(Config::default().validate(), Ok(()));
+  }
+
+  #[test]
+  fn custom_airlines() {
+    let config: Config = toml::from_str(
+      r#"
+      [[airlines]]
+      icao = "BAW"
+      telephony = "Speedbird"
+      fleet = ["a333", "b77l"]
+
+      [[airlines]]
+      icao = "EZY"
+      telephony = "Easy"
+      "#,
+    )
+    .unwrap();
+    assert_eq!(config.validate(), Ok(()));
+
+    let airlines = Airlines(config.airlines.unwrap());
+    let mut rng = Rng::with_seed(0);
+    for _ in 0..20 {
+      let callsign = airlines.random_callsign(&mut rng);
+      let telephony = airlines.telephony(&callsign);
+      let number = &callsign[3..];
+      match &callsign[..3] {
+        "BAW" => assert_eq!(telephony, format!("Speedbird {number}")),
+        "EZY" => assert_eq!(telephony, format!("Easy {number}")),
+        other => panic!("unexpected airline {other}"),
+      }
+    }
+    assert_eq!(
+      airlines.0[0].fleet,
+      vec![AircraftKind::A333, AircraftKind::B77L]
+    );
+  }
+
+  #[test]
+  fn empty_airlines_are_reported() {
+    let config = Config {
+      airlines: Some(Vec::new()),
+      ..Default::default()
+    };
+
+    assert_eq!(config.validate(), Err(vec![ConfigError::NoAirlines]));
+  }
+
+  #[test]
+  fn duplicate_airlines_are_reported() {
+    let config = Config {
+      airlines: Some(vec![
+        Airline::new("BAW", "Speedbird"),
+        Airline::new("baw", "Speedbird"),
+        Airline::new("BAW", "Shuttle"),
+      ]),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      config.validate(),
+      Err(vec![
+        ConfigError::InvalidAirline("baw".to_string()),
+        ConfigError::DuplicateAirline("BAW".to_string()),
+      ])
+    );
+  }
+}
@@ -1,13 +1,17 @@
 use axum::{
-  extract::{Path, State},
-  http,
+  extract::{Path, Query, State},
+  http, Json,
 };
 use internment::Intern;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot::error::RecvError;
 
 use crate::{
   http::shared::AppState,
   job::JobReq,
-  runner::{ResKind, TinyReqKind},
+  runner::{
+    ArgReqKind, FlightPlanError, ResKind, SpawnError, SpawnRequest, TinyReqKind,
+  },
 };
 
 pub async fn get_aircraft(
@@ -47,3 +51,126 @@ pub async fn get_one_aircraft(
     Err(http::StatusCode::INTERNAL_SERVER_ERROR)
   }
 }
+
+pub async fn post_spawn_aircraft(
+  State(mut state): State<AppState>,
+  Json(request): Json<SpawnRequest>,
+) -> Result<String, http::StatusCode> {
+  let res =
+    JobReq::send(ArgReqKind::SpawnAircraft(request), &mut state.big_sender)
+      .recv()
+      .await;
+  match res {
+    Ok(ResKind::SpawnAircraft(Ok(id))) => {
+      serde_json::to_string(&id).map_err(|_| http::StatusCode::BAD_REQUEST)
+    }
+    Ok(ResKind::SpawnAircraft(Err(
+      SpawnError::CallsignInUse(_) | SpawnError::NoFreeGate,
+    ))) => Err(http::StatusCode::CONFLICT),
+    Ok(ResKind::SpawnAircraft(Err(_))) => Err(http::StatusCode::BAD_REQUEST),
+    _ => Err(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StripsQuery {
+  frequency: f32,
+}
+
+pub async fn get_strips(
+  State(mut state): State<AppState>,
+  Query(query): Query<StripsQuery>,
+) -> Result<String, http::StatusCode> {
+  let res =
+    JobReq::send(TinyReqKind::Strips(query.frequency), &mut state.tiny_sender)
+      .recv()
+      .await;
+  if let Ok(ResKind::Strips(strips)) = res {
+    if let Ok(string) = serde_json::to_string(&strips) {
+      Ok(string)
+    } else {
+      Err(http::StatusCode::BAD_REQUEST)
+    }
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreezeQuery {
+  frozen: bool,
+}
+
+pub async fn post_freeze_aircraft(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+  Query(query): Query<FreezeQuery>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::FreezeAircraft(Intern::from(id), query.frozen),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  match res {
+    Ok(ResKind::OneAircraft(Some(aircraft))) => {
+      serde_json::to_string(&aircraft)
+        .map_err(|_| http::StatusCode::BAD_REQUEST)
+    }
+    Ok(ResKind::OneAircraft(None)) => Err(http::StatusCode::NOT_FOUND),
+    _ => Err(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+fn flight_plan_response(
+  res: Result<ResKind, RecvError>,
+) -> Result<String, http::StatusCode> {
+  match res {
+    Ok(ResKind::FlightPlan(Ok(waypoints))) => serde_json::to_string(&waypoints)
+      .map_err(|_| http::StatusCode::BAD_REQUEST),
+    Ok(ResKind::FlightPlan(Err(FlightPlanError::UnknownAircraft))) => {
+      Err(http::StatusCode::NOT_FOUND)
+    }
+    Ok(ResKind::FlightPlan(Err(_))) => Err(http::StatusCode::BAD_REQUEST),
+    _ => Err(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+pub async fn get_flight_plan(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::FlightPlan(Intern::from(id)),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  flight_plan_response(res)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlightPlanAmendment {
+  waypoints: Vec<Intern<String>>,
+  /// Replace the whole route instead of flying these waypoints first.
+  #[serde(default)]
+  replace: bool,
+}
+
+pub async fn post_flight_plan(
+  State(mut state): State<AppState>,
+  Path(id): Path<String>,
+  Json(amendment): Json<FlightPlanAmendment>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    ArgReqKind::AmendFlightPlan {
+      id: Intern::from(id),
+      waypoints: amendment.waypoints,
+      replace: amendment.replace,
+    },
+    &mut state.big_sender,
+  )
+  .recv()
+  .await;
+  flight_plan_response(res)
+}
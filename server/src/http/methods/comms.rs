@@ -4,10 +4,11 @@ use async_openai::error::OpenAIError;
 use axum::{
   body::Bytes,
   extract::{Query, State},
+  http, Json,
 };
 use engine::{
+  clock::Clock,
   command::{CommandReply, CommandWithFreq},
-  duration_now,
 };
 use internment::Intern;
 use reqwest::{header, multipart::Part, Client};
@@ -19,16 +20,18 @@ use crate::{
     AudioResponse,
   },
   job::JobReq,
-  prompter::Prompter,
-  runner::{ArgReqKind, ResKind, TinyReqKind},
+  prompter::{Error as PrompterError, ParseError, Prompter},
+  runner::{ArgReqKind, CommandError, ResKind, TinyReqKind},
   CLI,
 };
 
+/// Turns a message from ATC into a command, or explains why it was
+/// rejected.
 async fn complete_atc_request(
   tiny_sender: &mut GetSender,
   message: String,
   frequency: f32,
-) -> Option<CommandWithFreq> {
+) -> (Option<CommandWithFreq>, Vec<ParseError>) {
   let split = Prompter::split_request(message).await;
 
   // Split the request into the callsign and the rest of the message.
@@ -49,28 +52,35 @@ async fn complete_atc_request(
               "Inactive aircraft \"{}\" received command",
               aircraft.id
             );
-            return None;
+            return (None, Vec::new());
           }
 
           // Parse the command from the message.
           let command = Prompter::parse_into_command(split, &aircraft).await;
           match command {
             // Return the command.
-            Ok(command) => Some(CommandWithFreq::new(
-              aircraft.id.to_string(),
-              frequency,
-              command.reply,
-              command.tasks,
-            )),
+            Ok(command) => (
+              Some(CommandWithFreq::new(
+                aircraft.id.to_string(),
+                frequency,
+                command.reply,
+                command.tasks,
+              )),
+              Vec::new(),
+            ),
+            Err(PrompterError::NoTasks(errors)) => {
+              tracing::error!("Rejected the tasks of command");
+              (None, errors)
+            }
             Err(err) => {
               tracing::error!("Unable to parse command: {}", err);
-              None
+              (None, Vec::new())
             }
           }
         }
         _ => {
           tracing::error!("Unable to find aircraft for command");
-          None
+          (None, Vec::new())
         }
       }
     }
@@ -82,11 +92,12 @@ async fn complete_atc_request(
 pub struct CommsFrequencyQuery {
   frequency: f32,
 }
+/// Replies with why the command was rejected, if it was.
 pub async fn comms_text(
   State(mut state): State<AppState>,
   Query(query): Query<CommsFrequencyQuery>,
   text: String,
-) {
+) -> Json<Vec<ParseError>> {
   tracing::info!("Received comms text request: {} chars", text.len());
 
   let _ = JobReq::send(
@@ -101,7 +112,7 @@ pub async fn comms_text(
   .recv()
   .await;
 
-  let command =
+  let (command, errors) =
     complete_atc_request(&mut state.tiny_sender, text.clone(), query.frequency)
       .await;
   if let Some(command) = command {
@@ -114,11 +125,46 @@ pub async fn comms_text(
   }
 
   tracing::info!("Replied to text request");
+
+  Json(errors)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchCommandResult {
+  id: String,
+  accepted: bool,
+  error: Option<CommandError>,
+}
+
+pub async fn comms_batch(
+  State(mut state): State<AppState>,
+  Json(commands): Json<Vec<CommandWithFreq>>,
+) -> Result<String, http::StatusCode> {
+  let ids: Vec<String> = commands.iter().map(|c| c.id.clone()).collect();
+  let res =
+    JobReq::send(ArgReqKind::CommandBatch(commands), &mut state.big_sender)
+      .recv()
+      .await;
+
+  if let Ok(ResKind::CommandBatch(results)) = res {
+    let results: Vec<BatchCommandResult> = ids
+      .into_iter()
+      .zip(results)
+      .map(|(id, result)| BatchCommandResult {
+        id,
+        accepted: result.is_ok(),
+        error: result.err(),
+      })
+      .collect();
+    serde_json::to_string(&results).map_err(|_| http::StatusCode::BAD_REQUEST)
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
 }
 
-fn write_wav_data(bytes: &Bytes) {
+fn write_wav_data(bytes: &Bytes, clock: &dyn Clock) {
   if let Some(ref audio_path) = CLI.audio_path {
-    let now = duration_now();
+    let now = clock.now();
     let mut audio_path = audio_path.join(format!("{now:?}"));
     audio_path.set_extension("wav");
 
@@ -132,8 +178,9 @@ fn write_wav_data(bytes: &Bytes) {
 async fn transcribe_voice(
   bytes: Bytes,
   openai_api_key: Arc<str>,
+  clock: &dyn Clock,
 ) -> Result<String, OpenAIError> {
-  write_wav_data(&bytes);
+  write_wav_data(&bytes, clock);
 
   let client = Client::new();
   let form = reqwest::multipart::Form::new()
@@ -159,9 +206,9 @@ async fn transcribe_voice(
   Ok(text)
 }
 
-fn write_json_data(command: &CommandWithFreq) {
+fn write_json_data(command: &CommandWithFreq, clock: &dyn Clock) {
   if let Some(ref audio_path) = CLI.audio_path {
-    let now = duration_now();
+    let now = clock.now();
     let mut audio_path = audio_path.join(format!("{now:?}"));
     audio_path.set_extension("json");
 
@@ -192,7 +239,9 @@ pub async fn comms_voice(
 ) {
   tracing::info!("Received comms voice request: {} bytes", bytes.len());
 
-  match transcribe_voice(bytes, state.openai_api_key.clone()).await {
+  match transcribe_voice(bytes, state.openai_api_key.clone(), &*state.clock)
+    .await
+  {
     Ok(text) => {
       tracing::info!("Transcribed request: {} chars", text.len());
       if let Ok(reply) = serde_json::from_str::<AudioResponse>(&text) {
@@ -210,14 +259,14 @@ pub async fn comms_voice(
         .recv()
         .await;
 
-        if let Some(command) = complete_atc_request(
+        if let (Some(command), _) = complete_atc_request(
           &mut state.tiny_sender,
           reply.text.clone(),
           query.frequency,
         )
         .await
         {
-          write_json_data(&command);
+          write_json_data(&command, &*state.clock);
 
           let _ = JobReq::send(
             ArgReqKind::CommandReply(command.clone()),
@@ -233,3 +282,26 @@ pub async fn comms_voice(
 
   tracing::info!("Replied to voice request");
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsQuery {
+  frequency: f32,
+}
+
+/// Takes the callouts waiting to be spoken on a frequency, oldest first.
+pub async fn post_drain_tts(
+  State(mut state): State<AppState>,
+  Query(query): Query<TtsQuery>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(
+    TinyReqKind::DrainTts(query.frequency),
+    &mut state.tiny_sender,
+  )
+  .recv()
+  .await;
+  if let Ok(ResKind::Tts(messages)) = res {
+    serde_json::to_string(&messages).map_err(|_| http::StatusCode::BAD_REQUEST)
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
@@ -1,11 +1,11 @@
-use std::{ops::Add, time::Duration};
+use std::time::Duration;
 
 use axum::{
   extract::{Path, State},
   http, Form,
 };
-use engine::{duration_now, entities::flight::FlightKind};
-use serde::Deserialize;
+use engine::entities::flight::{Flight, FlightKind};
+use serde::{Deserialize, Serialize};
 
 use crate::{
   http::shared::AppState,
@@ -13,16 +13,24 @@ use crate::{
   runner::{ResKind, TinyReqKind},
 };
 
+/// The flights, along with the game time that they are scheduled by, since
+/// `spawn_at` is in game time rather than the time of day.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlightsResponse {
+  pub flights: Vec<Flight>,
+  pub now: Duration,
+}
+
 pub async fn get_flights(
   State(mut state): State<AppState>,
 ) -> Result<String, http::StatusCode> {
   let res = JobReq::send(TinyReqKind::Flights, &mut state.tiny_sender)
     .recv()
     .await;
-  if let Ok(ResKind::Flights(flights)) = res {
-    let mut sorted = flights;
-    sorted.sort_by_key(|f| f.spawn_at);
-    if let Ok(string) = serde_json::to_string(&sorted) {
+  if let Ok(ResKind::Flights { mut flights, now }) = res {
+    flights.sort_by_key(|f| f.spawn_at);
+    let response = FlightsResponse { flights, now };
+    if let Ok(string) = serde_json::to_string(&response) {
       Ok(string)
     } else {
       Err(http::StatusCode::BAD_REQUEST)
@@ -45,7 +53,7 @@ pub async fn create_flight(
   let res = JobReq::send(
     TinyReqKind::CreateFlight {
       kind: form.kind,
-      spawn_at: duration_now().add(Duration::from_secs(form.spawn_at)),
+      spawn_in: Duration::from_secs(form.spawn_at),
     },
     &mut state.tiny_sender,
   )
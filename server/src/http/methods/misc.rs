@@ -1,4 +1,8 @@
-use axum::{extract::State, http};
+use axum::{
+  extract::{Query, State},
+  http,
+};
+use serde::Deserialize;
 
 use crate::{
   http::shared::AppState,
@@ -20,6 +24,19 @@ pub async fn ping_pong(
   }
 }
 
+pub async fn get_health(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Health, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Health(health)) = res {
+    serde_json::to_string(&health).map_err(|_| http::StatusCode::BAD_REQUEST)
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
 pub async fn post_pause(
   State(mut state): State<AppState>,
 ) -> Result<(), http::StatusCode> {
@@ -32,3 +49,68 @@ pub async fn post_pause(
     Err(http::StatusCode::INTERNAL_SERVER_ERROR)
   }
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeScaleQuery {
+  scale: f32,
+}
+
+pub async fn post_time_scale(
+  State(mut state): State<AppState>,
+  Query(query): Query<TimeScaleQuery>,
+) -> Result<(), http::StatusCode> {
+  if !query.scale.is_finite() {
+    return Err(http::StatusCode::BAD_REQUEST);
+  }
+
+  let res =
+    JobReq::send(TinyReqKind::TimeScale(query.scale), &mut state.tiny_sender)
+      .recv()
+      .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
+
+#[cfg(debug_assertions)]
+pub async fn post_reload_assets(
+  State(mut state): State<AppState>,
+) -> Result<String, http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::ReloadAssets, &mut state.tiny_sender)
+    .recv()
+    .await;
+  match res {
+    Ok(ResKind::ReloadAssets(Ok(ids))) => {
+      serde_json::to_string(&ids).map_err(|_| http::StatusCode::BAD_REQUEST)
+    }
+    Ok(ResKind::ReloadAssets(Err(_))) => Err(http::StatusCode::BAD_REQUEST),
+    _ => Err(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+pub async fn post_save(
+  State(mut state): State<AppState>,
+) -> Result<(), http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Save, &mut state.tiny_sender)
+    .recv()
+    .await;
+  match res {
+    Ok(ResKind::Save(Ok(()))) => Ok(()),
+    _ => Err(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+pub async fn post_silent(
+  State(mut state): State<AppState>,
+) -> Result<(), http::StatusCode> {
+  let res = JobReq::send(TinyReqKind::Silent, &mut state.tiny_sender)
+    .recv()
+    .await;
+  if let Ok(ResKind::Any) = res {
+    Ok(())
+  } else {
+    Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+  }
+}
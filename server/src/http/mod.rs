@@ -8,16 +8,21 @@ use axum::{
   Router,
 };
 use methods::{
-  aircraft::{get_aircraft, get_one_aircraft},
-  comms::{comms_text, comms_voice},
+  aircraft::{
+    get_aircraft, get_flight_plan, get_one_aircraft, get_strips,
+    post_flight_plan, post_freeze_aircraft, post_spawn_aircraft,
+  },
+  comms::{comms_batch, comms_text, comms_voice, post_drain_tts},
   flights::{create_flight, delete_flight, get_flights},
-  misc::{ping_pong, post_pause},
+  misc::{
+    get_health, ping_pong, post_pause, post_save, post_silent, post_time_scale,
+  },
   state::{get_messages, get_points, get_world},
 };
 use serde::{Deserialize, Serialize};
 use shared::{AppState, GetSender, PostSender};
 
-use engine::engine::UICommand;
+use engine::{clock::Clock, engine::UICommand};
 use tower_http::cors::CorsLayer;
 
 pub async fn run(
@@ -25,30 +30,57 @@ pub async fn run(
   get_sender: GetSender,
   post_sender: PostSender,
   openai_api_key: Arc<str>,
+  clock: Arc<dyn Clock>,
 ) {
   let cors = CorsLayer::very_permissive();
+  let routes = Router::new()
+    .route("/", get(|| async { "Airwave API is active." }))
+    // Misc
+    .route("/pause", post(post_pause))
+    .route("/silent", post(post_silent))
+    .route("/timescale", post(post_time_scale))
+    .route("/save", post(post_save))
+    .route("/ping", get(ping_pong))
+    .route("/health", get(get_health))
+    // Comms
+    .route("/comms/text", post(comms_text))
+    .route("/comms/voice", post(comms_voice))
+    .route("/comms/batch", post(comms_batch))
+    .route("/comms/tts", post(post_drain_tts))
+    // Aircraft
+    .route(
+      "/game/aircraft",
+      get(get_aircraft).post(post_spawn_aircraft),
+    )
+    .route("/game/aircraft/:id", get(get_one_aircraft))
+    .route("/game/aircraft/:id/freeze", post(post_freeze_aircraft))
+    .route(
+      "/game/aircraft/:id/plan",
+      get(get_flight_plan).post(post_flight_plan),
+    )
+    .route("/strips", get(get_strips))
+    // Flights
+    .route("/game/flights", get(get_flights))
+    .route("/game/flight", post(create_flight))
+    .route("/game/flight/:id", delete(delete_flight))
+    // State
+    .route("/messages", get(get_messages))
+    .route("/world", get(get_world))
+    .route("/game/points", get(get_points));
+  // Dev
+  #[cfg(debug_assertions)]
+  let routes =
+    routes.route("/assets/reload", post(methods::misc::post_reload_assets));
+
   let app = Router::new().nest(
     "/api",
-    Router::new()
-      .route("/", get(|| async { "Airwave API is active." }))
-      // Misc
-      .route("/pause", post(post_pause))
-      .route("/ping", get(ping_pong))
-      // Comms
-      .route("/comms/text", post(comms_text))
-      .route("/comms/voice", post(comms_voice))
-      // Aircraft
-      .route("/game/aircraft", get(get_aircraft))
-      .route("/game/aircraft/:id", get(get_one_aircraft))
-      // Flights
-      .route("/game/flights", get(get_flights))
-      .route("/game/flight", post(create_flight))
-      .route("/game/flight/:id", delete(delete_flight))
-      // State
-      .route("/messages", get(get_messages))
-      .route("/world", get(get_world))
-      .route("/game/points", get(get_points))
-      .with_state(AppState::new(get_sender, post_sender, openai_api_key))
+    routes
+      .with_state(AppState::new(
+        get_sender,
+        post_sender,
+        openai_api_key,
+        clock,
+      ))
       .layer(cors),
   );
 
@@ -60,6 +92,7 @@ pub async fn run(
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[serde(tag = "type", content = "value")]
+#[allow(dead_code)]
 enum FrontendRequest {
   Voice { data: Vec<u8>, frequency: f32 },
   Text { text: String, frequency: f32 },
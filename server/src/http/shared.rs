@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use engine::clock::Clock;
 use tokio::sync::mpsc;
 
 use crate::{
@@ -15,6 +16,8 @@ pub struct AppState {
   pub tiny_sender: GetSender,
   pub big_sender: PostSender,
   pub openai_api_key: Arc<str>,
+  /// The runner's clock, which recordings are named by.
+  pub clock: Arc<dyn Clock>,
 }
 
 impl AppState {
@@ -22,11 +25,13 @@ impl AppState {
     get_sender: GetSender,
     post_sender: PostSender,
     openai_api_key: Arc<str>,
+    clock: Arc<dyn Clock>,
   ) -> Self {
     Self {
       tiny_sender: get_sender,
       big_sender: post_sender,
       openai_api_key,
+      clock,
     }
   }
 }
@@ -17,6 +17,8 @@ pub mod job;
 pub mod prompter;
 pub mod ring;
 pub mod runner;
+pub mod scenario;
+pub mod tts;
 
 pub static CLI: LazyLock<Cli> = LazyLock::new(Cli::parse);
 
@@ -37,4 +39,30 @@ pub struct Cli {
   /// The path to the config file.
   #[arg(short, long, default_value = None)]
   pub config_path: Option<PathBuf>,
+
+  /// A scenario file of scripted aircraft to spawn instead of random
+  /// traffic.
+  #[arg(long, default_value = None)]
+  pub scenario: Option<PathBuf>,
+
+  /// Resumes the game saved at this path, or at `assets/world.json` if none
+  /// is given, instead of starting a new one.
+  #[arg(long, num_args = 0..=1, default_missing_value = "assets/world.json")]
+  pub load: Option<PathBuf>,
+
+  /// Where to save the game, both when asked to and every minute of game
+  /// time. Without it, the game is saved to `assets/world.json` only when
+  /// asked to, or autosaved to the game given to `--load`.
+  #[arg(long, default_value = None)]
+  pub save: Option<PathBuf>,
+
+  /// A directory of airport files, each a JSON `Airport`, that replace the
+  /// built-in airports. In debug builds, they can be reloaded while running.
+  #[arg(long, default_value = None)]
+  pub assets: Option<PathBuf>,
+
+  /// Writes the player airport's taxi network to a GraphViz DOT file at this
+  /// path and exits.
+  #[arg(long, default_value = None)]
+  pub export_dot: Option<PathBuf>,
 }
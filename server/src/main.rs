@@ -11,13 +11,17 @@ use internment::Intern;
 use tokio::sync::mpsc;
 use turborand::{rng::Rng, SeededCore};
 
-use engine::entities::{airport::Airport, airspace::Airspace};
+use engine::{
+  airline::Airlines,
+  entities::{airport::Airport, airspace::Airspace, world::Score},
+};
 use server::{
   airport::new_v_pattern,
   config::Config,
   http,
   job::JobReq,
-  runner::{ArgReqKind, ResKind, Runner, TinyReqKind},
+  runner::{ArgReqKind, ResKind, Runner, TinyReqKind, DEFAULT_TICK_RATE},
+  scenario::Scenario,
   Cli, CLI, MANUAL_TOWER_AIRSPACE_RADIUS,
 };
 
@@ -35,6 +39,11 @@ async fn main() {
     seed,
     ref audio_path,
     ref config_path,
+    ref export_dot,
+    ref scenario,
+    ref assets,
+    ref load,
+    ref save,
   } = *CLI;
 
   if let Some(audio_path) = audio_path {
@@ -54,12 +63,26 @@ async fn main() {
 
   let config: Config = if fs::exists(&path).ok() == Some(true) {
     tracing::info!("Reading config at {}.", path.to_string_lossy());
-    Config::from_path(path).unwrap()
+    match Config::from_path(path) {
+      Ok(config) => config,
+      Err(e) => {
+        tracing::error!("{e}");
+        return;
+      }
+    }
   } else {
     tracing::info!("Using default config.");
     Config::default()
   };
 
+  if let Err(errors) = config.validate() {
+    tracing::error!("Found {} problem(s) in the config:", errors.len());
+    for error in errors {
+      tracing::error!("  - {error}");
+    }
+    return;
+  }
+
   let address = address
     .or_else(|| config.server.and_then(|s| s.address))
     .unwrap_or(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9001));
@@ -69,10 +92,10 @@ async fn main() {
   let (post_tx, post_rx) =
     mpsc::unbounded_channel::<JobReq<ArgReqKind, ResKind>>();
 
+  let world_config = config.world.unwrap_or_default();
   let seed = seed.unwrap_or(
-    config
-      .world
-      .and_then(|w| w.seed)
+    world_config
+      .seed
       .unwrap_or(SystemTime::now().elapsed().unwrap().as_secs()),
   );
 
@@ -80,12 +103,12 @@ async fn main() {
 
   let rng = Rng::with_seed(seed);
   let mut world_rng = Rng::with_seed(0);
-  let mut runner = Runner::new(
-    get_rx,
-    post_rx,
-    Some(PathBuf::from_str("assets/world.json").unwrap()),
-    rng,
-  );
+  let save_to = save
+    .clone()
+    .unwrap_or_else(|| PathBuf::from_str("assets/world.json").unwrap());
+  let mut runner = Runner::new(get_rx, post_rx, Some(save_to), rng)
+    .with_tick_rate(world_config.tick_rate.unwrap_or(DEFAULT_TICK_RATE));
+  runner.autosave = save.is_some();
 
   let mut player_airspace = Airspace {
     id: Intern::from_ref("KSFO"),
@@ -93,30 +116,109 @@ async fn main() {
     radius: MANUAL_TOWER_AIRSPACE_RADIUS,
     airports: vec![],
     frequencies: config.frequencies.unwrap_or_default(),
+    min_safe_altitude: config.min_safe_altitude,
+    wind: config.wind.unwrap_or_default(),
+    max_tailwind: config.max_tailwind,
+    floor: config.floor,
+    ceiling: config.ceiling,
+    visibility: config.visibility,
+    line_up_timeout: config.line_up_timeout,
+    vacate_after_line_up_timeout: config
+      .vacate_after_line_up_timeout
+      .unwrap_or(false),
+    fuel_reserve: config.fuel_reserve,
+    altitudes: world_config.altitudes.unwrap_or_default(),
   };
 
   let mut airport_ksfo = Airport {
     id: Intern::from_ref("KSFO"),
     center: player_airspace.pos,
+    min_departure_interval: config.min_departure_interval.unwrap_or(0.0),
     ..Default::default()
   };
 
   new_v_pattern::setup(&mut airport_ksfo);
 
   airport_ksfo.calculate_waypoints();
+
+  if let Some(export_dot) = export_dot {
+    match fs::write(export_dot, airport_ksfo.export_graph_dot()) {
+      Ok(()) => tracing::info!(
+        "Exported the taxi network to {}.",
+        export_dot.to_string_lossy()
+      ),
+      Err(e) => tracing::error!("Unable to export the taxi network: {e}"),
+    }
+    return;
+  }
+
   player_airspace.airports.push(airport_ksfo);
 
   runner.world.airspace = player_airspace;
+  if let Some(assets) = assets {
+    tracing::info!("Loading assets at {}.", assets.to_string_lossy());
+    runner.assets = Some(assets.clone());
+    if let Err(e) = runner.load_assets() {
+      tracing::error!("{e}");
+      return;
+    }
+  }
+  runner.world.magnetic_variation = config.magnetic_variation.unwrap_or(0.0);
+  if let Some(airlines) = config.airlines.clone() {
+    runner.world.airlines = Airlines(airlines);
+  }
+  if config.scored.unwrap_or(false) {
+    runner.game.points.score = Some(Score::default());
+  }
 
-  runner.generate_airspaces(&mut world_rng);
-  runner.fill_gates();
+  runner.generate_airspaces(&mut world_rng, &world_config.airspace_names());
 
-  //
+  if let Some(path) = load {
+    tracing::info!("Loading saved game at {}.", path.to_string_lossy());
+    match runner.resume(path) {
+      Ok(()) => {
+        if let Some(save) = save {
+          runner.save_to = Some(save.clone());
+        }
+      }
+      Err(e) => {
+        tracing::error!("{e}");
+        if let Some(save_to) = &runner.save_to {
+          tracing::warn!(
+            "Starting a new game instead, which is saved to {}.",
+            save_to.to_string_lossy()
+          );
+        }
+        runner.fill_gates();
+      }
+    }
+  } else if let Some(scenario) = scenario {
+    tracing::info!("Loading scenario at {}.", scenario.to_string_lossy());
+    match Scenario::from_path(scenario) {
+      Ok(scenario) => runner.scenario = Some(scenario),
+      Err(e) => {
+        tracing::error!("{e}");
+        return;
+      }
+    }
+  } else {
+    runner.fill_gates();
+  }
+  for warning in runner.world.validate(runner.game.aircraft()) {
+    tracing::warn!("{warning}");
+  }
 
+  runner.complete_quick_start();
+  let clock = runner.clock.clone();
   tracing::info!("Starting game loop...");
   tokio::task::spawn_blocking(move || runner.begin_loop());
 
-  let _ =
-    tokio::spawn(http::run(address, get_tx, post_tx, openai_api_key.into()))
-      .await;
+  let _ = tokio::spawn(http::run(
+    address,
+    get_tx,
+    post_tx,
+    openai_api_key.into(),
+    clock,
+  ))
+  .await;
 }
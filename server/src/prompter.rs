@@ -13,8 +13,9 @@ use serde_json::Value;
 use thiserror::Error;
 
 use engine::{
-  command::{Command, CommandReply, Tasks},
+  command::{Command, CommandReply, Task, Tasks},
   entities::aircraft::{Aircraft, AircraftState},
+  parse_spoken_altitude,
 };
 
 pub async fn send_chatgpt_request(
@@ -130,6 +131,109 @@ pub enum LoadPromptError {
   FS(String),
 }
 
+/// Why the tasks that a prompt returned, or one of them, were rejected.
+#[derive(Error, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum ParseError {
+  #[error("expected a list of tasks: {reason}")]
+  NotAList { reason: String },
+  #[error("item {index} is not a task")]
+  NotATask { index: usize },
+  #[error("item {index} is an unknown task \"{name}\"")]
+  UnknownTask { index: usize, name: String },
+  #[error("item {index} has a bad argument for \"{name}\": {reason}")]
+  BadArgument {
+    index: usize,
+    name: String,
+    reason: String,
+  },
+  #[error("unexpected input after the list of tasks: {text}")]
+  TrailingInput { text: String },
+}
+
+/// The tasks whose value is an altitude in feet.
+const ALTITUDE_TASKS: [&str; 4] =
+  ["altitude", "altitude-at-discretion", "climb", "descend"];
+
+/// Parses a list of tasks, explaining why each rejected one was. If any are
+/// rejected, none of them are kept, so that a command is never carried out
+/// only in part. Altitudes may be given as they were said, such as "flight
+/// level three seven zero".
+pub fn parse_tasks_verbose(s: &str) -> (Tasks, Vec<ParseError>) {
+  let mut tasks = Vec::new();
+  let mut errors = Vec::new();
+
+  let mut stream = serde_json::Deserializer::from_str(s).into_iter::<Value>();
+  let items = match stream.next() {
+    Some(Ok(Value::Array(items))) => items,
+    Some(Ok(value)) => {
+      errors.push(ParseError::NotAList {
+        reason: format!("found {value}"),
+      });
+      return (tasks, errors);
+    }
+    Some(Err(e)) => {
+      errors.push(ParseError::NotAList {
+        reason: e.to_string(),
+      });
+      return (tasks, errors);
+    }
+    None => {
+      errors.push(ParseError::NotAList {
+        reason: "found nothing".into(),
+      });
+      return (tasks, errors);
+    }
+  };
+  let rest = s[stream.byte_offset()..].trim();
+
+  for (index, mut item) in items.into_iter().enumerate() {
+    let Some(name) = item.get("type").and_then(Value::as_str) else {
+      errors.push(ParseError::NotATask { index });
+      continue;
+    };
+    let name = name.to_string();
+
+    if ALTITUDE_TASKS.contains(&name.as_str()) {
+      let spoken = item.get("value").and_then(Value::as_str);
+      if let Some(feet) = spoken.and_then(parse_spoken_altitude) {
+        item["value"] = feet.into();
+      }
+    }
+
+    match serde_json::from_value::<Task>(item) {
+      Ok(task) => match task.validate() {
+        Ok(()) => tasks.push(task),
+        Err(reason) => errors.push(ParseError::BadArgument {
+          index,
+          name,
+          reason,
+        }),
+      },
+      Err(e) if e.to_string().starts_with("unknown variant") => {
+        errors.push(ParseError::UnknownTask { index, name });
+      }
+      Err(e) => errors.push(ParseError::BadArgument {
+        index,
+        name,
+        reason: e.to_string(),
+      }),
+    }
+  }
+
+  if !rest.is_empty() {
+    errors.push(ParseError::TrailingInput {
+      text: rest.to_string(),
+    });
+  }
+
+  if !errors.is_empty() {
+    tasks.clear();
+  }
+
+  (tasks, errors)
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct CallsignAndRequest {
   pub callsign: String,
@@ -153,6 +257,8 @@ pub enum Error {
   OpenAI(#[from] OpenAIError),
   #[error("failed to complete prompt: {0}")]
   NoResult(String),
+  #[error("rejected tasks in prompt result: {0:?}")]
+  NoTasks(Vec<ParseError>),
 }
 
 impl Prompter {
@@ -195,6 +301,8 @@ impl Prompter {
     }
   }
 
+  /// Parses a request into a command, or explains why its tasks were
+  /// rejected.
   pub async fn parse_into_command(
     split: CallsignAndRequest,
     aircraft: &Aircraft,
@@ -221,14 +329,20 @@ impl Prompter {
     let result =
       send_chatgpt_request(prompt.clone(), split.request.clone()).await?;
     if let Some(result) = result {
-      let json: Tasks = serde_json::from_str(&result)
-        .map_err(|e| LoadPromptError::Deserialize(e, result))?;
+      let (tasks, errors) = parse_tasks_verbose(&result);
+      for error in errors.iter() {
+        tracing::warn!("rejected task ({}): {}", aircraft.id, error);
+      }
+      if !errors.is_empty() {
+        return Err(Error::NoTasks(errors));
+      }
+
       let command = Command {
         id: split.callsign.clone(),
         reply: CommandReply::WithCallsign {
           text: split.request,
         },
-        tasks: json.clone(),
+        tasks,
       };
 
       tracing::info!("prompt result ({}): {:?}", aircraft.id, command);
@@ -240,3 +354,195 @@ impl Prompter {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use engine::command::HoldDirection;
+  use internment::Intern;
+
+  use super::*;
+
+  fn messages(s: &str) -> (Tasks, Vec<String>) {
+    let (tasks, errors) = parse_tasks_verbose(s);
+    (tasks, errors.iter().map(|e| e.to_string()).collect())
+  }
+
+  #[test]
+  fn valid_tasks_have_no_errors() {
+    let (tasks, errors) = parse_tasks_verbose(
+      r#"[{"type":"heading","value":90},{"type":"altitude","value":4000}]"#,
+    );
+
+    assert_eq!(tasks, vec![Task::Heading(90.0), Task::Altitude(4000.0)]);
+    assert!(errors.is_empty());
+  }
+
+  #[test]
+  fn malformed_tasks_are_explained() {
+    assert_eq!(
+      messages(r#"[{"type":"altitude","value":"abcd"}]"#),
+      (
+        vec![],
+        vec![
+          "item 0 has a bad argument for \"altitude\": invalid type: string \"abcd\", expected f32"
+            .to_string()
+        ]
+      )
+    );
+
+    assert_eq!(
+      messages(r#"[{"type":"alt","value":4000},{"type":"ident"}]"#),
+      (
+        vec![],
+        vec!["item 0 is an unknown task \"alt\"".to_string()]
+      )
+    );
+
+    assert_eq!(
+      messages(r#"[{"type":"ident"}, 5, "heading"]"#),
+      (
+        vec![],
+        vec![
+          "item 1 is not a task".to_string(),
+          "item 2 is not a task".to_string()
+        ]
+      )
+    );
+
+    assert_eq!(
+      messages(r#"[{"type":"ident"}] and then"#),
+      (
+        vec![],
+        vec!["unexpected input after the list of tasks: and then".to_string()]
+      )
+    );
+
+    let (tasks, errors) = messages(r#"{"type":"ident"}"#);
+    assert!(tasks.is_empty());
+    assert_eq!(
+      errors,
+      vec![r#"expected a list of tasks: found {"type":"ident"}"#.to_string()]
+    );
+  }
+
+  #[test]
+  fn out_of_range_headings_are_rejected() {
+    assert_eq!(
+      messages(r#"[{"type":"heading","value":370},{"type":"ident"}]"#),
+      (
+        vec![],
+        vec![
+          "item 0 has a bad argument for \"heading\": heading 370 is not between 0 and 360"
+            .to_string()
+        ]
+      )
+    );
+
+    let (tasks, errors) = messages(r#"[{"type":"heading","value":-20}]"#);
+    assert!(tasks.is_empty());
+    assert_eq!(errors.len(), 1);
+
+    let (tasks, errors) = messages(r#"[{"type":"heading","value":360}]"#);
+    assert_eq!(tasks, vec![Task::Heading(360.0)]);
+    assert!(errors.is_empty());
+  }
+
+  #[test]
+  fn spoken_altitudes_are_parsed() {
+    let (tasks, errors) = messages(
+      r#"[{"type":"climb","value":"flight level three seven zero"},{"type":"altitude","value":"four thousand five hundred"}]"#,
+    );
+    assert_eq!(tasks, vec![Task::Climb(37000.0), Task::Altitude(4500.0)]);
+    assert!(errors.is_empty());
+
+    let (tasks, errors) =
+      messages(r#"[{"type":"descend","value":"lots of feet"}]"#);
+    assert!(tasks.is_empty());
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn holds_are_parsed() {
+    let hold = Task::Hold {
+      fix: Intern::from_ref("KLAX"),
+      direction: HoldDirection::Left,
+      leg_seconds: 60.0,
+    };
+    for name in ["hold", "hp"] {
+      let (tasks, errors) = messages(&format!(
+        r#"[{{"type":"{name}","value":{{"fix":"KLAX","direction":"left","leg_seconds":60}}}}]"#
+      ));
+      assert_eq!(tasks, vec![hold.clone()]);
+      assert!(errors.is_empty());
+    }
+
+    // Turns are to the right with minute legs unless we are told.
+    let (tasks, errors) =
+      messages(r#"[{"type":"hold","value":{"fix":"KLAX"}}]"#);
+    assert_eq!(
+      tasks,
+      vec![Task::Hold {
+        fix: Intern::from_ref("KLAX"),
+        direction: HoldDirection::Right,
+        leg_seconds: 60.0,
+      }]
+    );
+    assert!(errors.is_empty());
+
+    let (tasks, errors) = messages(
+      r#"[{"type":"hold","value":{"fix":"KLAX","direction":"right","leg_seconds":0}}]"#,
+    );
+    assert!(tasks.is_empty());
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn speeds_and_mach_numbers_are_parsed() {
+    let (tasks, errors) = messages(r#"[{"type":"speed","value":250}]"#);
+    assert_eq!(tasks, vec![Task::Speed(250.0)]);
+    assert!(errors.is_empty());
+
+    let (tasks, errors) = messages(r#"[{"type":"mach","value":0.78}]"#);
+    assert_eq!(tasks, vec![Task::Mach(0.78)]);
+    assert!(errors.is_empty());
+
+    let (tasks, errors) = messages(r#"[{"type":"mach","value":1.5}]"#);
+    assert!(tasks.is_empty());
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn directs_to_radials_are_parsed() {
+    let (tasks, errors) = messages(
+      r#"[{"type":"direct","value":{"fix":"KLAX"}},{"type":"direct","value":{"fix":"KLAX","radial":270,"distance":15}}]"#,
+    );
+    assert_eq!(
+      tasks,
+      vec![
+        Task::Direct {
+          fix: Intern::from_ref("KLAX"),
+          radial: None,
+          distance: None,
+        },
+        Task::Direct {
+          fix: Intern::from_ref("KLAX"),
+          radial: Some(270.0),
+          distance: Some(15.0),
+        },
+      ]
+    );
+    assert!(errors.is_empty());
+
+    for value in [
+      r#"{"fix":"KLAX","radial":400,"distance":15}"#,
+      r#"{"fix":"KLAX","radial":270,"distance":0}"#,
+      r#"{"fix":"KLAX","radial":270}"#,
+      r#"{"fix":"KLAX","radial":"west","distance":15}"#,
+    ] {
+      let (tasks, errors) =
+        messages(&format!(r#"[{{"type":"direct","value":{value}}}]"#));
+      assert!(tasks.is_empty(), "{value}");
+      assert_eq!(errors.len(), 1, "{value}");
+    }
+  }
+}
@@ -1,36 +1,46 @@
 use std::{
-  path::PathBuf,
-  time::{Duration, Instant},
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
 };
 
 use glam::Vec2;
 use internment::Intern;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tokio::sync::mpsc::error::TryRecvError;
 use turborand::{rng::Rng, TurboRand};
 
 use engine::{
-  circle_circle_intersection,
-  command::{CommandReply, CommandWithFreq, OutgoingCommandReply, Task},
-  duration_now,
-  engine::{Engine, Event},
+  clock::{Clock, SystemClock},
+  command::{CommandWithFreq, MessageOrigin, OutgoingCommandReply, Task},
+  engine::{Engine, Event, EventIndex, UICommand},
   entities::{
     aircraft::{
       events::{AircraftEvent, EventKind},
-      Aircraft, AircraftState,
+      Aircraft, AircraftKind, AircraftState, FlightSegment, NavMode,
+      ToTextOptions, BOARDING_DURATION,
     },
+    airport::{Airport, Gate},
     flight::{Flight, FlightKind, FlightStatus},
+    strip::FlightStrip,
     world::{Connection, ConnectionState, Game, Points, World},
   },
+  pathfinder::{new_vor, Node, NodeVORData},
+  poisson_disk_sample,
 };
 
 use crate::{
+  config::is_icao_airport_code,
   job::{JobQueue, JobReq},
   ring::RingBuffer,
-  AUTO_TOWER_AIRSPACE_RADIUS, MANUAL_TOWER_AIRSPACE_RADIUS,
-  TOWER_AIRSPACE_PADDING_RADIUS, WORLD_RADIUS,
+  scenario::Scenario,
+  tts::{TtsMessage, TtsQueue},
+  AUTO_TOWER_AIRSPACE_RADIUS, TOWER_AIRSPACE_PADDING_RADIUS, WORLD_RADIUS,
 };
 
+/// How many ticks run per second, unless configured otherwise.
+pub const DEFAULT_TICK_RATE: usize = 15;
 pub const SPAWN_RATE: Duration = Duration::from_secs(210);
 pub const PREP_SPAWN_RATE: Duration = Duration::from_secs(120);
 pub const SPAWN_LIMIT: usize = 34;
@@ -54,25 +64,42 @@ pub enum OutgoingReply {
 #[derive(Debug, Clone)]
 pub enum TinyReqKind {
   Ping,
+  Health,
   Pause,
+  Silent,
+  /// Runs the game faster or slower than real time.
+  TimeScale(f32),
 
   // Aircraft
   Aircraft,
   OneAircraft(Intern<String>),
+  FlightPlan(Intern<String>),
+  /// Stops or resumes the updates of an aircraft, for debugging.
+  FreezeAircraft(Intern<String>, bool),
+  Strips(f32),
 
   // Flights
   Flights,
   GetFlight(usize),
+  /// Schedules a flight to spawn after `spawn_in` of game time.
   CreateFlight {
     kind: FlightKind,
-    spawn_at: Duration,
+    spawn_in: Duration,
   },
   DeleteFlight(usize),
 
   // Other State
   Messages,
+  /// Takes the callouts waiting to be spoken on a frequency.
+  DrainTts(f32),
   World,
   Points,
+  /// Reloads the airports from the asset directory, for editing them while
+  /// the game runs.
+  #[cfg(debug_assertions)]
+  ReloadAssets,
+  /// Saves the world and game, including all aircraft.
+  Save,
 }
 
 #[derive(Debug, Clone)]
@@ -82,6 +109,18 @@ pub enum ArgReqKind {
   CommandATC(CommandWithFreq),
   /// A reply from an aircraft to ATC.
   CommandReply(CommandWithFreq),
+  /// Replies that all take effect on the same tick.
+  CommandBatch(Vec<CommandWithFreq>),
+
+  // Aircraft
+  /// Replaces an aircraft's route, or adds waypoints to fly before it.
+  AmendFlightPlan {
+    id: Intern<String>,
+    waypoints: Vec<Intern<String>>,
+    replace: bool,
+  },
+  /// Spawns an aircraft now, instead of waiting for a scheduled flight.
+  SpawnAircraft(SpawnRequest),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -89,19 +128,112 @@ pub enum ResKind {
   #[default]
   Any,
   Pong,
+  Health(Health),
 
   // Aircraft
   Aircraft(Vec<Aircraft>),
   OneAircraft(Option<Aircraft>),
+  /// The waypoints of an aircraft's route, in the order they are flown.
+  FlightPlan(Result<Vec<Node<NodeVORData>>, FlightPlanError>),
+  /// Whether each command of a batch was accepted, in the order sent.
+  CommandBatch(Vec<Result<(), CommandError>>),
+  /// The callsign of the spawned aircraft.
+  SpawnAircraft(Result<Intern<String>, SpawnError>),
+  Strips(Vec<FlightStrip>),
 
   // Flights
-  Flights(Vec<Flight>),
+  /// The flights, along with the game time that they are scheduled by.
+  Flights {
+    flights: Vec<Flight>,
+    now: Duration,
+  },
   OneFlight(Option<Flight>),
 
   // Other State
   Messages(Vec<OutgoingCommandReply>),
+  Tts(Vec<TtsMessage>),
   World(World),
   Points(Points),
+  /// The ids of the airports that were loaded.
+  #[cfg(debug_assertions)]
+  ReloadAssets(Result<Vec<Intern<String>>, String>),
+  Save(Result<(), String>),
+}
+
+/// Whether the game is up and running, for readiness checks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+  /// Whether the game loop has ticked the prepared game.
+  pub ready: bool,
+  /// Whether the world and the game have been prepared.
+  pub quick_start_complete: bool,
+  /// How long the game loop has been running.
+  pub uptime: Duration,
+  /// How many ticks run per second.
+  pub tick_rate: usize,
+}
+
+/// The version of [`SaveFile`] that is written and can be loaded. Bump it
+/// when saves from before a change would no longer load as they should.
+pub const SAVE_VERSION: u32 = 2;
+/// How much game time passes between saves to `save_to`. Like the game, this
+/// runs with the time scale and stops while paused.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A saved game, along with the world that it is played in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFile {
+  /// The [`SAVE_VERSION`] that wrote this save. Saves from before saves
+  /// were versioned have none.
+  #[serde(default)]
+  pub version: u32,
+  pub world: World,
+  pub game: Game,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlightPlanError {
+  UnknownAircraft,
+  /// Only aircraft flying between waypoints have a route.
+  NotFlying,
+  UnknownWaypoint(Intern<String>),
+}
+
+/// An aircraft to spawn on demand. Anything left out is picked at random,
+/// as for a scheduled flight.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpawnRequest {
+  pub callsign: Option<Intern<String>>,
+  pub kind: Option<AircraftKind>,
+  pub departing: Option<Intern<String>>,
+  pub arriving: Option<Intern<String>>,
+  /// Either parked at a gate, or flying inbound in the approach or enroute
+  /// segment. Defaults to the approach.
+  pub segment: Option<FlightSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Error)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpawnError {
+  #[error("the callsign {0} is already in use")]
+  CallsignInUse(Intern<String>),
+  #[error("there is no airport {0} to fly from or to")]
+  UnknownAirport(Intern<String>),
+  #[error("there are no other airports to fly from or to")]
+  NoConnections,
+  #[error("no gate is free to park at")]
+  NoFreeGate,
+  #[error("aircraft can't be spawned in the {0:?} segment")]
+  UnsupportedSegment(FlightSegment),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Error)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandError {
+  #[error("no aircraft has this callsign")]
+  UnknownAircraft,
+  #[error("the aircraft is not on this frequency")]
+  WrongFrequency,
 }
 
 #[derive(Debug)]
@@ -110,14 +242,36 @@ pub struct Runner {
   pub game: Game,
   pub engine: Engine,
   pub messages: RingBuffer<CommandWithFreq>,
+  /// Callouts waiting for a TTS backend to speak them.
+  pub tts: TtsQueue,
 
   pub get_queue: JobQueue<TinyReqKind, ResKind>,
   pub post_queue: JobQueue<ArgReqKind, ResKind>,
 
   pub save_to: Option<PathBuf>,
+  /// Whether the game is saved to `save_to` every [`AUTOSAVE_INTERVAL`],
+  /// which is only done when that path was chosen or loaded from.
+  pub autosave: bool,
+  /// A directory of airport files that replace the built-in airports.
+  pub assets: Option<PathBuf>,
   pub rng: Rng,
-
-  last_tick: Instant,
+  pub clock: Arc<dyn Clock>,
+  /// Scripted traffic that replaces the scheduled flights, if loaded.
+  pub scenario: Option<Scenario>,
+
+  /// When the game loop ran its first tick.
+  started: Option<Duration>,
+  /// Whether the world and the game have been prepared, and are ready for
+  /// the game loop.
+  quick_start_complete: bool,
+  /// Whether the game loop has finished a tick.
+  ticked: bool,
+  last_tick: Duration,
+  /// The game time that has passed since the first tick. It runs faster or
+  /// slower than the clock with the time scale, and stops while paused.
+  elapsed: Duration,
+  /// How many ticks have run since the game started.
+  ticks: usize,
   rate: usize,
 }
 
@@ -133,22 +287,45 @@ impl Runner {
       game: Game::default(),
       engine: Engine::default(),
       messages: RingBuffer::new(30),
+      tts: TtsQueue::default(),
 
       get_queue: JobQueue::new(get_rcv),
       post_queue: JobQueue::new(post_rcv),
 
       save_to,
+      autosave: false,
+      assets: None,
       rng,
-
-      last_tick: Instant::now(),
-      rate: 15,
+      clock: Arc::new(SystemClock),
+      scenario: None,
+
+      started: None,
+      quick_start_complete: false,
+      ticked: false,
+      last_tick: Duration::ZERO,
+      elapsed: Duration::ZERO,
+      ticks: 0,
+      rate: DEFAULT_TICK_RATE,
     }
   }
 
+  pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+    self.clock = Arc::new(clock);
+    self
+  }
+
+  /// Runs `rate` ticks per second. Each tick covers a shorter or longer
+  /// time, so the game plays at the same speed.
+  pub fn with_tick_rate(mut self, rate: usize) -> Self {
+    self.rate = rate;
+    self
+  }
+
   pub fn add_aircraft(&mut self, mut aircraft: Aircraft) {
-    while self.game.aircraft.iter().any(|a| a.id == aircraft.id) {
-      aircraft.id = Intern::from(Aircraft::random_callsign(&mut self.rng));
-    }
+    aircraft.id =
+      self
+        .game
+        .free_callsign(aircraft.id, &self.world.airlines, &mut self.rng);
 
     if aircraft.flight_plan.departing == aircraft.flight_plan.arriving {
       tracing::warn!(
@@ -157,67 +334,176 @@ impl Runner {
       return;
     }
 
-    self.game.aircraft.push(aircraft);
+    self.game.push_aircraft(aircraft);
   }
 
-  pub fn generate_airspaces(&mut self, world_rng: &mut Rng) {
-    let airspace_names = [
-      "KLAX", "KPHL", "KJFK", "KMGM", "KCLT", "KDFW", "KATL", "KMCO", "EGLL",
-      "EGLC", "EGNV", "EGNT", "EGGP", "EGCC", "EGKK", "EGHI",
-    ];
+  pub fn generate_airspaces(
+    &mut self,
+    world_rng: &mut Rng,
+    airspace_names: &[String],
+  ) {
+    // Place uncontrolled airspaces at Poisson-disk sampled points, which are
+    // guaranteed to be far enough apart without retrying random positions.
+    let separation =
+      (AUTO_TOWER_AIRSPACE_RADIUS + TOWER_AIRSPACE_PADDING_RADIUS) * 2.0;
+    let mut positions =
+      poisson_disk_sample(world_rng, WORLD_RADIUS, separation, 100).into_iter();
 
-    // Generate randomly positioned uncontrolled airspaces.
     for airspace_name in airspace_names {
-      // TODO: This is a brute-force approach. A better solution would be to use
-      //       some form of jitter or other, potentially, less infinite-loop-prone
-      //       solution.
-
-      let mut i = 0;
-
-      let airspace_position = 'outer: loop {
-        if i >= 1000 {
-          tracing::error!(
-            "Unable to find a place for airspace '{airspace_name}'"
-          );
-          std::process::exit(1);
-        }
+      let airspace_name = airspace_name.as_str();
+      if !is_icao_airport_code(airspace_name) {
+        tracing::warn!("Skipping invalid airspace name '{airspace_name}'");
+        continue;
+      }
 
-        i += 1;
+      if self.world.airspace.id.as_str() == airspace_name
+        || self
+          .world
+          .connections
+          .iter()
+          .any(|c| c.id.as_str() == airspace_name)
+      {
+        tracing::warn!("Skipping duplicate airspace '{airspace_name}'");
+        continue;
+      }
 
-        let position = Vec2::new(
-          (world_rng.f32() - 0.5) * WORLD_RADIUS,
-          (world_rng.f32() - 0.5) * WORLD_RADIUS,
+      // Connections from an earlier call weren't part of the sampling.
+      let connections = &self.world.connections;
+      let Some(airspace_position) = positions.find(|position| {
+        connections
+          .iter()
+          .all(|c| c.pos.distance(*position) >= separation)
+      }) else {
+        tracing::warn!(
+          "Skipping airspace '{airspace_name}', there is no room left for it"
         );
-
-        for airport in self.world.connections.iter() {
-          if circle_circle_intersection(
-            position,
-            airport.pos,
-            AUTO_TOWER_AIRSPACE_RADIUS + TOWER_AIRSPACE_PADDING_RADIUS,
-            AUTO_TOWER_AIRSPACE_RADIUS + TOWER_AIRSPACE_PADDING_RADIUS,
-          ) {
-            continue 'outer;
-          }
-        }
-
-        break position;
+        continue;
       };
 
       let connection = Connection {
         id: Intern::from_ref(airspace_name),
         state: ConnectionState::Active,
         pos: airspace_position,
-        transition: self
-          .world
-          .airspace
-          .pos
-          .move_towards(airspace_position, MANUAL_TOWER_AIRSPACE_RADIUS),
+        transition: self.world.airspace.boundary_entry(airspace_position),
       };
 
       self.world.connections.push(connection);
     }
   }
 
+  /// Writes the world and the game, with all of its aircraft, to `save_to`.
+  pub fn save(&self) -> Result<(), String> {
+    let Some(path) = &self.save_to else {
+      return Err("No save path was provided".to_string());
+    };
+
+    let save = SaveFile {
+      version: SAVE_VERSION,
+      world: self.world.clone(),
+      game: self.game.clone(),
+    };
+    let save = serde_json::to_string(&save)
+      .map_err(|e| format!("Failed to serialize save: {e}"))?;
+    std::fs::write(path, save)
+      .map_err(|e| format!("Failed to write save file: {e}"))
+  }
+
+  /// Replaces the world and the game with the ones saved at `save_to`.
+  pub fn load(&mut self) -> Result<(), String> {
+    let Some(path) = self.save_to.clone() else {
+      return Err("No save path was provided".to_string());
+    };
+
+    self.load_from(&path)
+  }
+
+  /// Replaces the world and the game with the ones saved at `path`. Saves
+  /// of another version are refused, since they may not read as they were
+  /// written.
+  pub fn load_from(&mut self, path: &Path) -> Result<(), String> {
+    let save = std::fs::read_to_string(path)
+      .map_err(|e| format!("Failed to read save file: {e}"))?;
+    let save: serde_json::Value = serde_json::from_str(&save)
+      .map_err(|e| format!("Failed to parse save file: {e}"))?;
+
+    let version = save
+      .get("version")
+      .and_then(serde_json::Value::as_u64)
+      .unwrap_or(0);
+    if version != SAVE_VERSION as u64 {
+      return Err(format!(
+        "Save file is version {version}, but only version {SAVE_VERSION} can be loaded"
+      ));
+    }
+
+    let save: SaveFile = serde_json::from_value(save)
+      .map_err(|e| format!("Failed to parse save file: {e}"))?;
+
+    self.world = save.world;
+    self.game = save.game;
+    self.game.reindex_aircraft();
+
+    Ok(())
+  }
+
+  /// Resumes the game saved at `path`, and autosaves back to it. If it can't
+  /// be loaded, the new game is saved beside it instead, so that a save that
+  /// failed to load is never written over.
+  pub fn resume(&mut self, path: &Path) -> Result<(), String> {
+    let result = self.load_from(path);
+    if result.is_ok() {
+      self.save_to = Some(path.to_path_buf());
+      self.autosave = true;
+    } else {
+      let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+      let name = format!("{stem}-{}.json", self.clock.now().as_secs());
+      self.save_to = Some(path.with_file_name(name));
+      self.autosave = false;
+    }
+
+    result
+  }
+
+  /// Loads each airport in the asset directory, replacing the airport with
+  /// the same id if there is one. Aircraft are left where they are, so
+  /// those at gates or on taxiways that no longer exist should be moved.
+  pub fn load_assets(&mut self) -> Result<Vec<Intern<String>>, String> {
+    let Some(dir) = &self.assets else {
+      return Err("No asset directory was provided".to_string());
+    };
+
+    let entries = std::fs::read_dir(dir)
+      .map_err(|e| format!("Failed to read asset directory: {e}"))?;
+    let mut paths: Vec<PathBuf> = entries
+      .filter_map(|e| e.ok().map(|e| e.path()))
+      .filter(|p| p.extension().is_some_and(|e| e == "json"))
+      .collect();
+    paths.sort();
+
+    // Parse everything first so that a bad file doesn't leave us with only
+    // some of the airports reloaded.
+    let mut airports = Vec::with_capacity(paths.len());
+    for path in paths.iter() {
+      let airport = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+      let mut airport: Airport = serde_json::from_str(&airport)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+      airport.calculate_waypoints();
+      airports.push(airport);
+    }
+
+    let ids = airports.iter().map(|a| a.id).collect();
+    let loaded = &mut self.world.airspace.airports;
+    for airport in airports {
+      match loaded.iter().position(|a| a.id == airport.id) {
+        Some(i) => loaded[i] = airport,
+        None => loaded.push(airport),
+      }
+    }
+
+    Ok(ids)
+  }
+
   pub fn fill_gates(&mut self) {
     let mut aircrafts: Vec<Aircraft> = Vec::new();
     for airport in self.world.airspace.airports.iter() {
@@ -227,86 +513,155 @@ impl Runner {
             gate.clone(),
             &mut self.rng,
             &self.world.airspace,
+            &self.world.airlines,
           );
           aircraft.flight_plan.departing = self.world.airspace.id;
-          aircraft.flight_plan.arriving = self
-            .rng
-            .sample(&self.world.connections)
-            .map(|c| c.id)
-            .unwrap_or_default();
 
           aircrafts.push(aircraft);
         }
       }
     }
 
-    for aircraft in aircrafts.drain(..) {
+    for mut aircraft in aircrafts.drain(..) {
+      aircraft.flight_plan.arriving = self
+        .world
+        .balanced_destination(self.game.aircraft(), &mut self.rng)
+        .map(|c| c.id)
+        .unwrap_or_default();
       self.add_aircraft(aircraft);
     }
   }
 
+  /// The current game time, which is what flights are scheduled and
+  /// aircraft are timed by.
+  pub fn now(&self) -> Duration {
+    self.started.unwrap_or_else(|| self.clock.now()) + self.elapsed
+  }
+
   pub fn handle_flights(&mut self) {
-    let now = duration_now();
+    let now = self.now();
     let mut to_mark: Vec<(usize, Intern<String>)> = Vec::new();
-    for flight in self.game.flights.iter() {
-      if flight.spawn_at <= now
-        && matches!(flight.status, FlightStatus::Scheduled)
-      {
-        match flight.kind {
-          FlightKind::Inbound => {
-            let aircraft = Aircraft::random_inbound(
-              self.world.airspace.frequencies.approach,
-              self.rng.sample(&self.world.connections).unwrap(),
-              &self.world.airspace,
-              &mut self.rng,
-            );
-
-            to_mark.push((flight.id, aircraft.id));
-
-            self.game.aircraft.push(aircraft);
-          }
-          FlightKind::Outbound => {
-            let aircraft =
-              self
-                .rng
-                .sample_iter(self.game.aircraft.iter_mut().filter(|a| {
-                  matches!(a.state, AircraftState::Parked { active: false, .. })
-                }));
-
-            if let Some(aircraft) = aircraft {
-              aircraft.flight_plan.departing = self.world.airspace.id;
-              aircraft.flight_plan.arriving =
-                self.rng.sample(&self.world.connections).unwrap().id;
-              aircraft.set_active(true);
-              aircraft.sync_targets_to_vals();
-
-              to_mark.push((flight.id, aircraft.id));
-
-              self.messages.push(CommandWithFreq::new(
-                aircraft.id.to_string(),
-                aircraft.frequency,
-                CommandReply::ReadyForDeparture {
-                  airport: aircraft.flight_plan.arriving.to_string(),
-                },
-                Vec::new(),
-              ));
-            } else {
-              tracing::warn!("No aircraft available for outbound flight.");
-            }
+    // Spawning adds to and changes our aircraft, so find the due flights
+    // first.
+    let due: Vec<(usize, FlightKind)> = self
+      .game
+      .flights
+      .iter()
+      .filter(|f| {
+        f.spawn_at <= now && matches!(f.status, FlightStatus::Scheduled)
+      })
+      .map(|f| (f.id, f.kind.clone()))
+      .collect();
+    for (flight, kind) in due {
+      match kind {
+        FlightKind::Inbound => {
+          let mut aircraft = Aircraft::random_inbound(
+            self.world.airspace.frequencies.approach,
+            self.rng.sample(&self.world.connections).unwrap(),
+            &self.world.airspace,
+            &mut self.rng,
+            &self.world.airlines,
+          );
+          aircraft.id = self.game.free_callsign(
+            aircraft.id,
+            &self.world.airlines,
+            &mut self.rng,
+          );
+
+          to_mark.push((flight, aircraft.id));
+
+          self.game.push_aircraft(aircraft);
+        }
+        FlightKind::Outbound => {
+          let arriving = self
+            .world
+            .balanced_destination(self.game.aircraft(), &mut self.rng)
+            .map(|c| c.id);
+          let mut fleet = self.game.aircraft_mut();
+          let aircraft = self.rng.sample_iter(fleet.iter_mut().filter(|a| {
+            matches!(a.state, AircraftState::Parked { active: false, .. })
+          }));
+
+          if let Some(aircraft) = aircraft {
+            aircraft.flight_plan.departing = self.world.airspace.id;
+            aircraft.flight_plan.arriving = arriving.unwrap();
+            aircraft.set_active(true);
+            aircraft.sync_targets_to_vals();
+            // Call ready for departure once everyone is on board.
+            aircraft.boarding_until = Some(now + BOARDING_DURATION);
+
+            to_mark.push((flight, aircraft.id));
+          } else {
+            tracing::warn!("No aircraft available for outbound flight.");
           }
         }
       }
     }
 
     for (flight, aircraft) in to_mark {
-      tracing::info!("Spawned flight #{}", flight);
+      if let Some(aircraft) = self.game.aircraft_by_id(aircraft) {
+        tracing::info!(
+          "Spawned flight #{}: {}",
+          flight,
+          aircraft.to_text(&self.world.airspace, ToTextOptions::verbose())
+        );
+      }
       self.game.flights.get_mut(flight).unwrap().status =
         FlightStatus::Ongoing(aircraft);
     }
   }
 
+  /// Spawns the aircraft of the loaded scenario that are due this tick.
+  pub fn spawn_scenario(&mut self) {
+    let Some(scenario) = &mut self.scenario else {
+      return;
+    };
+
+    let frequency = self.world.airspace.frequencies.approach;
+    // Spawns are due by game time, so count the ticks it would have taken
+    // at the normal speed.
+    let tick = (self.elapsed.as_secs_f32() * self.rate as f32).round();
+    for spawn in scenario.take_due(tick as usize) {
+      if self.game.callsign_in_use(spawn.id) {
+        tracing::warn!("Skipping duplicate scenario aircraft {}", spawn.id);
+        continue;
+      }
+
+      self.game.push_aircraft(spawn.to_aircraft(frequency));
+      if !spawn.route.is_empty() {
+        if let Err(e) = self.amend_flight_plan(spawn.id, &spawn.route, true) {
+          tracing::warn!(
+            "Unable to route scenario aircraft {}: {e:?}",
+            spawn.id
+          );
+        }
+      }
+    }
+  }
+
+  /// Marks the world and the game as prepared, once they are set up and
+  /// loaded.
+  pub fn complete_quick_start(&mut self) {
+    self.quick_start_complete = true;
+  }
+
+  pub fn health(&self) -> Health {
+    let uptime = self
+      .started
+      .map(|started| self.clock.now().saturating_sub(started))
+      .unwrap_or_default();
+
+    Health {
+      ready: self.quick_start_complete && self.ticked,
+      quick_start_complete: self.quick_start_complete,
+      uptime,
+      tick_rate: self.rate,
+    }
+  }
+
   pub fn tick(&mut self) {
-    self.last_tick = Instant::now();
+    self.last_tick = self.clock.now();
+    self.started.get_or_insert(self.last_tick);
 
     let mut commands: Vec<CommandWithFreq> = Vec::new();
 
@@ -320,31 +675,65 @@ impl Runner {
 
       match incoming.req() {
         TinyReqKind::Ping => incoming.reply(ResKind::Pong),
+        TinyReqKind::Health => incoming.reply(ResKind::Health(self.health())),
         TinyReqKind::Pause => {
           self.game.paused = !self.game.paused;
         }
+        TinyReqKind::Silent => {
+          self.game.silent = !self.game.silent;
+          incoming.reply(ResKind::Any);
+        }
+        TinyReqKind::TimeScale(scale) => {
+          self
+            .engine
+            .events
+            .push(Event::UiEvent(UICommand::SetTimeScale(*scale).into()));
+          incoming.reply(ResKind::Any);
+        }
 
         // Aircraft
         TinyReqKind::Aircraft => {
-          incoming.reply(ResKind::Aircraft(self.game.aircraft.clone()));
+          incoming.reply(ResKind::Aircraft(self.game.aircraft().to_vec()));
         }
         TinyReqKind::OneAircraft(id) => {
+          let aircraft = self.game.aircraft_by_id(*id).cloned();
+          incoming.reply(ResKind::OneAircraft(aircraft));
+        }
+        TinyReqKind::FlightPlan(id) => {
+          incoming.reply(ResKind::FlightPlan(self.flight_plan(*id)));
+        }
+        TinyReqKind::FreezeAircraft(id, frozen) => {
           let aircraft =
-            self.game.aircraft.iter().find(|a| a.id == *id).cloned();
+            self.game.aircraft_by_id_mut(*id).map(|mut aircraft| {
+              aircraft.frozen = *frozen;
+              aircraft.clone()
+            });
           incoming.reply(ResKind::OneAircraft(aircraft));
         }
+        TinyReqKind::Strips(frequency) => {
+          let strips = self
+            .game
+            .aircraft()
+            .iter()
+            .filter(|a| a.frequency == *frequency)
+            .map(|a| FlightStrip::new(a, &self.world.airspace))
+            .collect();
+          incoming.reply(ResKind::Strips(strips));
+        }
 
         // Flights
         TinyReqKind::Flights => {
-          incoming
-            .reply(ResKind::Flights(self.game.flights.flights().to_vec()));
+          incoming.reply(ResKind::Flights {
+            flights: self.game.flights.flights().to_vec(),
+            now: self.now(),
+          });
         }
         TinyReqKind::GetFlight(id) => {
           let flight = self.game.flights.get(*id).cloned();
           incoming.reply(ResKind::OneFlight(flight));
         }
-        TinyReqKind::CreateFlight { kind, spawn_at } => {
-          let id = self.game.flights.add(kind.clone(), *spawn_at);
+        TinyReqKind::CreateFlight { kind, spawn_in } => {
+          let id = self.game.flights.add(kind.clone(), self.now() + *spawn_in);
           incoming
             .reply(ResKind::OneFlight(self.game.flights.get(id).cloned()));
         }
@@ -355,14 +744,38 @@ impl Runner {
 
         // Other State
         TinyReqKind::Messages => incoming.reply(ResKind::Messages(
-          self.messages.iter().cloned().map(|m| m.into()).collect(),
+          self
+            .messages
+            .iter()
+            .cloned()
+            .map(|m| OutgoingCommandReply::new(m, &self.world.airlines))
+            .collect(),
         )),
+        TinyReqKind::DrainTts(frequency) => {
+          incoming.reply(ResKind::Tts(self.tts.drain(*frequency)))
+        }
         TinyReqKind::World => {
-          incoming.reply(ResKind::World(self.world.clone()))
+          incoming.reply(ResKind::World(self.world.without_pathfinders()))
         }
         TinyReqKind::Points => {
           incoming.reply(ResKind::Points(self.game.points.clone()));
         }
+        TinyReqKind::Save => {
+          let result = self.save();
+          if let Err(e) = &result {
+            tracing::error!("Unable to save: {e}");
+          }
+          incoming.reply(ResKind::Save(result));
+        }
+        #[cfg(debug_assertions)]
+        TinyReqKind::ReloadAssets => {
+          let result = self.load_assets();
+          match &result {
+            Ok(ids) => tracing::info!("Reloaded airports: {ids:?}"),
+            Err(e) => tracing::error!("Unable to reload assets: {e}"),
+          }
+          incoming.reply(ResKind::ReloadAssets(result));
+        }
       }
     }
 
@@ -376,17 +789,53 @@ impl Runner {
 
       match incoming.req() {
         ArgReqKind::CommandATC(command) => {
-          self.messages.push(command.clone());
+          self.messages.push(
+            command
+              .clone()
+              .with_origin(MessageOrigin::Atc)
+              .with_created(self.clock.now()),
+          );
           incoming.reply(ResKind::Any);
         }
         ArgReqKind::CommandReply(command) => {
           commands.push(command.clone());
           incoming.reply(ResKind::Any);
         }
+        ArgReqKind::CommandBatch(batch) => {
+          let results = batch
+            .iter()
+            .map(|command| -> Result<(), CommandError> {
+              self.validate_command(command)?;
+              commands.push(command.clone());
+              Ok(())
+            })
+            .collect();
+          incoming.reply(ResKind::CommandBatch(results));
+        }
+
+        // Aircraft
+        ArgReqKind::AmendFlightPlan {
+          id,
+          waypoints,
+          replace,
+        } => {
+          let plan = self.amend_flight_plan(*id, waypoints, *replace);
+          incoming.reply(ResKind::FlightPlan(plan));
+        }
+        ArgReqKind::SpawnAircraft(request) => {
+          let result = self.spawn_aircraft(request);
+          match &result {
+            Ok(id) => tracing::info!("Spawned {id} on request"),
+            Err(e) => tracing::warn!("Unable to spawn aircraft: {e}"),
+          }
+          incoming.reply(ResKind::SpawnAircraft(result));
+        }
       }
     }
 
     if self.game.paused {
+      // A paused game won't get any further until it is resumed.
+      self.ticked = true;
       return;
     }
 
@@ -395,85 +844,273 @@ impl Runner {
     }
 
     let dt = 1.0 / self.rate as f32;
-    let events =
-      self
-        .engine
-        .tick(&self.world, &mut self.game, &mut self.rng, dt);
-
-    // Run through all callout events and broadcast them
-    self.messages.extend(
-      events
-        .iter()
-        .filter_map(|e| match e {
-          Event::Aircraft(AircraftEvent {
-            kind: EventKind::Callout(command),
-            ..
-          }) => Some(command),
-          _ => None,
-        })
-        .cloned(),
+    self.engine.tick(
+      &self.world,
+      &mut self.game,
+      &mut self.rng,
+      dt,
+      self.now(),
     );
 
-    self.handle_flights();
-    self.cleanup(events.iter());
-    // TODO: self.save_world();
+    let index = self.engine.event_index();
+
+    // Broadcast the callouts
+    let silent = self.game.silent;
+    let now = self.clock.now();
+    let callouts: Vec<CommandWithFreq> = index
+      .callouts
+      .iter()
+      .filter(|c| !(silent && c.reply.is_routine()))
+      .map(|c| c.clone().with_created(now))
+      .collect();
+    for callout in callouts.iter() {
+      self.tts.push(callout, &self.world.airlines);
+    }
+    self.messages.extend(callouts);
+
+    if self.scenario.is_some() {
+      self.spawn_scenario();
+    } else {
+      self.handle_flights();
+    }
+    self.cleanup(&index);
+    self.ticked = true;
+    self.ticks += 1;
+    let before = self.elapsed;
+    self.elapsed += Duration::from_secs_f32(dt * self.game.time_scale());
+
+    let interval = AUTOSAVE_INTERVAL.as_secs();
+    let autosave =
+      before.as_secs() / interval != self.elapsed.as_secs() / interval;
+    if self.autosave && self.save_to.is_some() && autosave {
+      if let Err(e) = self.save() {
+        tracing::error!("Unable to autosave: {e}");
+      }
+    }
+  }
+
+  pub fn should_tick(&self) -> bool {
+    self.clock.now().saturating_sub(self.last_tick)
+      >= Duration::from_secs_f32(1.0 / self.rate as f32)
   }
 
   pub fn begin_loop(&mut self) {
     loop {
-      if Instant::now() - self.last_tick
-        >= Duration::from_secs_f32(1.0 / self.rate as f32)
-      {
+      if self.should_tick() {
         self.tick();
       }
     }
   }
 
-  fn cleanup<'a, T>(&mut self, events: T)
-  where
-    T: Iterator<Item = &'a Event>,
-  {
-    for event in events.filter_map(|e| match e {
-      Event::Aircraft(aircraft_event) => Some(aircraft_event),
-      Event::UiEvent(_) => None,
-    }) {
-      match event {
-        AircraftEvent {
-          id,
-          kind: EventKind::Delete,
-        } => {
-          let index = self
-            .game
-            .aircraft
+  fn cleanup(&mut self, index: &EventIndex) {
+    for id in index.deletes.iter() {
+      self.game.remove_aircraft(*id);
+    }
+
+    for id in index.completed_flights.iter() {
+      if let Some(flight) = self.game.flights.get_by_aircraft_id(*id) {
+        self.game.flights.get_mut(flight).unwrap().status =
+          FlightStatus::Completed(*id, self.now());
+      }
+    }
+  }
+
+  /// The waypoints of an aircraft's route, in the order they are flown.
+  pub fn flight_plan(
+    &self,
+    id: Intern<String>,
+  ) -> Result<Vec<Node<NodeVORData>>, FlightPlanError> {
+    let aircraft = self
+      .game
+      .aircraft_by_id(id)
+      .ok_or(FlightPlanError::UnknownAircraft)?;
+    if let AircraftState::Flying { waypoints, .. } = &aircraft.state {
+      // Waypoints are flown from the back.
+      Ok(waypoints.iter().rev().cloned().collect())
+    } else {
+      Err(FlightPlanError::NotFlying)
+    }
+  }
+
+  /// Spawns an aircraft as requested, either parked at a free gate ready to
+  /// depart or flying inbound to our airspace.
+  pub fn spawn_aircraft(
+    &mut self,
+    request: &SpawnRequest,
+  ) -> Result<Intern<String>, SpawnError> {
+    if let Some(id) = request.callsign {
+      if self.game.callsign_in_use(id) {
+        return Err(SpawnError::CallsignInUse(id));
+      }
+    }
+
+    let find_connection = |id: Option<Intern<String>>, rng: &mut Rng| match id {
+      Some(id) => self
+        .world
+        .connections
+        .iter()
+        .find(|c| c.id == id)
+        .ok_or(SpawnError::UnknownAirport(id)),
+      None => rng
+        .sample(&self.world.connections)
+        .ok_or(SpawnError::NoConnections),
+    };
+
+    let mut aircraft = match request.segment.unwrap_or(FlightSegment::Approach)
+    {
+      FlightSegment::Parked => {
+        let departing =
+          request.departing.filter(|d| *d != self.world.airspace.id);
+        if let Some(departing) = departing {
+          if !self
+            .world
+            .airspace
+            .airports
             .iter()
-            .enumerate()
-            .find_map(|(i, a)| (a.id == *id).then_some(i));
-          if let Some(index) = index {
-            self.game.aircraft.swap_remove(index);
+            .any(|a| a.id == departing)
+          {
+            return Err(SpawnError::UnknownAirport(departing));
           }
         }
-        AircraftEvent {
-          id,
-          kind: EventKind::CompleteFlight,
-        } => {
-          if let Some(flight) = self.game.flights.get_by_aircraft_id(*id) {
-            self.game.flights.get_mut(flight).unwrap().status =
-              FlightStatus::Completed(*id, duration_now());
+        let arriving = match request.arriving {
+          Some(_) => find_connection(request.arriving, &mut self.rng)?,
+          None => self
+            .world
+            .balanced_destination(self.game.aircraft(), &mut self.rng)
+            .ok_or(SpawnError::NoConnections)?,
+        };
+
+        let occupied = |gate: &Gate| {
+          self.game.aircraft().iter().any(|a| match &a.state {
+            AircraftState::Parked { at, .. } => at.value == gate.pos,
+            _ => false,
+          })
+        };
+        let gates: Vec<&Gate> = self
+          .world
+          .airspace
+          .airports
+          .iter()
+          .filter(|a| departing.is_none_or(|d| a.id == d))
+          .flat_map(|a| a.terminals.iter().flat_map(|t| t.gates.iter()))
+          .filter(|g| !occupied(g))
+          .collect();
+        let gate = self.rng.sample(&gates).ok_or(SpawnError::NoFreeGate)?;
+
+        let mut aircraft = Aircraft::random_parked(
+          (*gate).clone(),
+          &mut self.rng,
+          &self.world.airspace,
+          &self.world.airlines,
+        );
+        aircraft.flight_plan.departing =
+          departing.unwrap_or(self.world.airspace.id);
+        aircraft.flight_plan.arriving = arriving.id;
+        aircraft.set_active(true);
+        aircraft.boarding_until = Some(self.now() + BOARDING_DURATION);
+        aircraft
+      }
+      FlightSegment::Approach | FlightSegment::Enroute => {
+        if let Some(arriving) = request.arriving {
+          if arriving != self.world.airspace.id {
+            return Err(SpawnError::UnknownAirport(arriving));
           }
         }
-        _ => {}
+        let departing = find_connection(request.departing, &mut self.rng)?;
+
+        Aircraft::random_inbound(
+          self.world.airspace.frequencies.approach,
+          departing,
+          &self.world.airspace,
+          &mut self.rng,
+          &self.world.airlines,
+        )
+      }
+      segment => return Err(SpawnError::UnsupportedSegment(segment)),
+    };
+
+    let random_id = aircraft.id;
+    aircraft.id = match request.callsign {
+      Some(id) => id,
+      None => self.game.free_callsign(
+        aircraft.id,
+        &self.world.airlines,
+        &mut self.rng,
+      ),
+    };
+    // A requested or reissued callsign may be of another airline, which
+    // flies another fleet.
+    if request.kind.is_some() || aircraft.id != random_id {
+      let kind = request.kind.clone().unwrap_or_else(|| {
+        self.world.airlines.random_kind(&aircraft.id, &mut self.rng)
+      });
+      aircraft.fuel = kind.stats().fuel_capacity;
+      aircraft.kind = kind;
+    }
+    aircraft.sync_targets_to_vals();
+
+    let id = aircraft.id;
+    self.game.push_aircraft(aircraft);
+    Ok(id)
+  }
+
+  /// Replaces an aircraft's route with `names`, or adds them to be flown
+  /// before the rest of the route when `replace` is false.
+  pub fn amend_flight_plan(
+    &mut self,
+    id: Intern<String>,
+    names: &[Intern<String>],
+    replace: bool,
+  ) -> Result<Vec<Node<NodeVORData>>, FlightPlanError> {
+    let mut amended = Vec::with_capacity(names.len());
+    for name in names.iter().rev() {
+      let to = self
+        .world
+        .find_waypoint(*name)
+        .ok_or(FlightPlanError::UnknownWaypoint(*name))?;
+      amended.push(new_vor(*name, to));
+    }
+
+    {
+      let mut aircraft = self
+        .game
+        .aircraft_by_id_mut(id)
+        .ok_or(FlightPlanError::UnknownAircraft)?;
+      // Borrow the aircraft itself, so that its fields can be borrowed apart.
+      let aircraft = &mut *aircraft;
+      if let AircraftState::Flying { waypoints, .. } = &mut aircraft.state {
+        if replace {
+          waypoints.clear();
+        }
+        waypoints.extend(amended);
+        aircraft.flight_plan.plan_route(aircraft.pos, waypoints);
+        aircraft.nav_mode = NavMode::LNav;
+        aircraft.hold = None;
+      } else {
+        return Err(FlightPlanError::NotFlying);
       }
     }
+
+    self.flight_plan(id)
+  }
+
+  /// Checks that a command can be given: the aircraft must exist and be
+  /// tuned to the frequency that it was given on.
+  fn validate_command(
+    &self,
+    command: &CommandWithFreq,
+  ) -> Result<(), CommandError> {
+    match self.game.aircraft_by_id(Intern::from_ref(&command.id)) {
+      Some(aircraft) if aircraft.frequency == command.frequency => Ok(()),
+      Some(_) => Err(CommandError::WrongFrequency),
+      None => Err(CommandError::UnknownAircraft),
+    }
   }
 
   fn execute_command(&mut self, command: CommandWithFreq) {
+    let command = command.with_created(self.clock.now());
     let id = Intern::from_ref(&command.id);
-    if self
-      .game
-      .aircraft
-      .iter()
-      .any(|a| a.id == id && a.frequency == command.frequency)
-    {
+    if self.validate_command(&command).is_ok() {
       self.engine.events.extend(
         command
           .tasks
@@ -485,7 +1122,7 @@ impl Runner {
       let mut callout = true;
       for task in command.tasks.iter() {
         match task {
-          Task::Ident => {
+          Task::Ident | Task::SayAgain | Task::ExpectRunway(..) => {
             // Don't generate a callout for these commands
             callout = command.tasks.len() > 1;
           }
@@ -498,6 +1135,11 @@ impl Runner {
       }
 
       if callout {
+        if let Some(mut aircraft) = self.game.aircraft_by_id_mut(id) {
+          aircraft.last_readback = Some(command.to_text(&self.world.airlines));
+        }
+
+        self.tts.push(&command, &self.world.airlines);
         self.messages.push(command.clone());
       }
     }
@@ -511,7 +1153,7 @@ impl Runner {
   //   loop {
   //     let realtime = i as f32 * 1.0 / self.rate as f32;
   //     if Duration::from_secs_f32(realtime - last_spawn) >= PREP_SPAWN_RATE
-  //       && self.game.aircraft.len() < SPAWN_LIMIT
+  //       && self.game.aircraft().len() < SPAWN_LIMIT
   //     {
   //       self.spawn_inbound();
   //       last_spawn = realtime;
@@ -525,7 +1167,7 @@ impl Runner {
 
   //     self.cleanup(events.iter());
 
-  //     if self.game.aircraft.iter().any(|aircraft| {
+  //     if self.game.aircraft().iter().any(|aircraft| {
   //       aircraft.altitude != 0.0
   //         && aircraft.pos.distance_squared(self.world.airspace.pos)
   //           <= MANUAL_TOWER_AIRSPACE_RADIUS.powf(2.0)
@@ -538,3 +1180,863 @@ impl Runner {
   //   }
   // }
 }
+
+#[cfg(test)]
+mod test {
+  use std::collections::HashSet;
+
+  use engine::{
+    airline::Airlines,
+    angle_between_points,
+    clock::MockClock,
+    command::{CommandReply, Severity},
+    entities::{
+      aircraft::{AircraftKind, FlightPlan, LandingState, TaxiingState},
+      airport::Runway,
+    },
+    pathfinder::{NodeBehavior, NodeKind},
+  };
+  use tokio::sync::mpsc;
+  use turborand::SeededCore;
+
+  use crate::http::shared::{GetSender, PostSender};
+
+  use super::*;
+
+  fn test_runner(clock: MockClock) -> (Runner, GetSender, PostSender) {
+    let (get_tx, get_rx) = mpsc::unbounded_channel();
+    let (post_tx, post_rx) = mpsc::unbounded_channel();
+    let runner =
+      Runner::new(get_rx, post_rx, None, Rng::with_seed(0)).with_clock(clock);
+
+    (runner, get_tx, post_tx)
+  }
+
+  #[tokio::test]
+  async fn health_reports_ready_once_running() {
+    let clock = MockClock::new(Duration::from_secs(1000));
+    let (mut runner, mut get_tx, _post_tx) = test_runner(clock.clone());
+
+    let health = runner.health();
+    assert!(!health.ready);
+    assert!(!health.quick_start_complete);
+    assert_eq!(health.uptime, Duration::ZERO);
+
+    // Prepared, but the loop hasn't finished a tick when it answers.
+    runner.complete_quick_start();
+    let request = JobReq::send(TinyReqKind::Health, &mut get_tx);
+    runner.tick();
+    let Ok(ResKind::Health(health)) = request.recv().await else {
+      panic!("expected a health reply");
+    };
+    assert!(!health.ready);
+    assert!(health.quick_start_complete);
+
+    // Ready once the loop has ticked the prepared world.
+    clock.advance(Duration::from_secs(5));
+    let request = JobReq::send(TinyReqKind::Health, &mut get_tx);
+    runner.tick();
+    let Ok(ResKind::Health(health)) = request.recv().await else {
+      panic!("expected a health reply");
+    };
+    assert!(health.ready);
+    assert_eq!(health.uptime, Duration::from_secs(5));
+    assert_eq!(health.tick_rate, runner.rate);
+  }
+
+  #[test]
+  fn tick_gating_follows_clock() {
+    let clock = MockClock::new(Duration::from_secs(1000));
+    let (mut runner, _get_tx, _post_tx) = test_runner(clock.clone());
+    let tick_length = Duration::from_secs_f32(1.0 / runner.rate as f32);
+
+    assert!(runner.should_tick());
+    runner.tick();
+    assert!(!runner.should_tick());
+
+    clock.advance(tick_length / 2);
+    assert!(!runner.should_tick());
+
+    clock.advance(tick_length / 2);
+    assert!(runner.should_tick());
+    runner.tick();
+    assert!(!runner.should_tick());
+  }
+
+  #[test]
+  fn tick_rate_keeps_the_game_speed() {
+    let clock = MockClock::new(Duration::from_secs(1000));
+    let (runner, _get_tx, _post_tx) = test_runner(clock.clone());
+    let mut runner = runner.with_tick_rate(30);
+    let tick_length = Duration::from_secs_f32(1.0 / 30.0);
+    assert_eq!(runner.health().tick_rate, 30);
+
+    runner.game.push_aircraft(
+      Aircraft {
+        id: Intern::from_ref("AAL1234"),
+        speed: 250.0,
+        altitude: 7000.0,
+        ..Default::default()
+      }
+      .with_synced_targets(),
+    );
+
+    // A second of ticks, each gated by the clock.
+    for _ in 0..30 {
+      assert!(runner.should_tick());
+      runner.tick();
+      assert!(!runner.should_tick());
+      clock.advance(tick_length);
+    }
+
+    // We flew for a second at 250 knots, as we would have at any tick rate.
+    let distance = runner.game.aircraft()[0].pos.length();
+    let expected = 250.0 * engine::KNOT_TO_FEET_PER_SECOND;
+    assert!(
+      (distance - expected).abs() < 1.0,
+      "{distance} != {expected}"
+    );
+  }
+
+  #[test]
+  fn messages_are_tagged_with_origin() {
+    let clock = MockClock::new(Duration::from_secs(1000));
+    let (mut runner, _get_tx, mut post_tx) = test_runner(clock);
+    runner.add_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      frequency: 118.5,
+      ..Default::default()
+    });
+
+    let _atc = JobReq::send(
+      ArgReqKind::CommandATC(CommandWithFreq::new(
+        "ATC".to_string(),
+        118.5,
+        CommandReply::Blank {
+          text: "American 1234, ident.".to_string(),
+        },
+        Vec::new(),
+      )),
+      &mut post_tx,
+    );
+    let _reply = JobReq::send(
+      ArgReqKind::CommandReply(CommandWithFreq::new(
+        "AAL1234".to_string(),
+        118.5,
+        CommandReply::Empty,
+        vec![Task::Ident],
+      )),
+      &mut post_tx,
+    );
+    runner.tick();
+
+    // Both are timed by the runner's clock, not the system's.
+    let created = Duration::from_secs(1000);
+    let origins: Vec<(&str, MessageOrigin, Duration)> = runner
+      .messages
+      .iter()
+      .map(|m| (m.id.as_str(), m.origin, m.created))
+      .collect();
+    assert_eq!(
+      origins,
+      vec![
+        ("ATC", MessageOrigin::Atc, created),
+        ("AAL1234", MessageOrigin::Pilot, created)
+      ]
+    );
+  }
+
+  #[test]
+  fn say_again_repeats_last_readback() {
+    let (mut runner, _get_tx, mut post_tx) = test_runner(MockClock::default());
+    runner.add_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      frequency: 118.5,
+      ..Default::default()
+    });
+
+    let _climb = JobReq::send(
+      ArgReqKind::CommandReply(CommandWithFreq::new(
+        "AAL1234".to_string(),
+        118.5,
+        CommandReply::WithCallsign {
+          text: "Climb and maintain 4000 feet".to_string(),
+        },
+        vec![Task::Altitude(4000.0)],
+      )),
+      &mut post_tx,
+    );
+    runner.tick();
+    let airlines = runner.world.airlines.clone();
+    let readback = runner.messages.iter().last().unwrap().to_text(&airlines);
+
+    let _say_again = JobReq::send(
+      ArgReqKind::CommandReply(CommandWithFreq::new(
+        "AAL1234".to_string(),
+        118.5,
+        CommandReply::WithCallsign {
+          text: "Say again".to_string(),
+        },
+        vec![Task::SayAgain],
+      )),
+      &mut post_tx,
+    );
+    runner.tick();
+
+    let messages: Vec<String> = runner
+      .messages
+      .iter()
+      .map(|m| m.to_text(&airlines))
+      .collect();
+    assert_eq!(messages, vec![readback.clone(), readback]);
+  }
+
+  #[test]
+  fn generate_airspaces_from_custom_list() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    runner.world.airspace.id = Intern::from_ref("KSFO");
+
+    runner.generate_airspaces(
+      &mut Rng::with_seed(0),
+      &["KLAX", "EGLL", "KSFO", "K1A5", "egcc"].map(String::from),
+    );
+
+    let ids: Vec<&str> = runner
+      .world
+      .connections
+      .iter()
+      .map(|c| c.id.as_str())
+      .collect();
+    assert_eq!(ids, vec!["KLAX", "EGLL"]);
+  }
+
+  #[test]
+  fn generated_airspaces_are_separated() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    let names: Vec<String> = crate::config::DEFAULT_AIRSPACE_NAMES
+      .iter()
+      .map(|n| n.to_string())
+      .collect();
+
+    runner.generate_airspaces(&mut Rng::with_seed(0), &names);
+
+    let connections = &runner.world.connections;
+    assert_eq!(connections.len(), names.len());
+
+    let separation =
+      (AUTO_TOWER_AIRSPACE_RADIUS + TOWER_AIRSPACE_PADDING_RADIUS) * 2.0;
+    for (i, a) in connections.iter().enumerate() {
+      for b in connections.iter().skip(i + 1) {
+        assert!(
+          a.pos.distance(b.pos) >= separation,
+          "{} and {} are too close",
+          a.id,
+          b.id
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn silent_mode_keeps_only_safety_callouts() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    runner.game.silent = true;
+
+    runner.add_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      ..Default::default()
+    });
+    runner.add_aircraft(Aircraft {
+      id: Intern::from_ref("SKW5678"),
+      pos: Vec2::new(5000.0, 0.0),
+      heading: 270.0,
+      speed: 150.0,
+      altitude: 5000.0,
+      state: AircraftState::Landing {
+        runway: Runway {
+          id: Intern::from_ref("27"),
+          heading: 270.0,
+          length: 7000.0,
+          ..Default::default()
+        },
+        state: LandingState::Glideslope,
+      },
+      ..Default::default()
+    });
+
+    runner.engine.events.push(
+      AircraftEvent::new(
+        Intern::from_ref("AAL1234"),
+        EventKind::NamedFrequency("tower".to_string()),
+      )
+      .into(),
+    );
+    runner.tick();
+
+    let replies: Vec<&CommandReply> =
+      runner.messages.iter().map(|m| &m.reply).collect();
+    assert!(replies
+      .iter()
+      .any(|r| matches!(r, CommandReply::GoAround { .. })));
+    assert!(!replies
+      .iter()
+      .any(|r| matches!(r, CommandReply::CheckIn { .. })));
+  }
+
+  #[test]
+  fn callouts_carry_a_severity() {
+    let outgoing = |reply| {
+      OutgoingCommandReply::new(
+        CommandWithFreq::new("AAL1234".to_string(), 118.5, reply, Vec::new()),
+        &Airlines::default(),
+      )
+    };
+
+    let warning = outgoing(CommandReply::LowAltitude { altitude: 2000.0 });
+    assert_eq!(warning.severity, Severity::Warning);
+
+    let info = outgoing(CommandReply::ReadyForDeparture {
+      airport: "KSFO".to_string(),
+    });
+    assert_eq!(info.severity, Severity::Info);
+  }
+
+  #[test]
+  fn amended_flight_plan_is_followed() {
+    let (mut runner, _get_tx, mut post_tx) = test_runner(MockClock::default());
+    for (id, pos) in [
+      ("KLAX", Vec2::new(0.0, 200000.0)),
+      ("KJFK", Vec2::new(200000.0, 0.0)),
+    ] {
+      runner.world.connections.push(Connection {
+        id: Intern::from_ref(id),
+        state: ConnectionState::Active,
+        pos,
+        transition: pos,
+      });
+    }
+
+    runner.game.push_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      speed: 250.0,
+      altitude: 7000.0,
+      state: AircraftState::Flying {
+        waypoints: vec![new_vor(
+          Intern::from_ref("KLAX"),
+          Vec2::new(0.0, 200000.0),
+        )],
+        enroute: false,
+      },
+      ..Default::default()
+    });
+    let id = Intern::from_ref("AAL1234");
+
+    let _unknown = JobReq::send(
+      ArgReqKind::AmendFlightPlan {
+        id,
+        waypoints: vec![Intern::from_ref("NOWHERE")],
+        replace: true,
+      },
+      &mut post_tx,
+    );
+    let _amend = JobReq::send(
+      ArgReqKind::AmendFlightPlan {
+        id,
+        waypoints: vec![Intern::from_ref("KJFK")],
+        replace: false,
+      },
+      &mut post_tx,
+    );
+    runner.tick();
+
+    let names: Vec<String> = runner
+      .flight_plan(id)
+      .unwrap()
+      .iter()
+      .map(|wp| wp.name.to_string())
+      .collect();
+    assert_eq!(names, vec!["KJFK", "KLAX"]);
+
+    let heading = runner.game.aircraft_by_id(id).unwrap().target.heading;
+    let expected = angle_between_points(Vec2::ZERO, Vec2::new(200000.0, 0.0));
+    assert!((heading - expected).abs() < 1.0);
+    assert_eq!(
+      runner.amend_flight_plan(id, &[Intern::from_ref("NOWHERE")], true),
+      Err(FlightPlanError::UnknownWaypoint(Intern::from_ref(
+        "NOWHERE"
+      )))
+    );
+  }
+
+  #[test]
+  fn frozen_aircraft_stop_moving() {
+    let (mut runner, mut get_tx, _post_tx) = test_runner(MockClock::default());
+    for id in ["AAL1234", "SKW5678"] {
+      runner.game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          speed: 250.0,
+          altitude: 7000.0,
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+    }
+    let frozen = Intern::from_ref("AAL1234");
+    let moving = Intern::from_ref("SKW5678");
+
+    let _freeze =
+      JobReq::send(TinyReqKind::FreezeAircraft(frozen, true), &mut get_tx);
+    runner.tick();
+
+    let position =
+      |runner: &Runner, id| runner.game.aircraft_by_id(id).unwrap().pos;
+    let (frozen_start, moving_start) =
+      (position(&runner, frozen), position(&runner, moving));
+    for _ in 0..10 {
+      runner.tick();
+    }
+
+    assert_eq!(position(&runner, frozen), frozen_start);
+    assert_ne!(position(&runner, moving), moving_start);
+    assert!(runner.game.aircraft_by_id(frozen).unwrap().frozen);
+
+    let _release =
+      JobReq::send(TinyReqKind::FreezeAircraft(frozen, false), &mut get_tx);
+    runner.tick();
+    assert_ne!(position(&runner, frozen), frozen_start);
+  }
+
+  #[test]
+  fn scenario_spawns_on_schedule() {
+    let clock = MockClock::default();
+    let (mut runner, _get_tx, _post_tx) = test_runner(clock.clone());
+    let tick_length = Duration::from_secs_f32(1.0 / runner.rate as f32);
+
+    let scenario: Scenario = serde_json::from_str(
+      r#"{
+        "spawns": [
+          { "tick": 0, "id": "AAL1234", "altitude": 4000, "speed": 250 },
+          { "tick": 30, "id": "SKW5678", "altitude": 6000, "speed": 250 }
+        ]
+      }"#,
+    )
+    .unwrap();
+    runner.scenario = Some(scenario);
+
+    let ids = |runner: &Runner| -> Vec<&str> {
+      runner
+        .game
+        .aircraft()
+        .iter()
+        .map(|a| a.id.as_str())
+        .collect()
+    };
+
+    runner.tick();
+    assert_eq!(ids(&runner), vec!["AAL1234"]);
+    assert_eq!(
+      runner.game.aircraft()[0].frequency,
+      runner.world.airspace.frequencies.approach
+    );
+
+    for _ in 1..30 {
+      clock.advance(tick_length);
+      runner.tick();
+      assert_eq!(ids(&runner), vec!["AAL1234"]);
+    }
+
+    clock.advance(tick_length);
+    runner.tick();
+    assert_eq!(ids(&runner), vec!["AAL1234", "SKW5678"]);
+    assert_eq!(runner.game.aircraft()[1].altitude, 6000.0);
+    assert!(runner.scenario.as_ref().unwrap().spawns.is_empty());
+  }
+
+  #[test]
+  fn fast_forward_keeps_the_scenario_schedule() {
+    let (mut runner, mut get_tx, _post_tx) = test_runner(MockClock::default());
+    runner.scenario = Some(
+      serde_json::from_str(
+        r#"{ "spawns": [{ "tick": 30, "id": "SKW5678", "speed": 250 }] }"#,
+      )
+      .unwrap(),
+    );
+
+    // At twice the speed, 15 ticks cover as much game time as 30, so the
+    // spawn is due on the 16th instead of the 31st.
+    let _time_scale = JobReq::send(TinyReqKind::TimeScale(2.0), &mut get_tx);
+    for _ in 0..15 {
+      runner.tick();
+      assert!(runner.game.aircraft().is_empty());
+    }
+
+    runner.tick();
+    assert_eq!(runner.game.time_scale, 2.0);
+    assert_eq!(runner.game.aircraft().len(), 1);
+  }
+
+  #[test]
+  fn saves_of_other_versions_are_refused() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    let path = std::env::temp_dir()
+      .join(format!("airwave-old-save-{}.json", std::process::id()));
+    runner.save_to = Some(path.clone());
+    runner.save().unwrap();
+
+    let mut save: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    save.as_object_mut().unwrap().remove("version");
+    std::fs::write(&path, save.to_string()).unwrap();
+
+    let result = runner.load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.unwrap_err().contains("version 0"));
+  }
+
+  #[test]
+  fn saves_that_fail_to_load_are_not_autosaved_over() {
+    let (runner, _get_tx, _post_tx) =
+      test_runner(MockClock::new(Duration::from_secs(1234)));
+    let mut runner = runner.with_tick_rate(1);
+    let path = std::env::temp_dir()
+      .join(format!("airwave-future-save-{}.json", std::process::id()));
+    let save = r#"{"version":999}"#;
+    std::fs::write(&path, save).unwrap();
+
+    assert!(runner.resume(&path).is_err());
+    assert!(!runner.autosave);
+    let save_to = runner.save_to.clone().unwrap();
+    assert_ne!(save_to, path);
+    assert!(save_to.to_string_lossy().ends_with("-1234.json"));
+
+    for _ in 0..AUTOSAVE_INTERVAL.as_secs() * 2 {
+      runner.tick();
+    }
+    let unchanged = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(unchanged, save);
+    assert!(!save_to.exists());
+  }
+
+  #[test]
+  fn game_is_autosaved() {
+    let (runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    let mut runner = runner.with_tick_rate(1);
+    let path = std::env::temp_dir()
+      .join(format!("airwave-autosave-{}.json", std::process::id()));
+    runner.save_to = Some(path.clone());
+    runner.autosave = true;
+
+    for _ in 1..AUTOSAVE_INTERVAL.as_secs() {
+      runner.tick();
+    }
+    assert!(!path.exists());
+
+    runner.tick();
+    assert!(path.exists());
+    std::fs::remove_file(&path).unwrap();
+
+    // The interval is in game time, so it passes twice as fast at double
+    // speed.
+    runner.game.time_scale = 2.0;
+    for _ in 1..AUTOSAVE_INTERVAL.as_secs() / 2 {
+      runner.tick();
+    }
+    assert!(!path.exists());
+
+    runner.tick();
+    assert!(path.exists());
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn saves_include_live_traffic() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    let path = std::env::temp_dir()
+      .join(format!("airwave-save-{}.json", std::process::id()));
+    runner.save_to = Some(path.clone());
+    runner.world.airspace.id = Intern::from_ref("KSFO");
+
+    let node = |name: &str, kind: NodeKind, pos: Vec2| {
+      Node::new(Intern::from_ref(name), kind, NodeBehavior::GoTo, pos)
+    };
+    let states = [
+      AircraftState::Flying {
+        waypoints: vec![new_vor(Intern::from_ref("FIX"), Vec2::new(0.0, 1.0))
+          .with_behavior(vec![EventKind::EnRoute(false)])],
+        enroute: true,
+      },
+      AircraftState::Landing {
+        runway: Runway {
+          id: Intern::from_ref("27"),
+          heading: 270.0,
+          length: 7000.0,
+          ..Default::default()
+        },
+        state: LandingState::Glideslope,
+      },
+      AircraftState::Taxiing {
+        current: node("A", NodeKind::Taxiway, Vec2::ZERO),
+        waypoints: vec![node("27", NodeKind::Runway, Vec2::X)],
+        state: TaxiingState::Stopped,
+      },
+      AircraftState::Parked {
+        at: node("A1", NodeKind::Gate, Vec2::Y),
+        active: true,
+      },
+    ];
+    for (i, state) in states.into_iter().enumerate() {
+      let mut aircraft = Aircraft {
+        id: Intern::from(format!("AAL{i:04}")),
+        pos: Vec2::new(i as f32 * 1000.0, 0.0),
+        speed: 180.0,
+        altitude: 3000.0,
+        state,
+        flight_plan: FlightPlan::new(
+          Intern::from_ref("KSFO"),
+          Intern::from_ref("KLAX"),
+        ),
+        squawk: Some(4321),
+        kind: AircraftKind::A21N,
+        fuel: 20000.0,
+        ..Default::default()
+      }
+      .with_synced_targets();
+      aircraft.target.altitude = 5000.0;
+      runner.game.push_aircraft(aircraft);
+    }
+
+    runner.save().unwrap();
+
+    let (mut loaded, _get_tx, _post_tx) = test_runner(MockClock::default());
+    loaded.save_to = Some(path.clone());
+    loaded.load().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.world.airspace.id, runner.world.airspace.id);
+    assert_eq!(loaded.game.aircraft(), runner.game.aircraft());
+    for aircraft in runner.game.aircraft().iter() {
+      assert!(loaded.game.aircraft_by_id(aircraft.id).is_some());
+    }
+  }
+
+  #[cfg(debug_assertions)]
+  #[test]
+  fn reloading_assets_replaces_airports() {
+    let (mut runner, mut get_tx, _post_tx) = test_runner(MockClock::default());
+    let dir = std::env::temp_dir()
+      .join(format!("airwave-assets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    runner.assets = Some(dir.clone());
+
+    let ksfo = Intern::from_ref("KSFO");
+    let write_airport = |length: f32| {
+      let mut airport = Airport::new(ksfo, Vec2::ZERO);
+      airport.add_runway(Runway {
+        id: Intern::from_ref("27"),
+        heading: 270.0,
+        length,
+        ..Default::default()
+      });
+      std::fs::write(
+        dir.join("KSFO.json"),
+        serde_json::to_string(&airport).unwrap(),
+      )
+      .unwrap();
+    };
+    let runway_length = |runner: &Runner| {
+      runner
+        .world
+        .airspace
+        .airports
+        .iter()
+        .find(|a| a.id == ksfo)
+        .map(|a| a.runways[0].length)
+    };
+
+    runner.game.push_aircraft(Aircraft {
+      id: Intern::from_ref("AAL1234"),
+      ..Default::default()
+    });
+
+    write_airport(7000.0);
+    let _reload = JobReq::send(TinyReqKind::ReloadAssets, &mut get_tx);
+    runner.tick();
+    assert_eq!(runway_length(&runner), Some(7200.0));
+
+    // Editing the file on disk shows up on the next reload.
+    write_airport(9000.0);
+    let _reload = JobReq::send(TinyReqKind::ReloadAssets, &mut get_tx);
+    runner.tick();
+    assert_eq!(runway_length(&runner), Some(9200.0));
+    assert_eq!(runner.world.airspace.airports.len(), 1);
+    assert!(runner
+      .game
+      .aircraft_by_id(Intern::from_ref("AAL1234"))
+      .is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn callsigns_stay_unique_across_deletes() {
+    let (mut runner, _get_tx, _post_tx) = test_runner(MockClock::default());
+    let aal = Intern::from_ref("AAL1234");
+    let spawn = |runner: &mut Runner| {
+      runner.add_aircraft(Aircraft {
+        id: aal,
+        ..Default::default()
+      });
+    };
+
+    spawn(&mut runner);
+    spawn(&mut runner);
+    assert_eq!(runner.game.aircraft().len(), 2);
+    assert_ne!(runner.game.aircraft()[0].id, runner.game.aircraft()[1].id);
+
+    runner.cleanup(&EventIndex {
+      deletes: vec![aal],
+      ..Default::default()
+    });
+    assert!(runner.game.aircraft_by_id(aal).is_none());
+
+    // The freed callsign is reused instead of being renamed.
+    spawn(&mut runner);
+    assert!(runner.game.aircraft_by_id(aal).is_some());
+
+    let ids: HashSet<_> = runner.game.aircraft().iter().map(|a| a.id).collect();
+    assert_eq!(ids.len(), runner.game.aircraft().len());
+    assert_eq!(ids.len(), 2);
+  }
+
+  #[tokio::test]
+  async fn command_batch_applies_on_one_tick() {
+    let (mut runner, _get_tx, mut post_tx) = test_runner(MockClock::default());
+    for id in ["AAL1234", "SKW5678", "JBU9012"] {
+      runner.game.push_aircraft(
+        Aircraft {
+          id: Intern::from_ref(id),
+          frequency: 118.5,
+          speed: 250.0,
+          altitude: 7000.0,
+          ..Default::default()
+        }
+        .with_synced_targets(),
+      );
+    }
+
+    let command = |id: &str, frequency: f32, task: Task| {
+      CommandWithFreq::new(
+        id.to_string(),
+        frequency,
+        CommandReply::Empty,
+        vec![task],
+      )
+    };
+    let batch = JobReq::send(
+      ArgReqKind::CommandBatch(vec![
+        command("AAL1234", 118.5, Task::Altitude(5000.0)),
+        command("SKW5678", 118.5, Task::Speed(210.0)),
+        command("JBU9012", 118.5, Task::Heading(90.0)),
+        command("JBU9012", 121.9, Task::Altitude(3000.0)),
+        command("DAL3456", 118.5, Task::Altitude(3000.0)),
+      ]),
+      &mut post_tx,
+    );
+    runner.tick();
+
+    let aircraft =
+      |id| runner.game.aircraft_by_id(Intern::from_ref(id)).unwrap();
+    assert_eq!(aircraft("AAL1234").target.altitude, 5000.0);
+    assert_eq!(aircraft("SKW5678").target.speed, 210.0);
+    assert_eq!(aircraft("JBU9012").target.heading, 90.0);
+    assert_eq!(aircraft("JBU9012").target.altitude, 7000.0);
+
+    let Ok(ResKind::CommandBatch(results)) = batch.recv().await else {
+      panic!("expected the results of the batch");
+    };
+    assert_eq!(
+      results,
+      vec![
+        Ok(()),
+        Ok(()),
+        Ok(()),
+        Err(CommandError::WrongFrequency),
+        Err(CommandError::UnknownAircraft),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn spawned_aircraft_are_listed() {
+    let (mut runner, mut get_tx, mut post_tx) =
+      test_runner(MockClock::default());
+    runner.world.airspace.id = Intern::from_ref("KSFO");
+    runner
+      .world
+      .airspace
+      .airports
+      .push(Airport::new(Intern::from_ref("KSFO"), Vec2::ZERO));
+    runner.world.connections.push(Connection {
+      id: Intern::from_ref("KLAX"),
+      state: ConnectionState::Active,
+      pos: Vec2::new(0.0, 200000.0),
+      transition: Vec2::new(0.0, 50000.0),
+    });
+
+    let spawn = JobReq::send(
+      ArgReqKind::SpawnAircraft(SpawnRequest {
+        callsign: Some(Intern::from_ref("AAL1234")),
+        kind: Some(AircraftKind::B737),
+        departing: Some(Intern::from_ref("KLAX")),
+        ..Default::default()
+      }),
+      &mut post_tx,
+    );
+    let unknown = JobReq::send(
+      ArgReqKind::SpawnAircraft(SpawnRequest {
+        departing: Some(Intern::from_ref("KJFK")),
+        ..Default::default()
+      }),
+      &mut post_tx,
+    );
+    // Our airport has no gates to park at.
+    let parked = JobReq::send(
+      ArgReqKind::SpawnAircraft(SpawnRequest {
+        segment: Some(FlightSegment::Parked),
+        ..Default::default()
+      }),
+      &mut post_tx,
+    );
+    runner.tick();
+
+    let list = JobReq::send(TinyReqKind::Aircraft, &mut get_tx);
+    runner.tick();
+
+    let Ok(ResKind::SpawnAircraft(id)) = spawn.recv().await else {
+      panic!("expected the spawned callsign");
+    };
+    assert_eq!(id, Ok(Intern::from_ref("AAL1234")));
+    let Ok(ResKind::SpawnAircraft(unknown)) = unknown.recv().await else {
+      panic!("expected an error");
+    };
+    assert_eq!(
+      unknown,
+      Err(SpawnError::UnknownAirport(Intern::from_ref("KJFK")))
+    );
+    let Ok(ResKind::SpawnAircraft(parked)) = parked.recv().await else {
+      panic!("expected an error");
+    };
+    assert_eq!(parked, Err(SpawnError::NoFreeGate));
+
+    let Ok(ResKind::Aircraft(aircraft)) = list.recv().await else {
+      panic!("expected the aircraft");
+    };
+    assert_eq!(aircraft.len(), 1);
+    assert_eq!(aircraft[0].id, Intern::from_ref("AAL1234"));
+    assert_eq!(aircraft[0].kind, AircraftKind::B737);
+    assert_eq!(aircraft[0].flight_plan.departing, Intern::from_ref("KLAX"));
+    assert_eq!(aircraft[0].flight_plan.arriving, Intern::from_ref("KSFO"));
+  }
+}
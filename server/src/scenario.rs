@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use glam::Vec2;
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+
+use engine::entities::aircraft::{Aircraft, AircraftState, FlightPlan};
+
+/// A scripted set of aircraft that spawn at fixed ticks, in place of the
+/// randomly scheduled flights. Used for training exercises and for
+/// reproducing bugs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+  pub spawns: Vec<ScenarioSpawn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioSpawn {
+  /// The tick to spawn on, counted from when the scenario was loaded.
+  pub tick: usize,
+  pub id: Intern<String>,
+
+  #[serde(default)]
+  pub pos: Vec2,
+  #[serde(default)]
+  pub heading: f32,
+  #[serde(default)]
+  pub speed: f32,
+  #[serde(default)]
+  pub altitude: f32,
+  /// Defaults to the approach frequency of the airspace.
+  #[serde(default)]
+  pub frequency: Option<f32>,
+
+  #[serde(default)]
+  pub state: AircraftState,
+  #[serde(default)]
+  pub flight_plan: FlightPlan,
+  /// Named waypoints to fly, in order, replacing any in `state`.
+  #[serde(default)]
+  pub route: Vec<Intern<String>>,
+}
+
+impl ScenarioSpawn {
+  pub fn to_aircraft(&self, default_frequency: f32) -> Aircraft {
+    Aircraft {
+      id: self.id,
+      pos: self.pos,
+      heading: self.heading,
+      speed: self.speed,
+      altitude: self.altitude,
+      frequency: self.frequency.unwrap_or(default_frequency),
+      state: self.state.clone(),
+      flight_plan: self.flight_plan.clone(),
+      ..Default::default()
+    }
+    .with_synced_targets()
+  }
+}
+
+impl Scenario {
+  pub fn from_path<T>(path: T) -> Result<Self, String>
+  where
+    T: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let scenario = std::fs::read_to_string(path);
+    match scenario {
+      Ok(scenario) => match serde_json::from_str(&scenario) {
+        Ok(scenario) => Ok(scenario),
+        Err(err) => Err(format!("Failed to parse scenario file: {}", err)),
+      },
+      Err(err) => Err(format!("Failed to read scenario file: {}", err)),
+    }
+  }
+
+  /// Removes and returns the spawns that are due on or before `tick`.
+  pub fn take_due(&mut self, tick: usize) -> Vec<ScenarioSpawn> {
+    let (due, later): (Vec<_>, Vec<_>) =
+      self.spawns.drain(..).partition(|s| s.tick <= tick);
+    self.spawns = later;
+
+    due
+  }
+}
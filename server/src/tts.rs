@@ -0,0 +1,197 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use engine::{
+  airline::Airlines, command::CommandWithFreq, spoken_heading, spoken_speed,
+  NATO_DIGITS,
+};
+
+/// How many callouts are kept for each frequency until they are drained,
+/// dropping the oldest ones.
+pub const TTS_QUEUE_CAPACITY: usize = 30;
+
+/// Spells out the numbers in `text` one digit at a time, the way they are
+/// said on the radio, such as "118.5" as "one one eight decimal five".
+pub fn wordify(text: &str) -> String {
+  let chars: Vec<char> = text.chars().collect();
+  let mut spoken = String::new();
+  for (i, c) in chars.iter().enumerate() {
+    let prev = i.checked_sub(1).and_then(|i| chars.get(i));
+    let next = chars.get(i + 1);
+
+    if let Some(digit) = c.to_digit(10) {
+      if prev.is_some_and(|p| p.is_alphabetic()) {
+        spoken.push(' ');
+      }
+      spoken.push_str(NATO_DIGITS[digit as usize]);
+      if next.is_some_and(|n| n.is_alphanumeric()) {
+        spoken.push(' ');
+      }
+    } else if *c == '.'
+      && prev.is_some_and(|p| p.is_ascii_digit())
+      && next.is_some_and(|n| n.is_ascii_digit())
+    {
+      spoken.push_str(" decimal ");
+    } else {
+      spoken.push(*c);
+    }
+  }
+
+  spoken
+}
+
+/// Says the headings and speeds in `text` the way they are said on the
+/// radio, such as "heading 40" as "heading zero four zero" and "210.0 knots"
+/// as "two one zero knots". Other numbers are left to [`wordify`].
+pub fn speak_headings_and_speeds(text: &str) -> String {
+  let words: Vec<&str> = text.split(' ').collect();
+  let bare = |word: &str| word.trim_end_matches([',', '.']).to_lowercase();
+
+  words
+    .iter()
+    .enumerate()
+    .map(|(i, word)| {
+      let number = word.trim_end_matches([',', '.']);
+      let rest = &word[number.len()..];
+      let Ok(value) = number.parse::<f32>() else {
+        return word.to_string();
+      };
+
+      let prev = i.checked_sub(1).map(|i| bare(words[i]));
+      let next = words.get(i + 1).map(|w| bare(w));
+      if prev.as_deref() == Some("heading") {
+        format!("{}{rest}", spoken_heading(value))
+      } else if next.as_deref() == Some("knots") {
+        format!("{}{rest}", spoken_speed(value))
+      } else {
+        word.to_string()
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// A callout, rendered as it should be spoken by a TTS backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsMessage {
+  pub id: String,
+  pub frequency: f32,
+  pub text: String,
+}
+
+impl TtsMessage {
+  pub fn new(value: &CommandWithFreq, airlines: &Airlines) -> Self {
+    Self {
+      id: value.id.clone(),
+      frequency: value.frequency,
+      text: wordify(&speak_headings_and_speeds(&value.to_text(airlines))),
+    }
+  }
+}
+
+/// Callouts waiting to be spoken, in order, for each frequency.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TtsQueue {
+  queues: HashMap<u32, VecDeque<TtsMessage>>,
+}
+
+/// Frequencies to the kHz, so that they can be compared exactly.
+fn frequency_key(frequency: f32) -> u32 {
+  (frequency * 1000.0).round() as u32
+}
+
+impl TtsQueue {
+  pub fn push(&mut self, callout: &CommandWithFreq, airlines: &Airlines) {
+    let message = TtsMessage::new(callout, airlines);
+    if message.text.is_empty() {
+      return;
+    }
+
+    let queue = self
+      .queues
+      .entry(frequency_key(message.frequency))
+      .or_default();
+    if queue.len() == TTS_QUEUE_CAPACITY {
+      queue.pop_front();
+    }
+    queue.push_back(message);
+  }
+
+  /// Removes and returns the callouts waiting on `frequency`, oldest first.
+  pub fn drain(&mut self, frequency: f32) -> Vec<TtsMessage> {
+    self
+      .queues
+      .remove(&frequency_key(frequency))
+      .map(Vec::from)
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use engine::command::CommandReply;
+
+  use super::*;
+
+  fn callout(id: &str, frequency: f32, altitude: f32) -> CommandWithFreq {
+    CommandWithFreq::new(
+      id.to_string(),
+      frequency,
+      CommandReply::LevelOff { altitude },
+      Vec::new(),
+    )
+  }
+
+  #[test]
+  fn numbers_are_spelled_out() {
+    assert_eq!(wordify("118.5"), "one one eight decimal five");
+    assert_eq!(
+      wordify("runway 27L, squawk 0421."),
+      "runway two seven L, squawk zero four two one."
+    );
+    assert_eq!(wordify("gate A1"), "gate A one");
+  }
+
+  #[test]
+  fn headings_and_speeds_are_spoken() {
+    let message = TtsMessage::new(
+      &CommandWithFreq::new(
+        "AAL1234".to_string(),
+        118.5,
+        CommandReply::Blank {
+          text:
+            "Turn left heading 40, reduce speed to 210.0 knots, climb to 5000."
+              .to_string(),
+        },
+        Vec::new(),
+      ),
+      &Airlines::default(),
+    );
+    assert_eq!(
+      message.text,
+      "Turn left heading zero four zero, reduce speed to two one zero knots, climb to five zero zero zero."
+    );
+  }
+
+  #[test]
+  fn callouts_are_queued_per_frequency() {
+    let airlines = Airlines::default();
+    let mut queue = TtsQueue::default();
+    queue.push(&callout("AAL1234", 118.5, 4000.0), &airlines);
+    queue.push(&callout("SKW5678", 121.9, 5000.0), &airlines);
+    queue.push(&callout("JBU9012", 118.5, 6000.0), &airlines);
+
+    let tower: Vec<String> =
+      queue.drain(118.5).into_iter().map(|m| m.id).collect();
+    assert_eq!(tower, vec!["AAL1234", "JBU9012"]);
+
+    let ground = queue.drain(121.9);
+    assert_eq!(ground.len(), 1);
+    assert_eq!(ground[0].id, "SKW5678");
+    assert!(ground[0].text.contains("five thousand feet"));
+
+    // Draining empties the queue.
+    assert!(queue.drain(118.5).is_empty());
+  }
+}